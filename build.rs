@@ -0,0 +1,30 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Embed the git commit and build time so releases are identifiable from the
+/// `/version` command and the build-info metric.
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let build_unix_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    println!("cargo:rustc-env=GIT_HASH={git_hash}");
+    println!("cargo:rustc-env=BUILD_UNIX_TIME={build_unix_time}");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+
+    // The gRPC control service needs protoc; only ask for it when the
+    // feature is actually enabled.
+    if std::env::var("CARGO_FEATURE_GRPC").is_ok() {
+        tonic_build::compile_protos("proto/control.proto")
+            .expect("compiling proto/control.proto (protoc must be installed)");
+        println!("cargo:rerun-if-changed=proto/control.proto");
+    }
+}
@@ -0,0 +1,124 @@
+//! Rotating timestamped backups of state files.
+//!
+//! Before a state file (solutions store, progress cursors) is mutated, a
+//! timestamped copy `<name>.<UTC stamp>.bak` is placed next to it and old
+//! copies beyond the retention count are pruned. `BACKUP_KEEP` controls how
+//! many backups are kept per file (default 5; `0` disables backups).
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+const DEFAULT_KEEP: usize = 5;
+
+/// Retention count from the environment.
+pub fn keep_from_env() -> usize {
+    std::env::var("BACKUP_KEEP")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_KEEP)
+}
+
+/// Take a backup of `path` (if it exists) and prune old ones.
+///
+/// Returns the path of the new backup, or `None` when nothing was backed up
+/// (missing source or retention disabled).
+pub fn backup_file(path: &Path, keep: usize) -> Result<Option<PathBuf>> {
+    if keep == 0 || !path.exists() {
+        return Ok(None);
+    }
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ");
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("backup target has no file name")?;
+    let backup_path = path.with_file_name(format!("{file_name}.{stamp}.bak"));
+    std::fs::copy(path, &backup_path)
+        .with_context(|| format!("copying {} to {}", path.display(), backup_path.display()))?;
+    prune(path, file_name, keep)?;
+    Ok(Some(backup_path))
+}
+
+/// Remove the oldest backups of `path` beyond `keep`.
+fn prune(path: &Path, file_name: &str, keep: usize) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let prefix = format!("{file_name}.");
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(".bak"))
+        })
+        .collect();
+    // Timestamps sort lexicographically, so name order is age order.
+    backups.sort();
+    while backups.len() > keep {
+        let oldest = backups.remove(0);
+        if let Err(err) = std::fs::remove_file(&oldest) {
+            tracing::warn!("failed to prune backup {}: {err}", oldest.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_count(dir: &Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter(|e| {
+                e.as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_str()
+                    .unwrap()
+                    .ends_with(".bak")
+            })
+            .count()
+    }
+
+    #[test]
+    fn missing_source_is_a_noop() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = backup_file(&dir.path().join("absent.log"), 3).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn rotation_keeps_only_n_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("solutions.log");
+        for i in 0..5 {
+            std::fs::write(&target, format!("gen {i}")).unwrap();
+            backup_file(&target, 3).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(backup_count(dir.path()), 3);
+        // The newest backup holds the latest pre-mutation contents.
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.to_str().unwrap().ends_with(".bak"))
+            .collect();
+        names.sort();
+        assert_eq!(
+            std::fs::read_to_string(names.last().unwrap()).unwrap(),
+            "gen 4"
+        );
+    }
+
+    #[test]
+    fn keep_zero_disables_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("solutions.log");
+        std::fs::write(&target, "data").unwrap();
+        assert!(backup_file(&target, 0).unwrap().is_none());
+        assert_eq!(backup_count(dir.path()), 0);
+    }
+}
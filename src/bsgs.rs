@@ -0,0 +1,321 @@
+//! Baby-step giant-step solver for pubkey-known puzzles.
+//!
+//! The deterministic counterpart to the kangaroo solver: with a table of
+//! `m` baby points (`j*G` for `j` in `1..=m`) and one point subtraction
+//! per giant step, BSGS covers `m` keys of the interval per step and is
+//! guaranteed to find the key after `width / m` steps — if the table
+//! fits in memory. `BSGS_MEMORY_MB` caps the table; when the interval is
+//! too wide to sweep in one session the giant walk starts at a random
+//! offset so successive sessions sample different slices, which keeps
+//! the solver useful as a randomized hybrid on the big puzzles.
+//!
+//! Selected with `PUBKEY_SOLVER=bsgs`; solutions flow through the same
+//! `CheckResult` path (journal, Telegram, sweep) as every other strategy.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use bitcoin::secp256k1::{self, Secp256k1};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+
+use crate::checker::{self, CheckResult};
+use crate::keygen;
+use crate::puzzles::Puzzle;
+use crate::state::AppState;
+
+/// Rough bytes per baby-table entry (u64 key + u32 value + map overhead).
+const BYTES_PER_ENTRY: u64 = 48;
+
+/// Baby-table size from the `BSGS_MEMORY_MB` budget (default 256 MB).
+fn table_size_from_env() -> u64 {
+    let mb: u64 = std::env::var("BSGS_MEMORY_MB")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256);
+    (mb.max(1) * 1024 * 1024 / BYTES_PER_ENTRY).max(16)
+}
+
+/// Low 64 bits of a point's X coordinate, the baby-table key.
+fn x_fragment(point: &secp256k1::PublicKey) -> u64 {
+    let serialized = point.serialize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&serialized[25..33]);
+    u64::from_be_bytes(bytes)
+}
+
+/// One BSGS run over a puzzle interval.
+pub struct Solver {
+    secp: Secp256k1<secp256k1::All>,
+    target: secp256k1::PublicKey,
+    /// X fragment of `j*G` → `j`, for `j` in `1..=m`.
+    table: HashMap<u64, u32>,
+    m: u64,
+    /// `-(m*G)`, subtracted on every giant step.
+    giant_stride_neg: secp256k1::PublicKey,
+    /// `Q = target - range_start*G`, the giant walk's origin.
+    origin: secp256k1::PublicKey,
+    /// Current giant position, `Q - giant_index*m*G`.
+    position: secp256k1::PublicKey,
+    giant_index: u64,
+    giants_done: u64,
+    /// Largest giant index that can still land inside the interval.
+    max_giant: u64,
+    range_start: BigUint,
+    /// Set when the key is exactly `range_start` (`Q` is the identity,
+    /// which points can't represent).
+    direct_hit: Option<BigUint>,
+}
+
+impl Solver {
+    /// Build the baby table (`table_size` entries) for a puzzle with a
+    /// known public key, starting the giant walk at a random offset.
+    pub fn build(puzzle: &Puzzle, table_size: u64) -> Result<Self> {
+        let target = match puzzle.pubkey_point {
+            // Loaded puzzles carry the point pre-parsed.
+            Some(bytes) => secp256k1::PublicKey::from_slice(&bytes)
+                .context("cached public key bytes are not a point")?,
+            None => {
+                let pubkey_hex = puzzle
+                    .public_key
+                    .as_deref()
+                    .with_context(|| format!("puzzle #{} has no public key", puzzle.number))?;
+                secp256k1::PublicKey::from_slice(
+                    &hex::decode(pubkey_hex.trim()).context("public_key is not hex")?,
+                )
+                .context("public_key is not a valid secp256k1 point")?
+            }
+        };
+        let (range_start, range_end) = puzzle.range()?;
+        if range_start > range_end {
+            bail!("puzzle #{}: empty key range", puzzle.number);
+        }
+        let width = &range_end - &range_start;
+        let m = BigUint::from(table_size)
+            .min(&width + BigUint::one())
+            .to_u64()
+            .expect("table size fits u64");
+        let secp = Secp256k1::new();
+        let g = keygen::secret_key_from_biguint(&BigUint::one())?.public_key(&secp);
+        let mut table = HashMap::with_capacity(m as usize);
+        let mut baby = g;
+        for j in 1..=m {
+            table.insert(x_fragment(&baby), j as u32);
+            if j < m {
+                baby = baby.combine(&g).context("baby walk landed on infinity")?;
+            }
+        }
+        let start_point = keygen::secret_key_from_biguint(&range_start)?.public_key(&secp);
+        let direct_hit = (target == start_point).then(|| range_start.clone());
+        // When the target *is* range_start*G, Q is the identity and the
+        // combine fails; direct_hit already carries that answer, so the
+        // origin value is never consulted.
+        let origin = target.combine(&start_point.negate(&secp)).unwrap_or(target);
+        let giant_stride_neg = keygen::secret_key_from_biguint(&BigUint::from(m))?
+            .public_key(&secp)
+            .negate(&secp);
+        let max_giant = (&width / m).to_u64().unwrap_or(u64::MAX);
+        // Random starting offset: sessions too short to cover the whole
+        // interval still sample a fresh slice each time.
+        let giant_index = if max_giant > 0 {
+            rand::Rng::gen_range(&mut rand::thread_rng(), 0..=max_giant)
+        } else {
+            0
+        };
+        let position = if giant_index == 0 {
+            origin
+        } else {
+            let offset = BigUint::from(giant_index) * BigUint::from(m);
+            let offset_neg = keygen::secret_key_from_biguint(&offset)?
+                .public_key(&secp)
+                .negate(&secp);
+            origin.combine(&offset_neg).context("giant offset landed on infinity")?
+        };
+        Ok(Self {
+            secp,
+            target,
+            table,
+            m,
+            giant_stride_neg,
+            origin,
+            position,
+            giant_index,
+            giants_done: 0,
+            max_giant,
+            range_start,
+            direct_hit,
+        })
+    }
+
+    /// Keys ruled out per giant step.
+    pub fn stride(&self) -> u64 {
+        self.m
+    }
+
+    /// Whether every giant position has been visited.
+    pub fn exhausted(&self) -> bool {
+        self.giants_done > self.max_giant
+    }
+
+    /// Check the current giant position against the baby table and move
+    /// one stride. Returns the key when a table hit verifies against the
+    /// target point (64-bit fragment clashes are filtered out here).
+    pub fn giant_step(&mut self) -> Result<Option<secp256k1::SecretKey>> {
+        if let Some(value) = self.direct_hit.take() {
+            let key = keygen::secret_key_from_biguint(&value)?;
+            return Ok(Some(key));
+        }
+        if self.exhausted() {
+            return Ok(None);
+        }
+        if let Some(&j) = self.table.get(&x_fragment(&self.position)) {
+            let d = BigUint::from(self.giant_index) * BigUint::from(self.m) + BigUint::from(j);
+            let candidate = &self.range_start + d;
+            if let Ok(key) = keygen::secret_key_from_biguint(&candidate) {
+                if key.public_key(&self.secp) == self.target {
+                    return Ok(Some(key));
+                }
+            }
+        }
+        self.giants_done += 1;
+        if self.giant_index == self.max_giant {
+            // Wrap around to cover the slice before the random start.
+            self.giant_index = 0;
+            self.position = self.origin;
+        } else {
+            self.giant_index += 1;
+            self.position = self
+                .position
+                .combine(&self.giant_stride_neg)
+                .context("giant walk landed on infinity")?;
+        }
+        Ok(None)
+    }
+}
+
+/// Run one fixed-duration BSGS session on a pubkey-known puzzle.
+pub async fn run_session(state: &Arc<AppState>, puzzle: &Puzzle) -> Vec<CheckResult> {
+    let duration = std::time::Duration::from_secs(state.config.scheduler.session_duration_secs);
+    let stop = Arc::new(AtomicBool::new(false));
+    let worker_state = Arc::clone(state);
+    let worker_puzzle = puzzle.clone();
+    let worker_stop = Arc::clone(&stop);
+    let handle =
+        tokio::task::spawn_blocking(move || solver_loop(&worker_state, &worker_puzzle, &worker_stop));
+    tokio::time::sleep(duration).await;
+    stop.store(true, Ordering::Relaxed);
+    match handle.await {
+        Ok(Ok(found)) => found,
+        Ok(Err(err)) => {
+            tracing::warn!("BSGS solver failed: {err:#}");
+            Vec::new()
+        }
+        Err(err) => {
+            tracing::warn!("BSGS solver panicked: {err}");
+            Vec::new()
+        }
+    }
+}
+
+fn solver_loop(state: &AppState, puzzle: &Puzzle, stop: &AtomicBool) -> Result<Vec<CheckResult>> {
+    let mut solver = Solver::build(puzzle, table_size_from_env())?;
+    tracing::info!(
+        "BSGS session on puzzle #{} (table {}, {} giant step(s) to cover the interval)",
+        puzzle.number,
+        solver.stride(),
+        solver.max_giant.saturating_add(1),
+    );
+    let mut found = Vec::new();
+    let mut unreported: u64 = 0;
+    while !stop.load(Ordering::Relaxed) && !solver.exhausted() {
+        if let Some(key) = solver.giant_step()? {
+            match checker::check_private_key_against_puzzle(&key, puzzle)? {
+                Some(result) => {
+                    tracing::info!(
+                        "BSGS: MATCH on puzzle #{} (key {})",
+                        puzzle.number,
+                        checker::redact_secret(result.reveal_private_key())
+                    );
+                    state.stats.record_match();
+                    state.metrics.matches.inc();
+                    found.push(result);
+                    break;
+                }
+                None => tracing::warn!(
+                    "BSGS solved the discrete log for puzzle #{} but the derived address \
+                     does not match; check the puzzle data",
+                    puzzle.number
+                ),
+            }
+        }
+        // Each giant step rules out a full baby-table's worth of keys.
+        unreported += solver.stride();
+        if unreported >= 100_000 {
+            state.stats.record_checked(unreported);
+            state
+                .metrics
+                .keys_checked
+                .with_label_values(&["bsgs"])
+                .inc_by(unreported);
+            unreported = 0;
+        }
+    }
+    state.stats.record_checked(unreported);
+    if solver.exhausted() {
+        tracing::warn!(
+            "BSGS covered the whole interval of puzzle #{} without a hit; \
+             the public key or range is wrong",
+            puzzle.number
+        );
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pubkey_puzzle(secret: u32, bits: u32) -> Puzzle {
+        let secp = Secp256k1::new();
+        let key = keygen::secret_key_from_biguint(&BigUint::from(secret)).unwrap();
+        Puzzle {
+            number: bits,
+            address: checker::derive_bitcoin_address(&key, checker::AddressType::Compressed).unwrap(),
+            range_start: format!("{:x}", 1u64 << (bits - 1)),
+            range_end: format!("{:x}", (1u64 << bits) - 1),
+            reward_btc: 0.0,
+            solved: false,
+            public_key: Some(hex::encode(key.public_key(&secp).serialize())),
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn finds_the_key_with_a_small_table() {
+        let puzzle = pubkey_puzzle(0xabc, 12);
+        let mut solver = Solver::build(&puzzle, 64).unwrap();
+        // 2^11 keys / 64 per step = 32 steps, doubled for the random
+        // start's wrap-around.
+        for _ in 0..=2 * (solver.max_giant + 1) {
+            if let Some(key) = solver.giant_step().unwrap() {
+                assert_eq!(key.secret_bytes()[30..], [0x0a, 0xbc]);
+                return;
+            }
+        }
+        panic!("BSGS missed a 12-bit interval");
+    }
+
+    #[test]
+    fn range_start_itself_is_found() {
+        let puzzle = pubkey_puzzle(0x800, 12);
+        let mut solver = Solver::build(&puzzle, 64).unwrap();
+        let key = solver.giant_step().unwrap().expect("direct hit");
+        assert_eq!(key.secret_bytes()[30..], [0x08, 0x00]);
+    }
+}
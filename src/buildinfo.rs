@@ -0,0 +1,70 @@
+//! Compile-time build identification.
+//!
+//! The git hash and build time are injected by `build.rs`; the feature list
+//! is assembled from `cfg!` so optional backends show up when compiled in.
+//! Surfaced through the `/version` bot command and the
+//! `btclotto_build_info` metric.
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_HASH: &str = env!("GIT_HASH");
+
+/// Build time as seconds since the Unix epoch (0 when unavailable).
+const BUILD_UNIX_TIME: &str = env!("BUILD_UNIX_TIME");
+
+/// Build time formatted as a UTC date-time string.
+pub fn build_date() -> String {
+    BUILD_UNIX_TIME
+        .parse::<i64>()
+        .ok()
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Comma-separated list of compiled-in optional features.
+pub fn features() -> String {
+    let mut features: Vec<&str> = Vec::new();
+    if cfg!(feature = "otel") {
+        features.push("otel");
+    }
+    if cfg!(feature = "gpu") {
+        features.push("gpu");
+    }
+    if cfg!(feature = "cuda") {
+        features.push("cuda");
+    }
+    if cfg!(feature = "grpc") {
+        features.push("grpc");
+    }
+    if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(",")
+    }
+}
+
+/// The `/version` report body.
+pub fn text(uptime_secs: u64) -> String {
+    format!(
+        "{} v{}\nCommit: {}\nBuilt: {}\nFeatures: {}\nUptime: {}s",
+        env!("CARGO_PKG_NAME"),
+        VERSION,
+        GIT_HASH,
+        build_date(),
+        features(),
+        uptime_secs,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_text_includes_identifiers() {
+        let text = text(42);
+        assert!(text.contains(VERSION));
+        assert!(text.contains(GIT_HASH));
+        assert!(text.contains("Uptime: 42s"));
+    }
+}
@@ -0,0 +1,329 @@
+//! Block explorer abstraction.
+//!
+//! One client type covering the hosted Esplora instances (blockstream.info,
+//! mempool.space — they share the API), a self-hosted Esplora, and a
+//! user-run Bitcoin Core node. Used wherever the bot needs on-chain truth:
+//! the `/balance` command today, solved-puzzle detection and pre-sweep
+//! checks as they land. Selected via `CHAIN_BACKEND`.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+
+/// Aggregate on-chain state of one address.
+#[derive(Debug, Clone, Copy)]
+pub struct AddressInfo {
+    /// Lifetime satoshis received.
+    pub funded_sat: u64,
+    /// Lifetime satoshis spent.
+    pub spent_sat: u64,
+    pub tx_count: u64,
+}
+
+impl AddressInfo {
+    pub fn balance_sat(&self) -> u64 {
+        self.funded_sat.saturating_sub(self.spent_sat)
+    }
+}
+
+/// One unspent output of an address.
+#[derive(Debug, Clone)]
+pub struct Utxo {
+    pub txid: String,
+    pub vout: u32,
+    pub value_sat: u64,
+}
+
+/// A configured chain backend.
+pub enum ChainClient {
+    /// Esplora HTTP API (blockstream.info, mempool.space, self-hosted).
+    Esplora { client: reqwest::Client, base: String },
+    /// Bitcoin Core JSON-RPC; balance via `scantxoutset`, so no address
+    /// index is required (but history is unavailable).
+    CoreRpc {
+        client: reqwest::Client,
+        url: String,
+        user: String,
+        password: String,
+    },
+}
+
+/// Extract [`AddressInfo`] from an Esplora `/address/<addr>` response.
+fn parse_esplora_address(value: &Value) -> Result<AddressInfo> {
+    let stats = value
+        .get("chain_stats")
+        .context("Esplora response missing chain_stats")?;
+    let field = |name: &str| -> Result<u64> {
+        stats
+            .get(name)
+            .and_then(Value::as_u64)
+            .with_context(|| format!("Esplora chain_stats missing {name}"))
+    };
+    Ok(AddressInfo {
+        funded_sat: field("funded_txo_sum")?,
+        spent_sat: field("spent_txo_sum")?,
+        tx_count: field("tx_count")?,
+    })
+}
+
+impl ChainClient {
+    /// Build the backend selected by the configuration, if any.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let esplora = |base: &str| {
+            Some(ChainClient::Esplora {
+                client: reqwest::Client::new(),
+                base: base.trim_end_matches('/').to_string(),
+            })
+        };
+        match config.chain_backend.as_deref() {
+            None => None,
+            Some("blockstream") => esplora("https://blockstream.info/api"),
+            Some("mempool") => esplora("https://mempool.space/api"),
+            Some("esplora") => match &config.esplora_url {
+                Some(url) => esplora(url),
+                None => {
+                    tracing::warn!("CHAIN_BACKEND=esplora but ESPLORA_URL is unset");
+                    None
+                }
+            },
+            Some("core") => match (&config.core_rpc_url, &config.core_rpc_user) {
+                (Some(url), Some(user)) => Some(ChainClient::CoreRpc {
+                    client: reqwest::Client::new(),
+                    url: url.clone(),
+                    user: user.clone(),
+                    password: config.core_rpc_password.clone().unwrap_or_default(),
+                }),
+                _ => {
+                    tracing::warn!("CHAIN_BACKEND=core needs CORE_RPC_URL and CORE_RPC_USER");
+                    None
+                }
+            },
+            Some(other) => {
+                tracing::warn!(
+                    "unknown CHAIN_BACKEND {other:?}; expected blockstream, mempool, esplora or core"
+                );
+                None
+            }
+        }
+    }
+
+    /// One Core JSON-RPC call.
+    async fn core_call(&self, method: &str, params: Value) -> Result<Value> {
+        let ChainClient::CoreRpc {
+            client,
+            url,
+            user,
+            password,
+        } = self
+        else {
+            unreachable!("core_call on a non-Core backend");
+        };
+        let response: Value = client
+            .post(url)
+            .basic_auth(user, Some(password))
+            .json(&json!({ "jsonrpc": "1.0", "id": "btclotto", "method": method, "params": params }))
+            .send()
+            .await
+            .context("Core RPC request failed")?
+            .json()
+            .await
+            .context("Core RPC response was not JSON")?;
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            bail!("Core RPC {method} failed: {error}");
+        }
+        response
+            .get("result")
+            .cloned()
+            .with_context(|| format!("Core RPC {method} returned no result"))
+    }
+
+    /// Balance and activity for one address.
+    pub async fn address_info(&self, address: &str) -> Result<AddressInfo> {
+        match self {
+            ChainClient::Esplora { client, base } => {
+                let value: Value = client
+                    .get(format!("{base}/address/{address}"))
+                    .send()
+                    .await
+                    .context("Esplora request failed")?
+                    .error_for_status()
+                    .context("Esplora rejected the request")?
+                    .json()
+                    .await
+                    .context("Esplora response was not JSON")?;
+                parse_esplora_address(&value)
+            }
+            ChainClient::CoreRpc { .. } => {
+                let result = self
+                    .core_call("scantxoutset", json!(["start", [format!("addr({address})")]]))
+                    .await?;
+                let total_btc = result
+                    .get("total_amount")
+                    .and_then(Value::as_f64)
+                    .context("scantxoutset returned no total_amount")?;
+                let utxos = result
+                    .get("unspents")
+                    .and_then(Value::as_array)
+                    .map(|u| u.len() as u64)
+                    .unwrap_or(0);
+                // Core only sees the current UTXO set, not spend history.
+                Ok(AddressInfo {
+                    funded_sat: (total_btc * 1e8).round() as u64,
+                    spent_sat: 0,
+                    tx_count: utxos,
+                })
+            }
+        }
+    }
+
+    /// The current unspent outputs of one address.
+    pub async fn utxos(&self, address: &str) -> Result<Vec<Utxo>> {
+        match self {
+            ChainClient::Esplora { client, base } => {
+                let value: Value = client
+                    .get(format!("{base}/address/{address}/utxo"))
+                    .send()
+                    .await
+                    .context("Esplora request failed")?
+                    .error_for_status()
+                    .context("Esplora rejected the request")?
+                    .json()
+                    .await
+                    .context("Esplora response was not JSON")?;
+                value
+                    .as_array()
+                    .context("Esplora utxo response was not an array")?
+                    .iter()
+                    .map(|utxo| {
+                        Ok(Utxo {
+                            txid: utxo
+                                .get("txid")
+                                .and_then(Value::as_str)
+                                .context("utxo missing txid")?
+                                .to_string(),
+                            vout: utxo
+                                .get("vout")
+                                .and_then(Value::as_u64)
+                                .context("utxo missing vout")? as u32,
+                            value_sat: utxo
+                                .get("value")
+                                .and_then(Value::as_u64)
+                                .context("utxo missing value")?,
+                        })
+                    })
+                    .collect()
+            }
+            ChainClient::CoreRpc { .. } => {
+                let result = self
+                    .core_call("scantxoutset", json!(["start", [format!("addr({address})")]]))
+                    .await?;
+                result
+                    .get("unspents")
+                    .and_then(Value::as_array)
+                    .context("scantxoutset returned no unspents")?
+                    .iter()
+                    .map(|utxo| {
+                        Ok(Utxo {
+                            txid: utxo
+                                .get("txid")
+                                .and_then(Value::as_str)
+                                .context("unspent missing txid")?
+                                .to_string(),
+                            vout: utxo
+                                .get("vout")
+                                .and_then(Value::as_u64)
+                                .context("unspent missing vout")? as u32,
+                            value_sat: (utxo
+                                .get("amount")
+                                .and_then(Value::as_f64)
+                                .context("unspent missing amount")?
+                                * 1e8)
+                                .round() as u64,
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Broadcast a raw transaction, returning its txid.
+    pub async fn broadcast(&self, tx_hex: &str) -> Result<String> {
+        match self {
+            ChainClient::Esplora { client, base } => {
+                let response = client
+                    .post(format!("{base}/tx"))
+                    .body(tx_hex.to_string())
+                    .send()
+                    .await
+                    .context("Esplora broadcast request failed")?;
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                if !status.is_success() {
+                    bail!("Esplora rejected the transaction: {status} {body}");
+                }
+                Ok(body.trim().to_string())
+            }
+            ChainClient::CoreRpc { .. } => {
+                let result = self
+                    .core_call("sendrawtransaction", json!([tx_hex]))
+                    .await?;
+                result
+                    .as_str()
+                    .map(str::to_string)
+                    .context("sendrawtransaction returned no txid")
+            }
+        }
+    }
+
+    /// Recent transaction ids touching the address (Esplora only).
+    pub async fn tx_history(&self, address: &str) -> Result<Vec<String>> {
+        match self {
+            ChainClient::Esplora { client, base } => {
+                let value: Value = client
+                    .get(format!("{base}/address/{address}/txs"))
+                    .send()
+                    .await
+                    .context("Esplora request failed")?
+                    .error_for_status()
+                    .context("Esplora rejected the request")?
+                    .json()
+                    .await
+                    .context("Esplora response was not JSON")?;
+                Ok(value
+                    .as_array()
+                    .map(|txs| {
+                        txs.iter()
+                            .filter_map(|tx| tx.get("txid").and_then(Value::as_str))
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default())
+            }
+            ChainClient::CoreRpc { .. } => {
+                bail!("transaction history needs an Esplora backend (blockstream/mempool/esplora)")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_esplora_address_stats() {
+        let value = serde_json::json!({
+            "address": "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+            "chain_stats": { "funded_txo_sum": 1500, "spent_txo_sum": 400, "tx_count": 7 },
+        });
+        let info = parse_esplora_address(&value).unwrap();
+        assert_eq!(info.balance_sat(), 1100);
+        assert_eq!(info.tx_count, 7);
+    }
+
+    #[test]
+    fn rejects_response_without_stats() {
+        assert!(parse_esplora_address(&serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,1421 @@
+//! Deriving Bitcoin addresses from candidate keys and checking them against
+//! puzzle targets.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use bitcoin::{Address, Network, PublicKey};
+
+use crate::puzzles::{Puzzle, Target};
+
+/// Which encoding of the public key produced the matching address:
+/// P2PKH over the compressed or uncompressed serialization, native
+/// segwit P2WPKH, or taproot P2TR (both always the compressed key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressType {
+    Compressed,
+    Uncompressed,
+    P2wpkh,
+    P2tr,
+}
+
+impl std::fmt::Display for AddressType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressType::Compressed => write!(f, "compressed"),
+            AddressType::Uncompressed => write!(f, "uncompressed"),
+            AddressType::P2wpkh => write!(f, "p2wpkh"),
+            AddressType::P2tr => write!(f, "p2tr"),
+        }
+    }
+}
+
+/// A successful match of a candidate key against a puzzle address.
+///
+/// Serialization keeps the full key (the journal and solution log must be
+/// able to recover it); the `Debug` impl redacts it so key material cannot
+/// reach log output through `{:?}` formatting, and every copy wipes its
+/// strings from memory when dropped. The key itself is private: code that
+/// needs it must go through [`CheckResult::reveal_private_key`], which
+/// keeps every exposure point greppable.
+#[derive(Clone, serde::Serialize, serde::Deserialize, zeroize::Zeroize, zeroize::ZeroizeOnDrop)]
+pub struct CheckResult {
+    #[zeroize(skip)]
+    pub puzzle_number: u32,
+    pub address: String,
+    private_key_hex: String,
+    #[zeroize(skip)]
+    pub address_type: AddressType,
+    /// Compressed public key, hex encoded. Empty only when the stored key
+    /// hex is not a well-formed secret key (a hand-edited journal or
+    /// solution-store entry). Redacted in `Debug` output: for an unswept
+    /// small-range puzzle the public key alone lets anyone re-solve it.
+    #[serde(default)]
+    pub public_key_hex: String,
+    /// When the match was found, in UTC. Entries journaled before this
+    /// field existed deserialize as the moment they are replayed.
+    #[zeroize(skip)]
+    #[serde(default = "chrono::Utc::now")]
+    pub found_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    wif_compressed: String,
+    #[serde(default)]
+    wif_uncompressed: String,
+    /// Network the address belongs to (the `NETWORK` setting, "bitcoin"
+    /// unless overridden), recorded so exported entries are
+    /// self-describing.
+    #[serde(default = "default_network")]
+    #[zeroize(skip)]
+    pub network: String,
+    /// Set when the hit is a vanity-prefix address rather than a puzzle or
+    /// target-set entry, so reporting can tell a cosmetic find from a
+    /// funded one.
+    #[serde(default)]
+    #[zeroize(skip)]
+    pub vanity: bool,
+}
+
+fn default_network() -> String {
+    network().to_string()
+}
+
+impl CheckResult {
+    /// Assemble a result around an already-hex-encoded key, deriving the
+    /// enrichment fields (public key, WIF encodings, timestamp) once here
+    /// so downstream consumers never touch the private key themselves.
+    pub fn new(
+        puzzle_number: u32,
+        address: String,
+        private_key_hex: String,
+        address_type: AddressType,
+    ) -> Self {
+        use zeroize::Zeroize;
+        let secret = hex::decode(&private_key_hex).ok().and_then(|mut bytes| {
+            let secret = SecretKey::from_slice(&bytes).ok();
+            bytes.zeroize();
+            secret
+        });
+        let public_key_hex = secret
+            .map(|s| hex::encode(public_key_for(&s).serialize()))
+            .unwrap_or_default();
+        let wif = |compressed| {
+            secret
+                .map(|inner| {
+                    bitcoin::PrivateKey {
+                        compressed,
+                        network: network().into(),
+                        inner,
+                    }
+                    .to_wif()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            puzzle_number,
+            address,
+            private_key_hex,
+            address_type,
+            public_key_hex,
+            found_at: chrono::Utc::now(),
+            wif_compressed: wif(true),
+            wif_uncompressed: wif(false),
+            network: default_network(),
+            vanity: false,
+        }
+    }
+
+    /// The solved key, hex encoded. Deliberately the only way out of the
+    /// container — call sites mark where key material leaves it (journal,
+    /// solution log, sweep, webhook, notifications).
+    pub fn reveal_private_key(&self) -> &str {
+        &self.private_key_hex
+    }
+
+    /// The human-readable claim the ownership proof signs.
+    pub fn proof_message(&self) -> String {
+        format!("Puzzle #{} solved by {}", self.puzzle_number, self.address)
+    }
+
+    /// A Bitcoin message signature (base64) over [`proof_message`], so the
+    /// solve can be claimed publicly without pasting the key anywhere.
+    /// Signed with the compressed-key recovery header unless the match was
+    /// on the uncompressed serialization; verifiers recover the public key
+    /// and check it against the address. `None` for hand-edited entries
+    /// whose key hex is malformed.
+    ///
+    /// [`proof_message`]: CheckResult::proof_message
+    pub fn proof_signature(&self) -> Option<String> {
+        use zeroize::Zeroize;
+        let mut bytes = hex::decode(&self.private_key_hex).ok()?;
+        let secret = SecretKey::from_slice(&bytes).ok();
+        bytes.zeroize();
+        let secret = secret?;
+        let hash = bitcoin::sign_message::signed_msg_hash(&self.proof_message());
+        let message = bitcoin::secp256k1::Message::from_digest(*hash.as_ref());
+        let signature = with_secp(|secp| secp.sign_ecdsa_recoverable(&message, &secret));
+        let signature = bitcoin::sign_message::MessageSignature {
+            signature,
+            compressed: self.address_type != AddressType::Uncompressed,
+        };
+        use base64::Engine;
+        Some(base64::engine::general_purpose::STANDARD.encode(signature.serialize()))
+    }
+
+    /// The solved key in wallet-import format. Stored at construction;
+    /// re-derived from the key hex only for entries journaled before the
+    /// WIF fields existed. `None` when the stored hex is not a well-formed
+    /// secret key, which can only happen for hand-edited journal or
+    /// solution-store entries.
+    pub fn wif(&self, compressed: bool) -> Option<zeroize::Zeroizing<String>> {
+        use zeroize::Zeroize;
+        let stored = if compressed {
+            &self.wif_compressed
+        } else {
+            &self.wif_uncompressed
+        };
+        if !stored.is_empty() {
+            return Some(zeroize::Zeroizing::new(stored.clone()));
+        }
+        let mut bytes = hex::decode(&self.private_key_hex).ok()?;
+        let secret = SecretKey::from_slice(&bytes).ok();
+        bytes.zeroize();
+        Some(zeroize::Zeroizing::new(
+            bitcoin::PrivateKey {
+                compressed,
+                network: network().into(),
+                inner: secret?,
+            }
+            .to_wif(),
+        ))
+    }
+}
+
+impl std::fmt::Debug for CheckResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckResult")
+            .field("puzzle_number", &self.puzzle_number)
+            .field("address", &self.address)
+            .field("private_key_hex", &redact_secret(&self.private_key_hex))
+            .field("address_type", &self.address_type)
+            .field("public_key_hex", &redact_secret(&self.public_key_hex))
+            .field("found_at", &self.found_at)
+            .field("wif_compressed", &redact_secret(&self.wif_compressed))
+            .field("wif_uncompressed", &redact_secret(&self.wif_uncompressed))
+            .field("network", &self.network)
+            .field("vanity", &self.vanity)
+            .finish()
+    }
+}
+
+/// Whether the operator opted in to full key material in logs.
+fn log_secrets() -> bool {
+    static FLAG: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *FLAG.get_or_init(|| {
+        std::env::var("LOG_SECRETS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false)
+    })
+}
+
+/// The network addresses are derived for (`NETWORK`): mainnet unless set
+/// to `testnet`, `regtest` or `signet`, which lets end-to-end runs target
+/// a puzzle file whose addresses the operator controls.
+pub fn network() -> Network {
+    static NETWORK: std::sync::OnceLock<Network> = std::sync::OnceLock::new();
+    *NETWORK.get_or_init(|| match std::env::var("NETWORK").ok().as_deref() {
+        None | Some("") | Some("mainnet") | Some("bitcoin") => Network::Bitcoin,
+        Some("testnet") => Network::Testnet,
+        Some("regtest") => Network::Regtest,
+        Some("signet") => Network::Signet,
+        Some(other) => {
+            tracing::warn!("unknown NETWORK {other:?}; staying on mainnet");
+            Network::Bitcoin
+        }
+    })
+}
+
+/// Render key material for log output: the full value only when
+/// `LOG_SECRETS=true`, otherwise a short SHA-256 fingerprint that
+/// identifies the key without revealing any of its bits.
+pub fn redact_secret(key_hex: &str) -> String {
+    redact(key_hex, log_secrets())
+}
+
+fn redact(key_hex: &str, reveal: bool) -> String {
+    if reveal {
+        return key_hex.to_string();
+    }
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(key_hex.as_bytes());
+    format!("sha256:{}…", hex::encode(&digest[..4]))
+}
+
+/// Running totals for the whole process, shared between worker threads.
+#[derive(Debug, Default)]
+pub struct CheckStats {
+    pub keys_checked: AtomicU64,
+    pub matches_found: AtomicU64,
+    pub sessions_run: AtomicU64,
+    /// Keys checked by a GPU worker (OpenCL or CUDA), counted separately
+    /// so GPU and CPU throughput can be told apart.
+    #[cfg(any(feature = "gpu", feature = "cuda"))]
+    pub gpu_keys_checked: AtomicU64,
+    /// Lifetime keys checked per worker thread id.
+    thread_keys: std::sync::Mutex<Vec<u64>>,
+    /// Lifetime keys checked and last activity per puzzle number.
+    puzzle_keys: std::sync::Mutex<std::collections::HashMap<u32, PuzzleStat>>,
+}
+
+/// Per-puzzle slice of the running totals.
+#[derive(Debug, Clone, Copy)]
+pub struct PuzzleStat {
+    pub keys_checked: u64,
+    pub last_checked: chrono::DateTime<chrono::Utc>,
+}
+
+impl CheckStats {
+    pub fn record_checked(&self, n: u64) {
+        self.keys_checked.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Attribute `n` checked keys to one worker thread.
+    pub fn record_thread_checked(&self, thread_id: usize, n: u64) {
+        let mut threads = self.thread_keys.lock().unwrap();
+        if threads.len() <= thread_id {
+            threads.resize(thread_id + 1, 0);
+        }
+        threads[thread_id] += n;
+    }
+
+    /// Lifetime keys checked per thread id.
+    pub fn per_thread_totals(&self) -> Vec<u64> {
+        self.thread_keys.lock().unwrap().clone()
+    }
+
+    /// Attribute `n` checked keys to one puzzle.
+    pub fn record_puzzle_checked(&self, number: u32, n: u64) {
+        let now = chrono::Utc::now();
+        let mut puzzles = self.puzzle_keys.lock().unwrap();
+        let stat = puzzles.entry(number).or_insert(PuzzleStat {
+            keys_checked: 0,
+            last_checked: now,
+        });
+        stat.keys_checked += n;
+        stat.last_checked = now;
+    }
+
+    /// Lifetime keys checked per puzzle, ordered by puzzle number.
+    pub fn per_puzzle_totals(&self) -> Vec<(u32, PuzzleStat)> {
+        let mut totals: Vec<_> = self
+            .puzzle_keys
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&number, &stat)| (number, stat))
+            .collect();
+        totals.sort_unstable_by_key(|(number, _)| *number);
+        totals
+    }
+
+    #[cfg(any(feature = "gpu", feature = "cuda"))]
+    pub fn record_gpu_checked(&self, n: u64) {
+        self.keys_checked.fetch_add(n, Ordering::Relaxed);
+        self.gpu_keys_checked.fetch_add(n, Ordering::Relaxed);
+    }
+
+    #[cfg(any(feature = "gpu", feature = "cuda"))]
+    pub fn total_gpu_checked(&self) -> u64 {
+        self.gpu_keys_checked.load(Ordering::Relaxed)
+    }
+
+    pub fn record_match(&self) {
+        self.matches_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_session(&self) {
+        self.sessions_run.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Seed the counters from a restored snapshot.
+    pub fn restore(&self, keys_checked: u64, matches_found: u64, sessions_run: u64) {
+        self.keys_checked.store(keys_checked, Ordering::Relaxed);
+        self.matches_found.store(matches_found, Ordering::Relaxed);
+        self.sessions_run.store(sessions_run, Ordering::Relaxed);
+    }
+
+    pub fn total_checked(&self) -> u64 {
+        self.keys_checked.load(Ordering::Relaxed)
+    }
+
+    pub fn total_matches(&self) -> u64 {
+        self.matches_found.load(Ordering::Relaxed)
+    }
+
+    pub fn total_sessions(&self) -> u64 {
+        self.sessions_run.load(Ordering::Relaxed)
+    }
+}
+
+/// Hex-encode a secret key, wiping the intermediate byte copy so the only
+/// lasting plaintext lives inside the zeroize-on-drop [`CheckResult`].
+fn secret_to_hex(secret_key: &SecretKey) -> String {
+    use zeroize::Zeroize;
+    let mut bytes = secret_key.secret_bytes();
+    let hex = hex::encode(bytes);
+    bytes.zeroize();
+    hex
+}
+
+/// Render the address of the given type for an already-derived public key.
+fn address_from_public_key(
+    inner: &bitcoin::secp256k1::PublicKey,
+    address_type: AddressType,
+) -> String {
+    match address_type {
+        AddressType::Compressed => {
+            Address::p2pkh(PublicKey::new(*inner), network()).to_string()
+        }
+        AddressType::Uncompressed => {
+            Address::p2pkh(PublicKey::new_uncompressed(*inner), network()).to_string()
+        }
+        AddressType::P2wpkh => {
+            Address::p2wpkh(&bitcoin::CompressedPublicKey(*inner), network()).to_string()
+        }
+        AddressType::P2tr => with_secp(|secp| {
+            Address::p2tr(secp, inner.x_only_public_key().0, None, network()).to_string()
+        }),
+    }
+}
+
+/// Run `f` with this thread's shared secp256k1 context. `Secp256k1::new`
+/// allocates and precomputes multiplication tables; paying that once per
+/// thread instead of once per key is what keeps key derivation, the
+/// taproot tweak and the incremental walker off the allocator entirely.
+pub(crate) fn with_secp<T>(f: impl FnOnce(&Secp256k1<bitcoin::secp256k1::All>) -> T) -> T {
+    thread_local! {
+        static SECP: Secp256k1<bitcoin::secp256k1::All> = Secp256k1::new();
+    }
+    SECP.with(|secp| f(secp))
+}
+
+/// Derive the public key for a secret key on the shared per-thread context.
+pub fn public_key_for(secret_key: &SecretKey) -> bitcoin::secp256k1::PublicKey {
+    with_secp(|secp| secret_key.public_key(secp))
+}
+
+/// The BIP341 key-path output key for `inner` as internal key with no
+/// script tree — the 32 bytes a `bc1p…` address commits to.
+fn taproot_output_key(inner: &bitcoin::secp256k1::PublicKey) -> [u8; 32] {
+    use bitcoin::key::TapTweak;
+    with_secp(|secp| {
+        inner
+            .x_only_public_key()
+            .0
+            .tap_tweak(secp, None)
+            .0
+            .serialize()
+    })
+}
+
+/// Derive the address of the given type for a secret key.
+pub fn derive_bitcoin_address(secret_key: &SecretKey, address_type: AddressType) -> Result<String> {
+    Ok(address_from_public_key(
+        &public_key_for(secret_key),
+        address_type,
+    ))
+}
+
+/// Known key → address pairs from the first historically solved puzzles.
+const SELF_TEST_VECTORS: &[(u64, &str)] = &[
+    (0x1, "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"),
+    (0x3, "1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb"),
+    (0x7, "19ZewH8Kk1PDbSNdJ97FP4EiCjTRaZMZQA"),
+    (0x8, "1EhqbyUMvvs7BfL8goY6qcPbD6YKfPqb7e"),
+    (0x15, "1E6NuFjCi27W5zoXg8TRdcSRq84zJeBW3k"),
+];
+
+/// Derive the known keys of historically solved puzzles and compare the
+/// results against their recorded addresses, exercising both derivation
+/// and the matching path. Run once at startup: a mismatch means the
+/// key-to-address pipeline is silently broken — a dependency upgrade, a
+/// bad build — and every key checked afterwards would be wasted, so the
+/// caller must refuse to start.
+pub fn self_test() -> Result<()> {
+    // The literal vectors are mainnet addresses; off mainnet the run still
+    // proves the derive → decode → match round trip on its own output.
+    let mainnet = network() == Network::Bitcoin;
+    for &(key, expected) in SELF_TEST_VECTORS {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&key.to_be_bytes());
+        let secret = SecretKey::from_slice(&bytes).context("self-test key is invalid")?;
+        let derived = derive_bitcoin_address(&secret, AddressType::Compressed)?;
+        anyhow::ensure!(
+            !mainnet || derived == expected,
+            "key {key:#x} derived {derived}, expected {expected}"
+        );
+        let puzzle = Puzzle {
+            number: 64 - key.leading_zeros(),
+            address: derived.clone(),
+            range_start: format!("{key:x}"),
+            range_end: format!("{key:x}"),
+            reward_btc: 0.0,
+            solved: true,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: Some(Target::decode(&derived)?),
+        };
+        anyhow::ensure!(
+            check_private_key_against_puzzle(&secret, &puzzle)?.is_some(),
+            "key {key:#x} failed to match its own address {derived}"
+        );
+    }
+    Ok(())
+}
+
+/// Check one candidate key against one puzzle's target address.
+///
+/// Both the compressed and uncompressed address are derived and compared.
+pub fn check_private_key_against_puzzle(
+    secret_key: &SecretKey,
+    puzzle: &Puzzle,
+) -> Result<Option<CheckResult>> {
+    check_public_key_against_puzzle(&public_key_for(secret_key), secret_key, puzzle)
+}
+
+/// Like [`check_private_key_against_puzzle`] with the public key already in
+/// hand, skipping the scalar multiplication — the fast path for sequential
+/// scans that walk the public key forward incrementally.
+pub fn check_public_key_against_puzzle(
+    inner: &bitcoin::secp256k1::PublicKey,
+    secret_key: &SecretKey,
+    puzzle: &Puzzle,
+) -> Result<Option<CheckResult>> {
+    match match_point(inner, puzzle) {
+        Some((address, address_type)) => Ok(Some(CheckResult::new(
+            puzzle.number,
+            address,
+            secret_to_hex(secret_key),
+            address_type,
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// The address-comparison core shared by the direct and endomorphism
+/// checkers: which serialization of `inner`, if either, pays the puzzle's
+/// target address.
+///
+/// The comparison runs on raw hash160 digests — Base58Check-encoding two
+/// address strings per key was the hot path's single biggest cost — so
+/// the winning address is only rendered on a match.
+fn match_point(
+    inner: &bitcoin::secp256k1::PublicKey,
+    puzzle: &Puzzle,
+) -> Option<(String, AddressType)> {
+    // An exposed public key decides membership by itself: 33 bytes of
+    // point comparison, no hashing. A mismatch can return immediately;
+    // a hit falls through so the address renders per the target kind.
+    if let Some(known) = &puzzle.pubkey_point {
+        if inner.serialize() != *known {
+            return None;
+        }
+    }
+    // Loaded puzzles carry the target pre-decoded; fall back to the
+    // thread-local cache for puzzles built programmatically (CLI scans).
+    let target = match puzzle.target {
+        Some(target) => target,
+        None => cached_target(&puzzle.address)?,
+    };
+    match target {
+        Target::P2pkh { hash160: digest } => {
+            if hash160(&inner.serialize()) == digest {
+                return Some((
+                    address_from_public_key(inner, AddressType::Compressed),
+                    AddressType::Compressed,
+                ));
+            }
+            if !(puzzle.compressed_only || compressed_only())
+                && hash160(&inner.serialize_uncompressed()) == digest
+            {
+                return Some((
+                    address_from_public_key(inner, AddressType::Uncompressed),
+                    AddressType::Uncompressed,
+                ));
+            }
+        }
+        // P2WPKH commits to the compressed serialization only.
+        Target::P2wpkh { hash160: digest } => {
+            if hash160(&inner.serialize()) == digest {
+                return Some((
+                    address_from_public_key(inner, AddressType::P2wpkh),
+                    AddressType::P2wpkh,
+                ));
+            }
+        }
+        // P2TR commits to the candidate as BIP341 internal key.
+        Target::P2tr { output_key } => {
+            if taproot_output_key(inner) == output_key {
+                return Some((
+                    address_from_public_key(inner, AddressType::P2tr),
+                    AddressType::P2tr,
+                ));
+            }
+        }
+    }
+    None
+}
+
+/// Whether `COMPRESSED_ONLY=true` skips the uncompressed serialization
+/// for every puzzle, halving the hashing per P2PKH candidate. The
+/// per-puzzle `compressed_only` field does the same for a single entry.
+fn compressed_only() -> bool {
+    static FLAG: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *FLAG.get_or_init(|| {
+        std::env::var("COMPRESSED_ONLY")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false)
+    })
+}
+
+/// `ripemd160(sha256(bytes))`, the digest a P2PKH address commits to.
+fn hash160(bytes: &[u8]) -> [u8; 20] {
+    use bitcoin::hashes::Hash;
+    bitcoin::hashes::hash160::Hash::hash(bytes).to_byte_array()
+}
+
+/// [`Target::decode`] behind a one-entry thread-local cache: a worker
+/// thread checks millions of keys against the same target address, so
+/// the decode happens once per session rather than once per key. `None`
+/// for an undecodable address — such a target can never equal a derived
+/// address either, so the checks simply always miss.
+fn cached_target(address: &str) -> Option<Target> {
+    thread_local! {
+        static LAST: std::cell::RefCell<Option<(String, Option<Target>)>> =
+            const { std::cell::RefCell::new(None) };
+    }
+    LAST.with(|last| {
+        let mut last = last.borrow_mut();
+        if let Some((cached, target)) = &*last {
+            if cached == address {
+                return *target;
+            }
+        }
+        let target = Target::decode(address).ok();
+        *last = Some((address.to_string(), target));
+        target
+    })
+}
+
+/// A secondary list of targets searched alongside the puzzle list
+/// (`TARGET_SET_FILE`): one address per line, `#` comments allowed. The
+/// decoded hash160s live in one sorted, deduplicated array, so checking a
+/// candidate costs two binary searches — cheap enough for every derived
+/// key even with millions of funded addresses loaded.
+///
+/// A hit carries puzzle number 0, the "outside any puzzle" sentinel, and
+/// flows through the same journal and notification pipeline as a solve.
+pub struct TargetSet {
+    digests: Vec<[u8; 20]>,
+}
+
+impl TargetSet {
+    /// Load the set named by `TARGET_SET_FILE`, if any; an unreadable file
+    /// is reported and ignored rather than blocking startup.
+    pub fn from_config(config: &crate::config::Config) -> Option<Self> {
+        let path = config.target_set_file.as_ref()?;
+        match Self::load(path) {
+            Ok(set) => {
+                tracing::info!(
+                    "loaded {} extra target(s) from {}",
+                    set.len(),
+                    path.display()
+                );
+                Some(set)
+            }
+            Err(err) => {
+                tracing::warn!("ignoring {}: {err:#}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Parse one address per line, skipping blanks and `#` comments.
+    /// Undecodable lines — and P2TR entries, since the set compares bare
+    /// hash160s — are counted and reported once rather than failing the
+    /// whole file; these lists are usually scraped, not curated.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("reading target set {}", path.display()))?;
+        let mut digests = Vec::new();
+        let mut skipped = 0u64;
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match Target::decode(line) {
+                Ok(Target::P2pkh { hash160 }) | Ok(Target::P2wpkh { hash160 }) => {
+                    digests.push(hash160)
+                }
+                _ => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!("{}: skipped {skipped} unusable target line(s)", path.display());
+        }
+        digests.sort_unstable();
+        digests.dedup();
+        Ok(Self { digests })
+    }
+
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// Membership test for one digest, a binary search over the array.
+    fn contains(&self, digest: &[u8; 20]) -> bool {
+        self.digests.binary_search(digest).is_ok()
+    }
+
+    /// Check both serializations of a candidate against the set. The set
+    /// stores bare digests, so a hit is rendered as the P2PKH address of
+    /// the matching serialization; the key spends the funds either way.
+    pub fn check(
+        &self,
+        inner: &bitcoin::secp256k1::PublicKey,
+        secret_key: &SecretKey,
+    ) -> Option<CheckResult> {
+        if self.is_empty() {
+            return None;
+        }
+        let address_type = if self.contains(&hash160(&inner.serialize())) {
+            AddressType::Compressed
+        } else if self.contains(&hash160(&inner.serialize_uncompressed())) {
+            AddressType::Uncompressed
+        } else {
+            return None;
+        };
+        Some(CheckResult::new(
+            0,
+            address_from_public_key(inner, address_type),
+            secret_to_hex(secret_key),
+            address_type,
+        ))
+    }
+}
+
+/// Vanity hunt (`VANITY_PREFIXES`): address prefixes matched against the
+/// addresses every candidate key already yields, in the configured
+/// network's encodings. A prefix starting with the network's P2PKH
+/// leading character (`1` on mainnet, `m`/`n` elsewhere) is tested
+/// against the compressed P2PKH address, one starting with the bech32
+/// HRP plus `p` (`bc1p…`, `tb1p…`, `bcrt1p…`) against taproot and any
+/// other HRP-led prefix against P2WPKH, so only encodings some prefix
+/// could still match are rendered per candidate. Base58 and bech32 are
+/// case sensitive and the comparison is too.
+pub struct VanitySet {
+    p2pkh: Vec<String>,
+    p2wpkh: Vec<String>,
+    p2tr: Vec<String>,
+}
+
+/// The leading characters vanity prefixes are classified by on `network`:
+/// the P2PKH first characters and the bech32 prefix (HRP plus separator).
+fn vanity_leads(network: Network) -> (&'static [char], &'static str) {
+    match network {
+        Network::Testnet | Network::Signet => (&['m', 'n'], "tb1"),
+        Network::Regtest => (&['m', 'n'], "bcrt1"),
+        _ => (&['1'], "bc1"),
+    }
+}
+
+impl VanitySet {
+    /// Build the matcher from `VANITY_PREFIXES`, if set; prefixes that
+    /// cannot start a searchable address kind on the configured network
+    /// are reported and ignored rather than blocking startup.
+    pub fn from_config(config: &crate::config::Config) -> Option<Self> {
+        let mut set = Self {
+            p2pkh: Vec::new(),
+            p2wpkh: Vec::new(),
+            p2tr: Vec::new(),
+        };
+        let (p2pkh_leads, bech32) = vanity_leads(network());
+        let taproot = format!("{bech32}p");
+        for prefix in &config.vanity_prefixes {
+            if prefix.starts_with(&taproot) {
+                set.p2tr.push(prefix.clone());
+            } else if prefix.starts_with(bech32) {
+                set.p2wpkh.push(prefix.clone());
+            } else if prefix.chars().next().is_some_and(|c| p2pkh_leads.contains(&c)) {
+                set.p2pkh.push(prefix.clone());
+            } else {
+                let leads = p2pkh_leads
+                    .iter()
+                    .map(char::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                tracing::warn!(
+                    "ignoring vanity prefix {prefix:?}: searchable {} addresses start \
+                     with {leads} (p2pkh), {bech32}q (p2wpkh) or {taproot} (p2tr)",
+                    network()
+                );
+            }
+        }
+        let total = set.p2pkh.len() + set.p2wpkh.len() + set.p2tr.len();
+        if total == 0 {
+            return None;
+        }
+        tracing::info!("hunting {total} vanity prefix(es) alongside the puzzle search");
+        Some(set)
+    }
+
+    /// Test the candidate's addresses against the prefixes. A hit flows
+    /// through the normal solve pipeline with puzzle number 0 and the
+    /// vanity flag set.
+    pub fn check(
+        &self,
+        inner: &bitcoin::secp256k1::PublicKey,
+        secret_key: &SecretKey,
+    ) -> Option<CheckResult> {
+        let groups = [
+            (&self.p2pkh, AddressType::Compressed),
+            (&self.p2wpkh, AddressType::P2wpkh),
+            (&self.p2tr, AddressType::P2tr),
+        ];
+        for (prefixes, address_type) in groups {
+            if prefixes.is_empty() {
+                continue;
+            }
+            let address = address_from_public_key(inner, address_type);
+            if prefixes.iter().any(|p| address.starts_with(p.as_str())) {
+                let mut result =
+                    CheckResult::new(0, address, secret_to_hex(secret_key), address_type);
+                result.vanity = true;
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+/// The secp256k1 endomorphism: `λ·(x, y) = (β·x, y)` for the cube roots of
+/// unity λ (mod the group order) and β (mod the field prime).
+///
+/// One scalar multiplication `P = k·G` therefore hands over the points for
+/// five more keys — λk and λ²k via a field multiplication on the x
+/// coordinate, and the negations n−k, n−λk, n−λ²k by flipping y — each for
+/// a tiny fraction of the multiplication's cost. The related keys land
+/// anywhere in `[1, n)`, not inside the puzzle's range, so this widens the
+/// net across the whole curve rather than accelerating one range; it is
+/// opt-in via `ENDOMORPHISM=true`.
+pub struct Endomorphism {
+    field_p: num_bigint::BigUint,
+    beta: num_bigint::BigUint,
+    beta2: num_bigint::BigUint,
+    lambda: bitcoin::secp256k1::Scalar,
+    lambda2: bitcoin::secp256k1::Scalar,
+}
+
+impl Endomorphism {
+    pub fn new() -> Self {
+        use num_traits::Num;
+        let parse = |hex| {
+            num_bigint::BigUint::from_str_radix(hex, 16).expect("endomorphism constant parses")
+        };
+        let field_p =
+            parse("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f");
+        let beta = parse("7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee");
+        let beta2 = &beta * &beta % &field_p;
+        let lambda =
+            parse("5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72");
+        let lambda2 = &lambda * &lambda % crate::keygen::curve_order();
+        let scalar = |value: &num_bigint::BigUint| {
+            let mut bytes = [0u8; 32];
+            write_be(&mut bytes, value);
+            bitcoin::secp256k1::Scalar::from_be_bytes(bytes)
+                .expect("endomorphism scalar is below the curve order")
+        };
+        Self {
+            lambda: scalar(&lambda),
+            lambda2: scalar(&lambda2),
+            field_p,
+            beta,
+            beta2,
+        }
+    }
+
+    /// Check the five endomorphism-related candidates of `secret_key`
+    /// (whose point is `point`) against the puzzle. On a match the
+    /// returned result carries the related key — λk, λ²k or a negation —
+    /// not `secret_key` itself.
+    pub fn check_related(
+        &self,
+        point: &bitcoin::secp256k1::PublicKey,
+        secret_key: &SecretKey,
+        puzzle: &Puzzle,
+    ) -> Result<Option<CheckResult>> {
+        use num_bigint::BigUint;
+        let serialized = point.serialize_uncompressed();
+        let x = BigUint::from_bytes_be(&serialized[1..33]);
+        let y = BigUint::from_bytes_be(&serialized[33..65]);
+        let beta_x = &x * &self.beta % &self.field_p;
+        let beta2_x = &x * &self.beta2 % &self.field_p;
+        let neg_y = &self.field_p - &y;
+        // (x, y, scalar tweak, negate) per related key; the tweak and
+        // negation rebuild the matching secret only when an address hits.
+        let candidates: [(&BigUint, &BigUint, Option<&bitcoin::secp256k1::Scalar>, bool); 5] = [
+            (&beta_x, &y, Some(&self.lambda), false),
+            (&beta2_x, &y, Some(&self.lambda2), false),
+            (&x, &neg_y, None, true),
+            (&beta_x, &neg_y, Some(&self.lambda), true),
+            (&beta2_x, &neg_y, Some(&self.lambda2), true),
+        ];
+        for (cand_x, cand_y, tweak, negate) in candidates {
+            let mut bytes = [0u8; 65];
+            bytes[0] = 0x04;
+            write_be(&mut bytes[1..33], cand_x);
+            write_be(&mut bytes[33..65], cand_y);
+            let candidate = bitcoin::secp256k1::PublicKey::from_slice(&bytes)
+                .context("endomorphism image is not on the curve")?;
+            if let Some((address, address_type)) = match_point(&candidate, puzzle) {
+                let mut related = *secret_key;
+                if let Some(tweak) = tweak {
+                    related = related
+                        .mul_tweak(tweak)
+                        .context("lambda tweak left the key space")?;
+                }
+                if negate {
+                    related = related.negate();
+                }
+                return Ok(Some(CheckResult::new(
+                    puzzle.number,
+                    address,
+                    secret_to_hex(&related),
+                    address_type,
+                )));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Default for Endomorphism {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Left-pad `value` into `dest` as big-endian bytes.
+fn write_be(dest: &mut [u8], value: &num_bigint::BigUint) {
+    let bytes = value.to_bytes_be();
+    let pad = dest.len() - bytes.len();
+    dest[pad..].copy_from_slice(&bytes);
+}
+
+/// Steps between full re-derivations of an incrementally-walked point, so
+/// a hypothetical arithmetic slip cannot corrupt more than one interval.
+const REDERIVE_INTERVAL: u32 = 4096;
+
+/// Walks a public key along a fixed-stride key sequence by point addition.
+///
+/// `pubkey(k + stride) = pubkey(k) + stride·G` is one point addition,
+/// against a full scalar multiplication for deriving it from scratch —
+/// the bulk of the per-key CPU in sequential mode. The secret key for each
+/// position still comes from the caller; the walker only tracks the point.
+pub struct IncrementalWalker {
+    /// `stride·G`, added on every step.
+    step_point: bitcoin::secp256k1::PublicKey,
+    point: bitcoin::secp256k1::PublicKey,
+    steps_since_derive: u32,
+}
+
+impl IncrementalWalker {
+    /// A walker positioned on `start`, stepping by `stride` keys at a time.
+    pub fn new(start: &SecretKey, stride: u64) -> Result<Self> {
+        anyhow::ensure!(stride > 0, "stride must be nonzero");
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&stride.to_be_bytes());
+        let step_point = public_key_for(
+            &SecretKey::from_slice(&bytes).context("stride is not a valid scalar")?,
+        );
+        let point = public_key_for(start);
+        Ok(Self {
+            step_point,
+            point,
+            steps_since_derive: 0,
+        })
+    }
+
+    /// The point for the current position.
+    pub fn public_key(&self) -> bitcoin::secp256k1::PublicKey {
+        self.point
+    }
+
+    /// Advance one stride to `next`, which must be the current key plus the
+    /// stride. Periodically re-derives the point from `next` outright.
+    pub fn advance(&mut self, next: &SecretKey) -> Result<()> {
+        self.steps_since_derive += 1;
+        if self.steps_since_derive >= REDERIVE_INTERVAL {
+            self.point = public_key_for(next);
+            self.steps_since_derive = 0;
+        } else {
+            self.point = self
+                .point
+                .combine(&self.step_point)
+                .context("point addition landed on infinity")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Private key 0x1 has a famous, well-known compressed P2PKH address.
+    const KEY_ONE_COMPRESSED: &str = "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH";
+
+    fn key_one() -> SecretKey {
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        SecretKey::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn redaction_hides_key_material_unless_revealed() {
+        let redacted = redact("00000000000000000000000000000001", false);
+        assert!(redacted.starts_with("sha256:"));
+        assert!(!redacted.contains("0001"));
+        assert_eq!(redact("cafe", true), "cafe");
+    }
+
+    #[test]
+    fn debug_output_never_contains_the_key() {
+        let result = CheckResult::new(
+            1,
+            KEY_ONE_COMPRESSED.into(),
+            format!("{:064x}", 1),
+            AddressType::Compressed,
+        );
+        let debug = format!("{result:?}");
+        assert!(!debug.contains(&format!("{:064x}", 1)));
+        assert!(!debug.contains(&result.public_key_hex));
+        assert!(!debug.contains(result.wif(true).unwrap().as_str()));
+    }
+
+    #[test]
+    fn new_derives_the_enrichment_fields() {
+        let result = CheckResult::new(
+            1,
+            KEY_ONE_COMPRESSED.into(),
+            format!("{:064x}", 1),
+            AddressType::Compressed,
+        );
+        assert_eq!(
+            result.public_key_hex,
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(result.network, "bitcoin");
+        assert!(result.found_at <= chrono::Utc::now());
+        // Malformed key hex leaves the derived fields empty instead of
+        // refusing to build the container.
+        let bogus =
+            CheckResult::new(1, KEY_ONE_COMPRESSED.into(), "01d3".into(), AddressType::Compressed);
+        assert!(bogus.public_key_hex.is_empty());
+    }
+
+    #[test]
+    fn vanity_leads_follow_the_network() {
+        assert_eq!(vanity_leads(Network::Bitcoin), (&['1'][..], "bc1"));
+        assert_eq!(vanity_leads(Network::Testnet), (&['m', 'n'][..], "tb1"));
+        assert_eq!(vanity_leads(Network::Signet), (&['m', 'n'][..], "tb1"));
+        assert_eq!(vanity_leads(Network::Regtest), (&['m', 'n'][..], "bcrt1"));
+    }
+
+    #[test]
+    fn vanity_prefixes_match_the_rendered_address() {
+        let mut config = crate::config::Config::from_env();
+        config.vanity_prefixes = vec!["1BgGZ9".into(), "tooshort".into()];
+        let set = VanitySet::from_config(&config).expect("one usable prefix");
+        let point = public_key_for(&key_one());
+        let result = set.check(&point, &key_one()).expect("prefix hit");
+        assert!(result.vanity);
+        assert_eq!(result.puzzle_number, 0);
+        assert_eq!(result.address, KEY_ONE_COMPRESSED);
+        assert_eq!(result.address_type, AddressType::Compressed);
+        // A prefix the address does not start with misses.
+        config.vanity_prefixes = vec!["1Love".into()];
+        let set = VanitySet::from_config(&config).unwrap();
+        assert!(set.check(&point, &key_one()).is_none());
+        // Nothing usable at all disables the matcher.
+        config.vanity_prefixes = vec!["xyz".into()];
+        assert!(VanitySet::from_config(&config).is_none());
+    }
+
+    #[test]
+    fn proof_signature_verifies_against_the_address() {
+        use base64::Engine;
+        let result = CheckResult::new(
+            1,
+            KEY_ONE_COMPRESSED.into(),
+            format!("{:064x}", 1),
+            AddressType::Compressed,
+        );
+        let raw = base64::engine::general_purpose::STANDARD
+            .decode(result.proof_signature().unwrap())
+            .unwrap();
+        let signature = bitcoin::sign_message::MessageSignature::from_slice(&raw).unwrap();
+        let hash = bitcoin::sign_message::signed_msg_hash(&result.proof_message());
+        let address: Address = KEY_ONE_COMPRESSED
+            .parse::<Address<bitcoin::address::NetworkUnchecked>>()
+            .unwrap()
+            .assume_checked();
+        assert!(with_secp(|secp| signature.is_signed_by_address(secp, &address, hash)).unwrap());
+        // Malformed key hex yields no proof rather than a bogus one.
+        let bogus =
+            CheckResult::new(1, KEY_ONE_COMPRESSED.into(), "01d3".into(), AddressType::Compressed);
+        assert!(bogus.proof_signature().is_none());
+    }
+
+    #[test]
+    fn wif_encodings_match_the_known_key_one_forms() {
+        let result = CheckResult::new(
+            1,
+            KEY_ONE_COMPRESSED.into(),
+            format!("{:064x}", 1),
+            AddressType::Compressed,
+        );
+        assert_eq!(
+            result.wif(true).unwrap().as_str(),
+            "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn"
+        );
+        assert_eq!(
+            result.wif(false).unwrap().as_str(),
+            "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf"
+        );
+        let bogus = CheckResult::new(1, KEY_ONE_COMPRESSED.into(), "01d3".into(), AddressType::Compressed);
+        assert!(bogus.wif(true).is_none());
+    }
+
+    #[test]
+    fn derives_known_compressed_address() {
+        assert_eq!(
+            derive_bitcoin_address(&key_one(), AddressType::Compressed).unwrap(),
+            KEY_ONE_COMPRESSED
+        );
+    }
+
+    #[test]
+    fn check_matches_compressed_target() {
+        let puzzle = Puzzle {
+            number: 1,
+            address: KEY_ONE_COMPRESSED.into(),
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.0,
+            solved: true,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        let result = check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .expect("key 1 must match its own address");
+        assert_eq!(result.address_type, AddressType::Compressed);
+        assert_eq!(result.private_key_hex.trim_start_matches('0'), "1");
+    }
+
+    #[test]
+    fn endomorphism_finds_every_related_key() {
+        let mut bytes = [0u8; 32];
+        bytes[28..].copy_from_slice(&0xdead_beefu32.to_be_bytes());
+        let key = SecretKey::from_slice(&bytes).unwrap();
+        let point = key.public_key(&Secp256k1::new());
+        let endo = Endomorphism::new();
+        // The five related keys, derived independently through the scalar
+        // API the checker itself only uses on a hit.
+        let lambda = endo.lambda;
+        let related = [
+            key.mul_tweak(&lambda).unwrap(),
+            key.mul_tweak(&lambda).unwrap().mul_tweak(&lambda).unwrap(),
+            key.negate(),
+            key.mul_tweak(&lambda).unwrap().negate(),
+            key.mul_tweak(&lambda).unwrap().mul_tweak(&lambda).unwrap().negate(),
+        ];
+        for expected in related {
+            for address_type in [AddressType::Compressed, AddressType::Uncompressed] {
+                let puzzle = Puzzle {
+                    number: 99,
+                    address: derive_bitcoin_address(&expected, address_type).unwrap(),
+                    range_start: "1".into(),
+                    range_end: "1".into(),
+                    reward_btc: 0.0,
+                    solved: false,
+                    public_key: None,
+                    compressed_only: false,
+                    strategy: None,
+                    address_type: None,
+                    pubkey_point: None,
+                    target: None,
+                };
+                let result = endo
+                    .check_related(&point, &key, &puzzle)
+                    .unwrap()
+                    .expect("related key must match its own address");
+                assert_eq!(
+                    result.reveal_private_key(),
+                    hex::encode(expected.secret_bytes())
+                );
+            }
+        }
+        // An unrelated target stays a miss.
+        let puzzle = Puzzle {
+            number: 99,
+            address: KEY_ONE_COMPRESSED.into(),
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.0,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        assert!(endo.check_related(&point, &key, &puzzle).unwrap().is_none());
+    }
+
+    #[test]
+    fn incremental_walk_matches_scratch_derivation() {
+        let secp = Secp256k1::new();
+        let mut bytes = [0u8; 32];
+        bytes[31] = 5;
+        let mut key = SecretKey::from_slice(&bytes).unwrap();
+        let stride = 3u64;
+        let tweak = bitcoin::secp256k1::Scalar::from_be_bytes({
+            let mut b = [0u8; 32];
+            b[24..].copy_from_slice(&stride.to_be_bytes());
+            b
+        })
+        .unwrap();
+        let mut walker = IncrementalWalker::new(&key, stride).unwrap();
+        for _ in 0..64 {
+            key = key.add_tweak(&tweak).unwrap();
+            walker.advance(&key).unwrap();
+            assert_eq!(walker.public_key(), key.public_key(&secp));
+        }
+    }
+
+    #[test]
+    fn target_decoding_caches_and_rejects_garbage() {
+        // Digest-based matching must agree with full string derivation.
+        let decoded = cached_target(KEY_ONE_COMPRESSED).expect("valid P2PKH target");
+        let secp = Secp256k1::new();
+        assert_eq!(
+            decoded,
+            Target::P2pkh {
+                hash160: hash160(&key_one().public_key(&secp).serialize())
+            }
+        );
+        // Repeat lookups hit the cache and still agree.
+        assert_eq!(cached_target(KEY_ONE_COMPRESSED), Some(decoded));
+        // Garbage targets never match anything.
+        assert_eq!(cached_target("not-base58"), None);
+    }
+
+    #[test]
+    fn p2wpkh_target_matches_compressed_only() {
+        let bech32 = derive_bitcoin_address(&key_one(), AddressType::P2wpkh).unwrap();
+        let puzzle = Puzzle {
+            number: 1,
+            address: bech32.clone(),
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.1,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: Some(Target::decode(&bech32).unwrap()),
+        };
+        let hit = check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .expect("compressed hash160 pays the witness program");
+        assert_eq!(hit.address, bech32);
+        assert_eq!(hit.address_type, AddressType::P2wpkh);
+    }
+
+    #[test]
+    fn target_set_matches_either_serialization() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("targets.txt");
+        let compressed = derive_bitcoin_address(&key_one(), AddressType::Compressed).unwrap();
+        std::fs::write(
+            &path,
+            format!("# funded addresses\n{compressed}\nnot-an-address\n\n"),
+        )
+        .unwrap();
+        let set = TargetSet::load(&path).unwrap();
+        assert_eq!(set.len(), 1, "only the valid line loads");
+        let secp = Secp256k1::new();
+        let hit = set
+            .check(&key_one().public_key(&secp), &key_one())
+            .expect("compressed digest is in the set");
+        assert_eq!(hit.puzzle_number, 0, "target-set sentinel");
+        assert_eq!(hit.address, compressed);
+        assert_eq!(hit.address_type, AddressType::Compressed);
+        let mut bytes = [0u8; 32];
+        bytes[31] = 3;
+        let other = SecretKey::from_slice(&bytes).unwrap();
+        assert!(set.check(&other.public_key(&secp), &other).is_none());
+    }
+
+    #[test]
+    fn self_test_passes_on_a_healthy_build() {
+        self_test().unwrap();
+    }
+
+    #[test]
+    fn known_point_decides_membership_before_hashing() {
+        let mut puzzle = Puzzle {
+            number: 1,
+            address: KEY_ONE_COMPRESSED.into(),
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.1,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: Some(public_key_for(&key_one()).serialize()),
+            target: None,
+        };
+        let hit = check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .expect("the candidate's point equals the exposed one");
+        assert_eq!(hit.address, KEY_ONE_COMPRESSED);
+        // A differing point vetoes the candidate outright, even though the
+        // address hash would have matched.
+        let mut bytes = [0u8; 32];
+        bytes[31] = 2;
+        let other = SecretKey::from_slice(&bytes).unwrap();
+        puzzle.pubkey_point = Some(public_key_for(&other).serialize());
+        assert!(check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn compressed_only_skips_the_uncompressed_form() {
+        let uncompressed = derive_bitcoin_address(&key_one(), AddressType::Uncompressed).unwrap();
+        let mut puzzle = Puzzle {
+            number: 1,
+            address: uncompressed,
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.1,
+            solved: false,
+            public_key: None,
+            compressed_only: true,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        assert!(
+            check_private_key_against_puzzle(&key_one(), &puzzle)
+                .unwrap()
+                .is_none(),
+            "the uncompressed form must not be derived"
+        );
+        puzzle.compressed_only = false;
+        let hit = check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .expect("full check finds the uncompressed match");
+        assert_eq!(hit.address_type, AddressType::Uncompressed);
+    }
+
+    #[test]
+    fn p2tr_target_matches_the_tweaked_output_key() {
+        let bech32m = derive_bitcoin_address(&key_one(), AddressType::P2tr).unwrap();
+        assert!(bech32m.starts_with("bc1p"));
+        let puzzle = Puzzle {
+            number: 1,
+            address: bech32m.clone(),
+            range_start: "1".into(),
+            range_end: "1".into(),
+            reward_btc: 0.1,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: Some("p2tr".into()),
+            pubkey_point: None,
+            target: Some(Target::decode(&bech32m).unwrap()),
+        };
+        let hit = check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .expect("the key-path output key pays the taproot program");
+        assert_eq!(hit.address, bech32m);
+        assert_eq!(hit.address_type, AddressType::P2tr);
+        // A different key's output key misses.
+        let mut bytes = [0u8; 32];
+        bytes[31] = 2;
+        let other = SecretKey::from_slice(&bytes).unwrap();
+        assert!(check_private_key_against_puzzle(&other, &puzzle)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn check_rejects_wrong_target() {
+        let puzzle = Puzzle {
+            number: 2,
+            address: "1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb".into(),
+            range_start: "2".into(),
+            range_end: "3".into(),
+            reward_btc: 0.0,
+            solved: true,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        assert!(check_private_key_against_puzzle(&key_one(), &puzzle)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn per_puzzle_totals_accumulate_and_sort() {
+        let stats = CheckStats::default();
+        stats.record_puzzle_checked(71, 100);
+        stats.record_puzzle_checked(68, 50);
+        stats.record_puzzle_checked(71, 25);
+        let totals = stats.per_puzzle_totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].0, 68);
+        assert_eq!(totals[0].1.keys_checked, 50);
+        assert_eq!(totals[1].0, 71);
+        assert_eq!(totals[1].1.keys_checked, 125);
+        assert!(totals[1].1.last_checked >= totals[0].1.last_checked);
+    }
+}
@@ -0,0 +1,979 @@
+//! One-shot command-line interface.
+//!
+//! With no subcommand the binary runs the long-lived bot as before. A
+//! subcommand runs a single task to completion and exits, without loading
+//! the puzzle file or starting Telegram, HTTP or the scheduler.
+
+use std::io::Write;
+
+use anyhow::{ensure, Context, Result};
+use clap::{Args, Parser, Subcommand};
+use num_bigint::BigUint;
+use num_traits::{Num, Zero};
+
+use crate::checker;
+use crate::keygen;
+use crate::puzzles::Puzzle;
+
+#[derive(Parser)]
+#[command(version = crate::buildinfo::VERSION, about = "Bitcoin puzzle lotto solver")]
+pub struct Cli {
+    /// Validate config and puzzles, simulate one session, and exit without
+    /// doing any real work.
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Detach from the terminal, write DATA_DIR/bot.pid and log to files.
+    #[arg(long)]
+    pub daemon: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Sweep an arbitrary key range once and print any matches.
+    ScanRange(ScanRangeArgs),
+    /// Print time-to-exhaust and hit odds per puzzle at a given key rate.
+    Estimate(EstimateArgs),
+    /// Compare keygen strategies and checker backends on this machine.
+    Benchmark(BenchmarkArgs),
+    /// Write an annotated .env template, prompting for Telegram credentials.
+    Init(InitArgs),
+    /// Print the addresses and hash160 for a private key (hex or WIF).
+    Derive(DeriveArgs),
+    /// Inspect and slice the puzzle file.
+    Puzzles(PuzzlesArgs),
+    /// Signal a daemonized instance to shut down via its PID file.
+    Stop,
+    /// Restore an exported snapshot, then start the bot from it.
+    Resume(ResumeArgs),
+    /// Check the puzzle file, config and Telegram credentials; exit
+    /// non-zero on problems.
+    Validate(ValidateArgs),
+    /// Run the full pipeline against tiny generated puzzles that solve in
+    /// minutes, notifications included.
+    Demo,
+    /// Manage the Windows service registration.
+    Service(ServiceArgs),
+    /// Manage the encrypted secrets file (see SECRETS_PASSPHRASE).
+    Secrets(SecretsArgs),
+}
+
+#[derive(Args)]
+pub struct SecretsArgs {
+    #[command(subcommand)]
+    action: SecretsAction,
+}
+
+#[derive(Subcommand)]
+enum SecretsAction {
+    /// Store one secret, prompting for its value.
+    Set {
+        /// Env var name, e.g. TELEGRAM_BOT_TOKEN.
+        key: String,
+    },
+    /// List the names (not values) of stored secrets.
+    List,
+    /// Move known credentials out of the environment/.env into the file.
+    Migrate,
+}
+
+/// The secrets passphrase, from the environment or an interactive prompt.
+fn secrets_passphrase() -> Result<String> {
+    if let Some(passphrase) = crate::secrets::passphrase_from_env()? {
+        return Ok(passphrase);
+    }
+    let passphrase = prompt("Secrets passphrase")?;
+    ensure!(!passphrase.is_empty(), "a passphrase is required");
+    Ok(passphrase)
+}
+
+fn secrets_command(args: &SecretsArgs) -> Result<()> {
+    let path = crate::secrets::secrets_path();
+    let passphrase = secrets_passphrase()?;
+    match &args.action {
+        SecretsAction::Set { key } => {
+            let value = prompt(&format!("Value for {key}"))?;
+            ensure!(!value.is_empty(), "refusing to store an empty value");
+            let mut secrets = crate::secrets::read_all(&path, &passphrase)?;
+            secrets.insert(key.clone(), value);
+            crate::secrets::write_all(&path, &passphrase, &secrets)?;
+            println!("stored {key} in {}", path.display());
+        }
+        SecretsAction::List => {
+            let secrets = crate::secrets::read_all(&path, &passphrase)?;
+            if secrets.is_empty() {
+                println!("no secrets stored in {}", path.display());
+            } else {
+                for key in secrets.keys() {
+                    println!("{key}");
+                }
+            }
+        }
+        SecretsAction::Migrate => {
+            let moved = crate::secrets::migrate(&path, &passphrase)?;
+            if moved.is_empty() {
+                println!("nothing to migrate; no managed credentials are set");
+            } else {
+                println!("moved into {}:", path.display());
+                for key in &moved {
+                    println!("  {key}");
+                }
+                println!(
+                    "now delete these lines from .env and set SECRETS_PASSPHRASE \
+                     (or SECRETS_PASSPHRASE_FILE) instead"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Args)]
+pub struct ServiceArgs {
+    #[command(subcommand)]
+    action: ServiceAction,
+}
+
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Register the bot with the service control manager (run elevated).
+    Install,
+    /// Remove the service registration.
+    Uninstall,
+    /// Entry point the service control manager launches; not for shells.
+    Run,
+}
+
+#[cfg(unix)]
+fn stop_command() -> Result<()> {
+    println!("{}", crate::daemon::stop()?);
+    Ok(())
+}
+
+#[cfg(windows)]
+fn stop_command() -> Result<()> {
+    anyhow::bail!("stop is Unix-only; use `sc stop` or the service manager on Windows")
+}
+
+#[cfg(windows)]
+fn service_command(args: &ServiceArgs) -> Result<()> {
+    match args.action {
+        ServiceAction::Install => crate::service::install(),
+        ServiceAction::Uninstall => crate::service::uninstall(),
+        ServiceAction::Run => crate::service::run(),
+    }
+}
+
+#[cfg(not(windows))]
+fn service_command(_args: &ServiceArgs) -> Result<()> {
+    anyhow::bail!("the service subcommand is only available on Windows")
+}
+
+#[derive(Args)]
+pub struct ResumeArgs {
+    /// Snapshot file previously written by the export mechanism.
+    pub snapshot: std::path::PathBuf,
+}
+
+#[derive(Args)]
+pub struct ScanRangeArgs {
+    /// Inclusive range start, hex (0x prefix optional).
+    #[arg(long)]
+    start: String,
+    /// Inclusive range end, hex.
+    #[arg(long)]
+    end: String,
+    /// Target P2PKH address to compare every key against.
+    #[arg(long)]
+    address: String,
+    /// Also append matches as JSON lines to this file.
+    #[arg(long)]
+    export: Option<std::path::PathBuf>,
+}
+
+#[derive(Args)]
+pub struct EstimateArgs {
+    /// Assumed keys/second; measured with a short benchmark when omitted.
+    #[arg(long)]
+    rate: Option<u64>,
+    /// Puzzle file to estimate against.
+    #[arg(long, default_value = "puzzles.json")]
+    puzzles: std::path::PathBuf,
+}
+
+#[derive(Args)]
+pub struct BenchmarkArgs {
+    /// Seconds to run each scenario for.
+    #[arg(long, default_value_t = 2)]
+    secs: u64,
+    /// Write a machine-readable report to this JSON file.
+    #[arg(long)]
+    json: Option<std::path::PathBuf>,
+}
+
+/// Run a parsed subcommand to completion.
+pub fn run(command: Command) -> Result<()> {
+    match command {
+        Command::ScanRange(args) => scan_range(&args),
+        Command::Estimate(args) => estimate(&args),
+        Command::Benchmark(args) => benchmark(&args),
+        Command::Init(args) => init(&args),
+        Command::Derive(args) => derive(&args),
+        Command::Puzzles(args) => puzzles_command(&args),
+        Command::Stop => stop_command(),
+        Command::Service(args) => service_command(&args),
+        Command::Secrets(args) => secrets_command(&args),
+        // Intercepted in main: resuming arranges the snapshot import and
+        // then starts the full bot.
+        Command::Resume(_) => unreachable!("resume is handled in main"),
+        Command::Validate(args) => validate(&args),
+        // Like resume: demo prepares the environment, then the bot runs.
+        Command::Demo => unreachable!("demo is handled in main"),
+    }
+}
+
+fn parse_hex(name: &str, value: &str) -> Result<BigUint> {
+    BigUint::from_str_radix(value.trim_start_matches("0x"), 16)
+        .with_context(|| format!("--{name} is not a hex integer: {value:?}"))
+}
+
+/// Exhaustively check every key in the range against the target address,
+/// printing matches as JSON lines.
+fn scan_range(args: &ScanRangeArgs) -> Result<()> {
+    let start = parse_hex("start", &args.start)?;
+    let end = parse_hex("end", &args.end)?;
+    ensure!(start <= end, "--start is above --end");
+    // The checker works against puzzle targets; wrap the address in a
+    // synthetic, unnumbered one.
+    let puzzle = Puzzle {
+        number: 0,
+        address: args.address.clone(),
+        range_start: format!("{start:x}"),
+        range_end: format!("{end:x}"),
+        reward_btc: 0.0,
+        solved: false,
+        public_key: None,
+        compressed_only: false,
+        strategy: None,
+        address_type: None,
+        pubkey_point: None,
+        target: None,
+    };
+    let mut export = args
+        .export
+        .as_ref()
+        .map(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("opening {}", path.display()))
+        })
+        .transpose()?;
+    let started = std::time::Instant::now();
+    let mut current = start;
+    let mut checked = 0u64;
+    let mut matches = 0u64;
+    while current <= end {
+        // Zero is not a valid secret key; the scan starts above it.
+        if current.is_zero() {
+            current += 1u32;
+            continue;
+        }
+        let key = keygen::secret_key_from_biguint(&current)?;
+        if let Some(result) = checker::check_private_key_against_puzzle(&key, &puzzle)? {
+            let line = serde_json::to_string(&result)?;
+            println!("{line}");
+            if let Some(file) = &mut export {
+                writeln!(file, "{line}")?;
+            }
+            matches += 1;
+        }
+        checked += 1;
+        current += 1u32;
+    }
+    let secs = started.elapsed().as_secs_f64().max(f64::EPSILON);
+    eprintln!(
+        "checked {checked} key(s) in {secs:.1}s ({:.0} keys/s), {matches} match(es)",
+        checked as f64 / secs,
+    );
+    Ok(())
+}
+
+/// A synthetic 66-bit puzzle whose address never matches, for benchmarks.
+fn bench_target() -> (BigUint, BigUint, Puzzle) {
+    let start = BigUint::from(1u32) << 65;
+    let end = (&start << 1) - 1u32;
+    let puzzle = Puzzle {
+        number: 0,
+        address: "1BitcoinEaterAddressDontSendf59kuE".into(),
+        range_start: format!("{start:x}"),
+        range_end: format!("{end:x}"),
+        reward_btc: 0.0,
+        solved: false,
+        public_key: None,
+        compressed_only: false,
+        strategy: None,
+        address_type: None,
+        pubkey_point: None,
+        target: None,
+    };
+    (start, end, puzzle)
+}
+
+/// Measure the single-thread check rate over roughly one second.
+fn bench_rate() -> Result<u64> {
+    let (start, end, puzzle) = bench_target();
+    let result = bench_scenario("random", 1, || {
+        let key = keygen::generate_random_key_in_range(&start, &end)?;
+        checker::check_private_key_against_puzzle(&key, &puzzle)?;
+        Ok(())
+    })?;
+    Ok(result.rate)
+}
+
+/// One benchmark scenario's totals.
+#[derive(serde::Serialize)]
+struct BenchResult {
+    name: &'static str,
+    keys: u64,
+    rate: u64,
+}
+
+/// Run one closure repeatedly for `secs` seconds, counting iterations.
+fn bench_scenario(
+    name: &'static str,
+    secs: u64,
+    mut step: impl FnMut() -> Result<()>,
+) -> Result<BenchResult> {
+    let started = std::time::Instant::now();
+    let duration = std::time::Duration::from_secs(secs.max(1));
+    let mut keys = 0u64;
+    while started.elapsed() < duration {
+        step()?;
+        keys += 1;
+    }
+    let rate = (keys as f64 / started.elapsed().as_secs_f64()) as u64;
+    Ok(BenchResult { name, keys, rate })
+}
+
+/// Exercise the keygen strategies and checker backends, print a comparison
+/// table and optionally dump a JSON report.
+fn benchmark(args: &BenchmarkArgs) -> Result<()> {
+    let (start, end, puzzle) = bench_target();
+    let mut results = Vec::new();
+    eprintln!("running 3 scenario(s), {}s each...", args.secs.max(1));
+    results.push(bench_scenario("random + full check", args.secs, || {
+        let key = keygen::generate_random_key_in_range(&start, &end)?;
+        checker::check_private_key_against_puzzle(&key, &puzzle)?;
+        Ok(())
+    })?);
+    let mut cursor = start.clone();
+    results.push(bench_scenario("sequential + full check", args.secs, || {
+        let key = keygen::secret_key_from_biguint(&cursor)?;
+        checker::check_private_key_against_puzzle(&key, &puzzle)?;
+        cursor += 1u32;
+        Ok(())
+    })?);
+    let mut cursor = start.clone();
+    results.push(bench_scenario("sequential + compressed only", args.secs, || {
+        let key = keygen::secret_key_from_biguint(&cursor)?;
+        checker::derive_bitcoin_address(&key, checker::AddressType::Compressed)?;
+        cursor += 1u32;
+        Ok(())
+    })?);
+    println!("{:<30}  {:>10}  {:>10}", "scenario", "keys", "keys/s");
+    for result in &results {
+        println!("{:<30}  {:>10}  {:>10}", result.name, result.keys, result.rate);
+    }
+    if let Some(path) = &args.json {
+        let report = serde_json::json!({
+            "host": hostname::get().ok().and_then(|h| h.into_string().ok()),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "seconds_per_scenario": args.secs.max(1),
+            "results": results,
+        });
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("writing {}", path.display()))?;
+        eprintln!("report written to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Seconds rendered at a planning-friendly scale, up to scientific-notation
+/// years for the larger puzzles.
+fn human_duration(secs: f64) -> String {
+    const YEAR: f64 = 365.25 * 86400.0;
+    if secs >= 100.0 * YEAR {
+        format!("{:.1e} years", secs / YEAR)
+    } else if secs >= YEAR {
+        format!("{:.1} years", secs / YEAR)
+    } else if secs >= 86400.0 {
+        format!("{:.1} days", secs / 86400.0)
+    } else if secs >= 3600.0 {
+        format!("{:.1} hours", secs / 3600.0)
+    } else if secs >= 60.0 {
+        format!("{:.1} minutes", secs / 60.0)
+    } else {
+        format!("{secs:.1} seconds")
+    }
+}
+
+/// Print exhaust time and per-day hit probability for each unsolved puzzle.
+fn estimate(args: &EstimateArgs) -> Result<()> {
+    let puzzles = crate::puzzles::PuzzleCollection::load(&args.puzzles)?;
+    let rate = match args.rate {
+        Some(rate) => rate,
+        None => {
+            eprintln!("benchmarking single-thread rate for ~1s...");
+            bench_rate()?
+        }
+    };
+    ensure!(rate > 0, "rate must be above zero");
+    println!("At {rate} keys/s, one thread:");
+    println!("{:>7}  {:>8}  {:>15}  {:>12}", "puzzle", "bits", "exhaust in", "P(hit)/day");
+    for puzzle in puzzles.all().iter().filter(|p| !p.solved) {
+        let size = match puzzle.range_size().ok().and_then(|s| num_traits::ToPrimitive::to_f64(&s)) {
+            Some(size) if size > 0.0 => size,
+            _ => continue,
+        };
+        let exhaust = human_duration(size / rate as f64);
+        let per_day = (rate as f64 * 86400.0 / size).min(1.0);
+        println!(
+            "{:>7}  {:>8}  {:>15}  {:>12.2e}",
+            format!("#{}", puzzle.number),
+            size.log2().ceil() as u32,
+            exhaust,
+            per_day,
+        );
+    }
+    Ok(())
+}
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Where to write the config.
+    #[arg(long, default_value = ".env")]
+    output: std::path::PathBuf,
+    /// Overwrite an existing file.
+    #[arg(long)]
+    force: bool,
+}
+
+fn prompt(label: &str) -> Result<String> {
+    print!("{label}: ");
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Write the annotated `.env` template, filling in Telegram credentials
+/// interactively when run from a terminal.
+fn init(args: &InitArgs) -> Result<()> {
+    ensure!(
+        args.force || !args.output.exists(),
+        "{} already exists; pass --force to overwrite",
+        args.output.display(),
+    );
+    let mut template = include_str!("../.env.example").to_string();
+    if std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        println!("Telegram setup (leave blank to run headless):");
+        let token = prompt("  Bot token from @BotFather")?;
+        if !token.is_empty() {
+            template = template.replace("TELEGRAM_BOT_TOKEN=", &format!("TELEGRAM_BOT_TOKEN={token}"));
+        }
+        let chat_id = prompt("  Chat ID to notify")?;
+        if !chat_id.is_empty() {
+            if chat_id.parse::<i64>().is_err() {
+                eprintln!("  note: {chat_id:?} is not numeric; the bot will ignore it");
+            }
+            template = template.replace("TELEGRAM_CHAT_ID=", &format!("TELEGRAM_CHAT_ID={chat_id}"));
+        }
+    } else {
+        eprintln!("stdin is not a terminal; writing the template without prompting");
+    }
+    std::fs::write(&args.output, template)
+        .with_context(|| format!("writing {}", args.output.display()))?;
+    // The file can hold credentials; keep it private.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&args.output, std::fs::Permissions::from_mode(0o600))?;
+    }
+    println!("Wrote {}. Review it, then start the bot normally.", args.output.display());
+    Ok(())
+}
+
+#[derive(Args)]
+pub struct DeriveArgs {
+    /// Private key, hex (0x prefix optional) or WIF.
+    key: String,
+}
+
+/// Accept a private key as hex or WIF.
+fn parse_private_key(input: &str) -> Result<bitcoin::secp256k1::SecretKey> {
+    if let Ok(value) = BigUint::from_str_radix(input.trim_start_matches("0x"), 16) {
+        return keygen::secret_key_from_biguint(&value);
+    }
+    bitcoin::PrivateKey::from_wif(input)
+        .map(|pk| pk.inner)
+        .context("key is neither hex nor a valid WIF")
+}
+
+/// Print every serialization this bot (and its neighbours) deal in for one
+/// private key: WIFs, hash160s and the common address types.
+fn derive(args: &DeriveArgs) -> Result<()> {
+    use bitcoin::{Address, CompressedPublicKey, PrivateKey, PublicKey};
+    let secret = parse_private_key(&args.key)?;
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+    let inner = secret.public_key(&secp);
+    let compressed = PublicKey::new(inner);
+    let uncompressed = PublicKey::new_uncompressed(inner);
+    let segwit = CompressedPublicKey(inner);
+    // Keep the printable key serializations in zeroizing buffers so they
+    // are wiped once written to stdout.
+    let wif = |compressed| {
+        zeroize::Zeroizing::new(
+            PrivateKey {
+                compressed,
+                network: crate::checker::network().into(),
+                inner: secret,
+            }
+            .to_wif(),
+        )
+    };
+    let key_hex = zeroize::Zeroizing::new(hex::encode(secret.secret_bytes()));
+    println!("hex:                    {}", key_hex.as_str());
+    println!("WIF (compressed):       {}", wif(true).as_str());
+    println!("WIF (uncompressed):     {}", wif(false).as_str());
+    println!("hash160 (compressed):   {}", compressed.pubkey_hash());
+    println!("hash160 (uncompressed): {}", uncompressed.pubkey_hash());
+    println!("P2PKH (compressed):     {}", Address::p2pkh(compressed, crate::checker::network()));
+    println!("P2PKH (uncompressed):   {}", Address::p2pkh(uncompressed, crate::checker::network()));
+    println!("P2WPKH:                 {}", Address::p2wpkh(&segwit, crate::checker::network()));
+    println!("P2SH-P2WPKH:            {}", Address::p2shwpkh(&segwit, crate::checker::network()));
+    Ok(())
+}
+
+#[derive(Args)]
+pub struct PuzzlesArgs {
+    /// Puzzle file to query.
+    #[arg(long, default_value = "puzzles.json")]
+    file: std::path::PathBuf,
+    #[command(subcommand)]
+    action: PuzzlesAction,
+}
+
+#[derive(Subcommand)]
+enum PuzzlesAction {
+    /// One line per puzzle matching the filters.
+    List(PuzzleFilter),
+    /// Full detail for one puzzle.
+    Show { number: u32 },
+    /// Matching puzzles as pretty-printed JSON on stdout.
+    Export(PuzzleFilter),
+    /// Split one puzzle's range into disjoint sub-ranges, one per line.
+    Split {
+        number: u32,
+        /// How many sub-ranges (e.g. worker threads or machines).
+        #[arg(long, default_value_t = 4)]
+        parts: usize,
+    },
+}
+
+#[derive(Args)]
+struct PuzzleFilter {
+    /// Smallest puzzle (bit length) to include.
+    #[arg(long)]
+    min_bits: Option<u32>,
+    /// Largest puzzle (bit length) to include.
+    #[arg(long)]
+    max_bits: Option<u32>,
+    /// Minimum reward in BTC.
+    #[arg(long)]
+    min_reward: Option<f64>,
+    /// Only unsolved puzzles.
+    #[arg(long)]
+    unsolved: bool,
+    /// Only solved puzzles.
+    #[arg(long)]
+    solved: bool,
+}
+
+impl PuzzleFilter {
+    fn matches(&self, puzzle: &Puzzle) -> bool {
+        self.min_bits.is_none_or(|b| puzzle.number >= b)
+            && self.max_bits.is_none_or(|b| puzzle.number <= b)
+            && self.min_reward.is_none_or(|r| puzzle.reward_btc >= r)
+            && (!self.unsolved || !puzzle.solved)
+            && (!self.solved || puzzle.solved)
+    }
+}
+
+/// `puzzles list|show|export`: query the puzzle file from the shell with
+/// the same collection the daemon loads.
+fn puzzles_command(args: &PuzzlesArgs) -> Result<()> {
+    let puzzles = crate::puzzles::PuzzleCollection::load(&args.file)?;
+    match &args.action {
+        PuzzlesAction::List(filter) => {
+            println!(
+                "{:>7}  {:>8}  {:>12}  {:<36}  range",
+                "puzzle", "solved", "reward BTC", "address"
+            );
+            for puzzle in puzzles.all().iter().filter(|p| filter.matches(p)) {
+                println!(
+                    "{:>7}  {:>8}  {:>12}  {:<36}  {}..{}",
+                    format!("#{}", puzzle.number),
+                    if puzzle.solved { "yes" } else { "no" },
+                    puzzle.reward_btc,
+                    puzzle.address,
+                    puzzle.range_start,
+                    puzzle.range_end,
+                );
+            }
+        }
+        PuzzlesAction::Show { number } => {
+            let puzzle = puzzles
+                .get(*number)
+                .with_context(|| format!("no puzzle #{number} in {}", args.file.display()))?;
+            println!("{}", serde_json::to_string_pretty(puzzle)?);
+            if let Ok(size) = puzzle.range_size() {
+                println!("range size: {size} keys (~2^{})", size.bits().saturating_sub(1));
+            }
+        }
+        PuzzlesAction::Split { number, parts } => {
+            let puzzle = puzzles
+                .get(*number)
+                .with_context(|| format!("no puzzle #{number} in {}", args.file.display()))?;
+            for (i, (start, end)) in puzzle.get_subranges(*parts)?.iter().enumerate() {
+                println!("{i:>3}  {start:x}..{end:x}");
+            }
+        }
+        PuzzlesAction::Export(filter) => {
+            let matching: Vec<&Puzzle> =
+                puzzles.all().iter().filter(|p| filter.matches(p)).collect();
+            println!("{}", serde_json::to_string_pretty(&matching)?);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Puzzle file to check.
+    #[arg(long, default_value = "puzzles.json")]
+    puzzles: std::path::PathBuf,
+    /// Skip the Telegram getMe round trip.
+    #[arg(long)]
+    offline: bool,
+}
+
+/// Collect problems with the puzzle file: parse errors, bad Base58
+/// checksums, ranges of the wrong width and overlaps between puzzles.
+fn validate_puzzles(path: &std::path::Path, problems: &mut Vec<String>) {
+    // Lenient load: the strict loader stops at the first bad address,
+    // but this command's job is to list every problem in one pass.
+    let puzzles = match crate::puzzles::PuzzleCollection::load_lenient(path) {
+        Ok(puzzles) => puzzles,
+        Err(err) => {
+            problems.push(format!("puzzle file: {err:#}"));
+            return;
+        }
+    };
+    println!("ok    {} puzzle(s) loaded from {}", puzzles.all().len(), path.display());
+    let mut ranges = Vec::new();
+    for puzzle in puzzles.all() {
+        let (start, end) = match puzzle.range() {
+            Ok(range) => range,
+            Err(err) => {
+                problems.push(format!("{err:#}"));
+                continue;
+            }
+        };
+        if start > end {
+            problems.push(format!("puzzle #{}: range start is above its end", puzzle.number));
+            continue;
+        }
+        if end.bits() != u64::from(puzzle.number) {
+            problems.push(format!(
+                "puzzle #{}: range end is {} bits wide, expected {}",
+                puzzle.number,
+                end.bits(),
+                puzzle.number,
+            ));
+        }
+        let checked = std::str::FromStr::from_str(&puzzle.address)
+            .map_err(|e: bitcoin::address::ParseError| e.to_string())
+            .and_then(|a: bitcoin::Address<bitcoin::address::NetworkUnchecked>| {
+                a.require_network(crate::checker::network()).map_err(|e| e.to_string())
+            });
+        if let Err(err) = checked {
+            problems.push(format!("puzzle #{}: bad address: {err}", puzzle.number));
+        }
+        ranges.push((start, end, puzzle.number));
+    }
+    ranges.sort();
+    for pair in ranges.windows(2) {
+        if pair[1].0 <= pair[0].1 {
+            problems.push(format!(
+                "puzzles #{} and #{} have overlapping ranges",
+                pair[0].2, pair[1].2,
+            ));
+        }
+    }
+    if ranges.len() == puzzles.all().len() {
+        println!("ok    all ranges parse");
+    }
+}
+
+/// Collect config problems from the loaded environment.
+fn validate_config(config: &crate::config::Config, problems: &mut Vec<String>) {
+    problems.extend(config.validate());
+    println!("ok    config loaded");
+}
+
+/// Pre-deploy checks for scripts: puzzle file, config, and (unless
+/// `--offline`) the Telegram token via getMe.
+fn validate(args: &ValidateArgs) -> Result<()> {
+    let mut problems = Vec::new();
+    validate_puzzles(&args.puzzles, &mut problems);
+    let (config, env_problems) = crate::config::Config::from_env_checked();
+    problems.extend(env_problems);
+    validate_config(&config, &mut problems);
+    match (&config.telegram_token, args.offline) {
+        (Some(token), false) => {
+            let reply: Result<serde_json::Value> =
+                tokio::runtime::Runtime::new()?.block_on(async {
+                    let url = format!("https://api.telegram.org/bot{token}/getMe");
+                    Ok(reqwest::get(url).await?.json().await?)
+                });
+            match reply {
+                Ok(me) if me["ok"] == true => println!(
+                    "ok    Telegram token is valid (@{})",
+                    me["result"]["username"].as_str().unwrap_or("?"),
+                ),
+                Ok(me) => problems.push(format!(
+                    "Telegram rejected the token: {}",
+                    me["description"].as_str().unwrap_or("unknown error"),
+                )),
+                Err(err) => problems.push(format!("Telegram getMe failed: {err:#}")),
+            }
+        }
+        (Some(_), true) => println!("skip  Telegram check (--offline)"),
+        (None, _) => println!("skip  Telegram check (no token configured)"),
+    }
+    if problems.is_empty() {
+        println!("All checks passed.");
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("FAIL  {problem}");
+    }
+    anyhow::bail!("{} problem(s) found", problems.len());
+}
+
+/// Generate the demo puzzle file and point the environment at it. Each
+/// puzzle hides a freshly drawn key in an 8–20 bit range, so the random
+/// search solves one within minutes and the whole pipeline — session,
+/// match handling, notifications — fires for real.
+pub fn prepare_demo() -> Result<()> {
+    let mut puzzles = Vec::new();
+    for bits in [8u32, 12, 16, 20] {
+        let start = BigUint::from(1u32) << (bits - 1);
+        let end = (BigUint::from(1u32) << bits) - 1u32;
+        let key = keygen::generate_random_key_in_range(&start, &end)?;
+        let address = checker::derive_bitcoin_address(&key, checker::AddressType::Compressed)?;
+        puzzles.push(serde_json::json!({
+            "number": bits,
+            "address": address,
+            "range_start": format!("{start:x}"),
+            "range_end": format!("{end:x}"),
+            "reward_btc": 0.0,
+            "solved": false,
+        }));
+    }
+    let path = std::env::temp_dir().join("btclotto_demo_puzzles.json");
+    std::fs::write(&path, serde_json::to_string_pretty(&puzzles)?)
+        .with_context(|| format!("writing {}", path.display()))?;
+    std::env::set_var("PUZZLE_FILE", &path);
+    std::env::set_var("MIN_BITS", "8");
+    std::env::set_var("MAX_BITS", "20");
+    std::env::set_var("SESSION_INTERVAL_SECS", "5");
+    std::env::set_var("SESSION_DURATION_SECS", "10");
+    eprintln!(
+        "demo mode: 4 artificial puzzles (8–20 bits) written to {}; expect a solve within minutes",
+        path.display(),
+    );
+    Ok(())
+}
+
+/// Validate the loaded config and puzzles, run a short simulated session
+/// with synthetic keys, and report what the bot would do — without
+/// persisting, notifying or contacting anything.
+pub fn dry_run(config: &crate::config::Config, puzzles: &crate::puzzles::PuzzleCollection) -> Result<()> {
+    println!("Dry run: nothing is persisted, notified or contacted.");
+    let s = &config.scheduler;
+    println!(
+        "Schedule: every {}s for {}s on {} thread(s), bits {}..={}",
+        s.session_interval_secs, s.session_duration_secs, s.threads, s.min_bits, s.max_bits,
+    );
+    let mode = if let Some(url) = &config.coordinator_url {
+        format!("cluster worker of {url}")
+    } else if config.pool_url.is_some() {
+        "pool worker".to_string()
+    } else if config.redis_url.is_some() {
+        "shared Redis queue".to_string()
+    } else if config.cluster_coordinator {
+        "coordinator + local sessions".to_string()
+    } else {
+        "standalone".to_string()
+    };
+    println!("Mode: {mode}");
+    println!(
+        "Interfaces: telegram={} http={} control={}",
+        config.telegram_token.is_some() && config.telegram_chat_id.is_some(),
+        config
+            .http_listen
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| "off".to_string()),
+        config.control_socket.is_some(),
+    );
+    let eligible = puzzles.eligible(s.min_bits, s.max_bits);
+    ensure!(
+        !eligible.is_empty(),
+        "no eligible puzzles in bits {}..={}; the scheduler would idle",
+        s.min_bits,
+        s.max_bits,
+    );
+    let numbers: Vec<String> = eligible.iter().take(8).map(|p| format!("#{}", p.number)).collect();
+    println!(
+        "Eligible puzzles: {} ({}{})",
+        eligible.len(),
+        numbers.join(", "),
+        if eligible.len() > numbers.len() { ", ..." } else { "" },
+    );
+    // Simulate one session: synthetic keys against the first eligible
+    // puzzle, long enough to measure a rate.
+    let puzzle = eligible[0];
+    let (start, end) = puzzle.range()?;
+    let result = bench_scenario("simulated", 1, || {
+        let key = keygen::generate_random_key_in_range(&start, &end)?;
+        checker::check_private_key_against_puzzle(&key, puzzle)?;
+        Ok(())
+    })?;
+    let per_session = result.rate * s.threads as u64 * s.session_duration_secs;
+    println!(
+        "Simulated session on puzzle #{}: {} keys/s per thread, ~{} keys per real session",
+        puzzle.number, result.rate, per_session,
+    );
+    println!("Dry run OK.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_range_finds_a_known_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let export = dir.path().join("matches.jsonl");
+        let args = ScanRangeArgs {
+            start: "0x1".into(),
+            end: "5".into(),
+            // Private key 0x1's compressed P2PKH address.
+            address: "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".into(),
+            export: Some(export.clone()),
+        };
+        scan_range(&args).unwrap();
+        let lines = std::fs::read_to_string(&export).unwrap();
+        assert_eq!(lines.lines().count(), 1);
+        assert!(lines.contains("\"compressed\""));
+    }
+
+    #[test]
+    fn parses_the_scan_range_subcommand() {
+        let cli = Cli::try_parse_from([
+            "bot",
+            "scan-range",
+            "--start",
+            "0x80",
+            "--end",
+            "0xff",
+            "--address",
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Some(Command::ScanRange(_))));
+        assert!(parse_hex("start", "zz").is_err());
+    }
+
+    #[test]
+    fn private_keys_parse_from_hex_and_wif() {
+        let from_hex = parse_private_key("0x1").unwrap();
+        // Key 0x1's well-known compressed-WIF form.
+        let from_wif =
+            parse_private_key("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn").unwrap();
+        assert_eq!(from_hex, from_wif);
+        assert!(parse_private_key("not a key").is_err());
+    }
+
+    #[test]
+    fn validate_flags_overlapping_ranges_and_bad_addresses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("puzzles.json");
+        std::fs::write(
+            &path,
+            r#"[
+                {"number":2,"address":"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH","range_start":"2","range_end":"3","reward_btc":0.1,"solved":false},
+                {"number":3,"address":"not-an-address","range_start":"2","range_end":"7","reward_btc":0.1,"solved":false}
+            ]"#,
+        )
+        .unwrap();
+        let mut problems = Vec::new();
+        validate_puzzles(&path, &mut problems);
+        assert!(problems.iter().any(|p| p.contains("overlapping")));
+        assert!(problems.iter().any(|p| p.contains("bad address")));
+    }
+
+    #[test]
+    fn puzzle_filters_combine() {
+        let puzzle = Puzzle {
+            number: 66,
+            address: "x".into(),
+            range_start: "20000000000000000".into(),
+            range_end: "3ffffffffffffffff".into(),
+            reward_btc: 6.6,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        let mut filter = PuzzleFilter {
+            min_bits: Some(60),
+            max_bits: Some(70),
+            min_reward: Some(5.0),
+            unsolved: true,
+            solved: false,
+        };
+        assert!(filter.matches(&puzzle));
+        filter.min_reward = Some(10.0);
+        assert!(!filter.matches(&puzzle));
+        filter.min_reward = None;
+        filter.solved = true;
+        assert!(!filter.matches(&puzzle));
+    }
+
+    #[test]
+    fn renders_durations_at_a_planning_scale() {
+        assert_eq!(human_duration(30.0), "30.0 seconds");
+        assert_eq!(human_duration(7200.0), "2.0 hours");
+        assert_eq!(human_duration(86400.0 * 3.0), "3.0 days");
+        assert!(human_duration(1e18).ends_with("years"));
+    }
+}
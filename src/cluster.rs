@@ -0,0 +1,650 @@
+//! Coordinator/worker distributed mode.
+//!
+//! One instance runs with `CLUSTER_COORDINATOR=true` and slices puzzle
+//! ranges into fixed-size work units, handed out over the embedded HTTP
+//! server (`/cluster/lease`, `/cluster/complete`, `/cluster/match`).
+//! Workers run with `COORDINATOR_URL` pointing at it: they lease a unit,
+//! search it for one session, then report keys checked and any matches.
+//! Matches are persisted and announced on both ends, so one Telegram bot
+//! covers the whole fleet while each worker still keeps its own journal.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::response::Json;
+use axum::routing::post;
+use axum::Router;
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::checker::CheckResult;
+use crate::config::Config;
+use crate::notify::Fanout;
+use crate::scheduler;
+use crate::state::AppState;
+
+/// One slice of a puzzle's key range, inclusive hex bounds like the puzzle
+/// file itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkUnit {
+    pub id: u64,
+    pub puzzle_number: u32,
+    pub range_start: String,
+    pub range_end: String,
+}
+
+impl WorkUnit {
+    /// Parse the inclusive bounds into big integers.
+    pub fn range(&self) -> Result<(BigUint, BigUint)> {
+        let parse = |bound: &str, name: &str| {
+            BigUint::from_str_radix(bound.trim_start_matches("0x"), 16)
+                .with_context(|| format!("work unit {}: bad {name}", self.id))
+        };
+        Ok((
+            parse(&self.range_start, "range_start")?,
+            parse(&self.range_end, "range_end")?,
+        ))
+    }
+}
+
+/// One remote instance's running totals, pushed to the primary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStats {
+    pub node: String,
+    pub keys_checked: u64,
+    pub rate: u64,
+    pub matches_found: u64,
+    pub sessions_run: u64,
+    pub uptime_secs: u64,
+}
+
+/// Completion report a worker posts back for a leased unit.
+#[derive(Debug, Serialize, Deserialize)]
+struct CompletionReport {
+    id: u64,
+    keys_checked: u64,
+}
+
+/// Per-worker completion history kept by the coordinator.
+#[derive(Debug, Clone)]
+struct WorkerRecord {
+    units_completed: u64,
+    keys_checked: u64,
+    last_seen: std::time::Instant,
+}
+
+#[derive(Default)]
+struct CoordinatorInner {
+    next_id: u64,
+    /// Next unassigned key per puzzle; wraps when the range is exhausted.
+    cursors: HashMap<u32, BigUint>,
+    /// Leased units and when they were (last) handed out.
+    outstanding: HashMap<u64, (WorkUnit, std::time::Instant)>,
+    completed_units: u64,
+    /// Ranges imported from work files, handed out before fresh slices.
+    imported: std::collections::VecDeque<(u32, BigUint, BigUint)>,
+    /// Completion totals keyed by worker name.
+    workers: HashMap<String, WorkerRecord>,
+}
+
+/// Slices puzzle ranges into work units for remote workers.
+pub struct Coordinator {
+    unit_keys: BigUint,
+    /// Leases older than this are considered abandoned and reassigned.
+    lease_ttl: Duration,
+    inner: Mutex<CoordinatorInner>,
+}
+
+impl Coordinator {
+    /// Build the coordinator when `CLUSTER_COORDINATOR` enables it.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if !config.cluster_coordinator {
+            return None;
+        }
+        Some(Self {
+            unit_keys: BigUint::from(config.work_unit_keys.max(1)),
+            lease_ttl: Duration::from_secs(config.lease_ttl_secs.max(1)),
+            inner: Mutex::new(CoordinatorInner {
+                next_id: 1,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Re-hand-out the oldest expired lease, if any; a crashed worker's
+    /// range goes back into circulation instead of being lost.
+    pub fn reclaim_expired(&self) -> Option<WorkUnit> {
+        let mut inner = self.inner.lock().unwrap();
+        let expired = inner
+            .outstanding
+            .iter()
+            .filter(|(_, (_, leased_at))| leased_at.elapsed() >= self.lease_ttl)
+            .min_by_key(|(_, (_, leased_at))| *leased_at)
+            .map(|(id, _)| *id)?;
+        let entry = inner.outstanding.get_mut(&expired).expect("id just found");
+        entry.1 = std::time::Instant::now();
+        tracing::info!(
+            "lease on unit {} expired; reassigning (puzzle #{})",
+            expired,
+            entry.0.puzzle_number
+        );
+        Some(entry.0.clone())
+    }
+
+    /// Hand out the next unit of the given puzzle's range.
+    pub fn lease_from(&self, puzzle_number: u32, start: &BigUint, end: &BigUint) -> WorkUnit {
+        let mut inner = self.inner.lock().unwrap();
+        let cursor = inner
+            .cursors
+            .entry(puzzle_number)
+            .or_insert_with(|| start.clone());
+        if &*cursor > end {
+            // Random-search fleets keep going; wrap back to the start.
+            tracing::info!("puzzle #{puzzle_number} range fully handed out; wrapping");
+            *cursor = start.clone();
+        }
+        let unit_start = cursor.clone();
+        let mut unit_end = &unit_start + &self.unit_keys - 1u32;
+        if &unit_end > end {
+            unit_end = end.clone();
+        }
+        *cursor = &unit_end + 1u32;
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let unit = WorkUnit {
+            id,
+            puzzle_number,
+            range_start: format!("{unit_start:x}"),
+            range_end: format!("{unit_end:x}"),
+        };
+        inner
+            .outstanding
+            .insert(id, (unit.clone(), std::time::Instant::now()));
+        unit
+    }
+
+    /// Queue imported ranges; they are handed out ahead of fresh slices.
+    pub fn queue_import(&self, puzzle_number: u32, ranges: Vec<(BigUint, BigUint)>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .imported
+            .extend(ranges.into_iter().map(|(s, e)| (puzzle_number, s, e)));
+    }
+
+    /// Hand out the next imported range slice, if any are queued. Ranges
+    /// larger than one work unit are split and the remainder re-queued.
+    pub fn lease_imported(&self) -> Option<WorkUnit> {
+        let mut inner = self.inner.lock().unwrap();
+        let (puzzle_number, start, end) = inner.imported.pop_front()?;
+        let mut unit_end = &start + &self.unit_keys - 1u32;
+        if unit_end < end {
+            inner
+                .imported
+                .push_front((puzzle_number, &unit_end + 1u32, end));
+        } else {
+            unit_end = end;
+        }
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let unit = WorkUnit {
+            id,
+            puzzle_number,
+            range_start: format!("{start:x}"),
+            range_end: format!("{unit_end:x}"),
+        };
+        inner
+            .outstanding
+            .insert(id, (unit.clone(), std::time::Instant::now()));
+        Some(unit)
+    }
+
+    /// Everything not yet handed out (or handed out and unfinished), for
+    /// export back to keyhunt/BitCrack-format work files.
+    pub fn export_ranges(&self, puzzles: &crate::puzzles::PuzzleCollection) -> Vec<(u32, BigUint, BigUint)> {
+        let inner = self.inner.lock().unwrap();
+        let mut ranges = Vec::new();
+        for (unit, _) in inner.outstanding.values() {
+            if let Ok((start, end)) = unit.range() {
+                ranges.push((unit.puzzle_number, start, end));
+            }
+        }
+        ranges.extend(inner.imported.iter().cloned());
+        for (&number, cursor) in &inner.cursors {
+            let Some(end) = puzzles.get(number).and_then(|p| p.range().ok().map(|r| r.1))
+            else {
+                continue;
+            };
+            if cursor <= &end {
+                ranges.push((number, cursor.clone(), end));
+            }
+        }
+        ranges.sort_by_key(|(number, _, _)| *number);
+        ranges
+    }
+
+    /// Mark a unit finished; `false` for ids we never handed out.
+    pub fn complete(&self, id: u64, worker: &str, keys_checked: u64) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let known = inner.outstanding.remove(&id).is_some();
+        if known {
+            inner.completed_units += 1;
+            let record = inner
+                .workers
+                .entry(worker.to_string())
+                .or_insert(WorkerRecord {
+                    units_completed: 0,
+                    keys_checked: 0,
+                    last_seen: std::time::Instant::now(),
+                });
+            record.units_completed += 1;
+            record.keys_checked += keys_checked;
+            record.last_seen = std::time::Instant::now();
+        }
+        known
+    }
+
+    /// One stats line per known worker, sorted by name, for `/stats`.
+    pub fn worker_lines(&self) -> Vec<String> {
+        let inner = self.inner.lock().unwrap();
+        let mut names: Vec<_> = inner.workers.keys().cloned().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let record = &inner.workers[&name];
+                format!(
+                    "{name}: {} unit(s), {} keys ({}s ago)",
+                    record.units_completed,
+                    record.keys_checked,
+                    record.last_seen.elapsed().as_secs(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Resolve the caller's worker name from its bearer token. `Ok(None)` when
+/// no tokens are configured (open endpoints); `Err` carries the rejection.
+fn authenticate(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+) -> Result<Option<String>, Json<Value>> {
+    if state.config.cluster_tokens.is_empty() {
+        return Ok(None);
+    }
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token.and_then(|t| state.config.cluster_tokens.get(t)) {
+        Some(name) => Ok(Some(name.clone())),
+        None => {
+            tracing::warn!("rejected cluster request with a missing or unknown token");
+            Err(Json(json!({ "ok": false, "error": "unauthorized" })))
+        }
+    }
+}
+
+async fn lease_handler(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+) -> Json<Value> {
+    let worker = match authenticate(&state, &headers) {
+        Ok(worker) => worker,
+        Err(reply) => return reply,
+    };
+    let Some(coordinator) = &state.coordinator else {
+        return Json(json!({ "ok": false, "error": "not a coordinator" }));
+    };
+    if let Some(worker) = &worker {
+        tracing::debug!("lease request from worker {worker}");
+    }
+    if let Some(unit) = coordinator.reclaim_expired() {
+        return Json(json!({ "ok": true, "unit": unit }));
+    }
+    if let Some(unit) = coordinator.lease_imported() {
+        tracing::debug!("leased imported unit {} (puzzle #{})", unit.id, unit.puzzle_number);
+        return Json(json!({ "ok": true, "unit": unit }));
+    }
+    let Some(puzzle) = scheduler::pick_puzzle(&state) else {
+        return Json(json!({ "ok": true, "unit": null }));
+    };
+    match puzzle.range() {
+        Ok((start, end)) => {
+            let unit = coordinator.lease_from(puzzle.number, &start, &end);
+            tracing::debug!(
+                "leased unit {} (puzzle #{}, {}..{})",
+                unit.id,
+                unit.puzzle_number,
+                unit.range_start,
+                unit.range_end
+            );
+            Json(json!({ "ok": true, "unit": unit }))
+        }
+        Err(err) => Json(json!({ "ok": false, "error": format!("{err:#}") })),
+    }
+}
+
+async fn complete_handler(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(report): Json<CompletionReport>,
+) -> Json<Value> {
+    let worker = match authenticate(&state, &headers) {
+        Ok(worker) => worker,
+        Err(reply) => return reply,
+    };
+    let Some(coordinator) = &state.coordinator else {
+        return Json(json!({ "ok": false, "error": "not a coordinator" }));
+    };
+    let worker = worker.unwrap_or_else(|| "remote".to_string());
+    let known = coordinator.complete(report.id, &worker, report.keys_checked);
+    if !known {
+        return Json(json!({ "ok": false, "error": "unknown work unit id" }));
+    }
+    state.stats.record_checked(report.keys_checked);
+    state
+        .metrics
+        .keys_checked
+        .with_label_values(&[worker.as_str()])
+        .inc_by(report.keys_checked);
+    Json(json!({ "ok": true }))
+}
+
+async fn match_handler(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(result): Json<CheckResult>,
+) -> Json<Value> {
+    let worker = match authenticate(&state, &headers) {
+        Ok(worker) => worker,
+        Err(reply) => return reply,
+    };
+    tracing::warn!(
+        "remote worker {} reported a match for puzzle #{}",
+        worker.as_deref().unwrap_or("(unauthenticated)"),
+        result.puzzle_number
+    );
+    state.stats.record_match();
+    state.metrics.matches.inc();
+    match state.notifier() {
+        Some(notifier) => {
+            scheduler::handle_match(&state, notifier, &result).await;
+            Json(json!({ "ok": true }))
+        }
+        None => Json(json!({ "ok": false, "error": "notifier not ready" })),
+    }
+}
+
+async fn stats_handler(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(mut stats): Json<NodeStats>,
+) -> Json<Value> {
+    match authenticate(&state, &headers) {
+        // Authenticated pushes report under the token's name, so one worker
+        // cannot spoof another's totals.
+        Ok(Some(name)) => stats.node = name,
+        Ok(None) => {}
+        Err(reply) => return reply,
+    }
+    state
+        .metrics
+        .node_keys_checked
+        .with_label_values(&[&stats.node])
+        .set(stats.keys_checked as f64);
+    state
+        .metrics
+        .node_rate
+        .with_label_values(&[&stats.node])
+        .set(stats.rate as f64);
+    state.record_node_stats(stats);
+    Json(json!({ "ok": true }))
+}
+
+/// Coordinator routes, merged into the embedded HTTP server's router.
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/cluster/lease", post(lease_handler))
+        .route("/cluster/complete", post(complete_handler))
+        .route("/cluster/match", post(match_handler))
+}
+
+/// Stats aggregation route; served whenever the HTTP server runs, so any
+/// instance can act as the fleet's primary.
+pub fn stats_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/cluster/stats", post(stats_handler))
+}
+
+/// Write the coordinator's unsearched ranges to a keyhunt/BitCrack-format
+/// work file under the data directory.
+pub fn export_work(state: &AppState) -> Result<std::path::PathBuf> {
+    let coordinator = state
+        .coordinator
+        .as_ref()
+        .context("not a coordinator (set CLUSTER_COORDINATOR=true)")?;
+    let ranges = coordinator.export_ranges(&state.puzzles());
+    let path = state.config.data_dir.join("work_units.txt");
+    crate::workfile::write_ranges(&path, &ranges)?;
+    Ok(path)
+}
+
+/// Push this instance's totals to the primary named by `STATS_PUSH_URL`.
+pub async fn push_node_stats(
+    client: &reqwest::Client,
+    url: &str,
+    state: &AppState,
+    rate: u64,
+) -> Result<()> {
+    let stats = NodeStats {
+        node: state.config.node_name.clone(),
+        keys_checked: state.stats.total_checked(),
+        rate,
+        matches_found: state.stats.total_matches(),
+        sessions_run: state.stats.total_sessions(),
+        uptime_secs: state.uptime_secs(),
+    };
+    post_json(
+        client,
+        format!("{}/cluster/stats", url.trim_end_matches('/')),
+        &stats,
+    )
+    .await
+}
+
+/// HTTP client for coordinator/primary calls; presents this instance's
+/// bearer token (`CLUSTER_TOKEN`) on every request when one is set.
+pub fn worker_client(config: &Config) -> reqwest::Client {
+    let mut headers = reqwest::header::HeaderMap::new();
+    if let Some(token) = &config.cluster_token {
+        if let Ok(mut value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")) {
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        } else {
+            tracing::warn!("CLUSTER_TOKEN contains characters invalid in a header; ignored");
+        }
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap_or_default()
+}
+
+async fn lease_unit(client: &reqwest::Client, base: &str) -> Result<Option<WorkUnit>> {
+    let reply: Value = client
+        .post(format!("{base}/cluster/lease"))
+        .send()
+        .await
+        .context("lease request failed")?
+        .error_for_status()
+        .context("coordinator rejected the lease request")?
+        .json()
+        .await
+        .context("lease reply was not JSON")?;
+    reply
+        .get("unit")
+        .filter(|unit| !unit.is_null())
+        .map(|unit| serde_json::from_value(unit.clone()).context("bad work unit in lease reply"))
+        .transpose()
+}
+
+async fn post_json(client: &reqwest::Client, url: String, body: &impl Serialize) -> Result<()> {
+    client
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .context("request failed")?
+        .error_for_status()
+        .context("coordinator rejected the request")?;
+    Ok(())
+}
+
+/// Worker loop: lease units from the coordinator and search them, one
+/// session each, until shutdown. Replaces the standalone scheduler loop.
+pub async fn run_worker(state: Arc<AppState>, notifier: Arc<Fanout>, base: String) {
+    let base = base.trim_end_matches('/').to_string();
+    let client = worker_client(&state.config);
+    let interval = Duration::from_secs(state.config.scheduler.session_interval_secs);
+    tracing::info!("worker mode: leasing work units from {base}");
+    loop {
+        state.heartbeat();
+        if state.shutdown_requested() {
+            return;
+        }
+        if !state.is_running() {
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+        let unit = match lease_unit(&client, &base).await {
+            Ok(Some(unit)) => unit,
+            Ok(None) => {
+                tracing::info!("coordinator has no work; sleeping");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("coordinator unreachable: {err:#}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        let puzzle = state.puzzles().get(unit.puzzle_number).cloned();
+        let Some(puzzle) = puzzle else {
+            tracing::warn!(
+                "leased unit {} names unknown puzzle #{}; skipping",
+                unit.id,
+                unit.puzzle_number
+            );
+            tokio::time::sleep(interval).await;
+            continue;
+        };
+        let (start, end) = match unit.range() {
+            Ok(range) => range,
+            Err(err) => {
+                tracing::warn!("bad work unit from coordinator: {err:#}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        tracing::info!(
+            "searching unit {} (puzzle #{}, {}..{})",
+            unit.id,
+            unit.puzzle_number,
+            unit.range_start,
+            unit.range_end
+        );
+        state.set_active_puzzle(Some(puzzle.number));
+        let before = state.stats.total_checked();
+        let matches = scheduler::run_session_in(&state, &puzzle, &start, &end).await;
+        state.mark_session();
+        let checked = state.stats.total_checked() - before;
+        for result in &matches {
+            scheduler::handle_match(&state, &notifier, result).await;
+            if let Err(err) = post_json(
+                &client,
+                format!("{base}/cluster/match"),
+                result,
+            )
+            .await
+            {
+                tracing::error!("failed to submit match to coordinator: {err:#}");
+            }
+        }
+        let report = CompletionReport {
+            id: unit.id,
+            keys_checked: checked,
+        };
+        if let Err(err) = post_json(&client, format!("{base}/cluster/complete"), &report).await {
+            tracing::warn!("failed to report unit completion: {err:#}");
+        }
+        if let Ok((puzzle_start, _)) = puzzle.range() {
+            state
+                .coverage
+                .mark(puzzle.number, state.coverage.bucket_of(&puzzle_start, &start));
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinator(unit_keys: u32, lease_ttl: Duration) -> Coordinator {
+        Coordinator {
+            unit_keys: BigUint::from(unit_keys),
+            lease_ttl,
+            inner: Mutex::new(CoordinatorInner {
+                next_id: 1,
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn leases_disjoint_units_and_clamps_to_range_end() {
+        let c = coordinator(0x40, Duration::from_secs(900));
+        let start = BigUint::from(0x80u32);
+        let end = BigUint::from(0xffu32);
+        let first = c.lease_from(8, &start, &end);
+        let second = c.lease_from(8, &start, &end);
+        assert_eq!((first.range_start.as_str(), first.range_end.as_str()), ("80", "bf"));
+        assert_eq!((second.range_start.as_str(), second.range_end.as_str()), ("c0", "ff"));
+        // The range is handed out; the next lease wraps to the start.
+        let third = c.lease_from(8, &start, &end);
+        assert_eq!(third.range_start, "80");
+    }
+
+    #[test]
+    fn completion_only_acknowledges_known_units() {
+        let c = coordinator(0x10, Duration::from_secs(900));
+        let unit = c.lease_from(8, &BigUint::from(0x80u32), &BigUint::from(0xffu32));
+        assert!(c.complete(unit.id, "w1", 16));
+        assert!(!c.complete(unit.id, "w1", 16));
+        assert!(!c.complete(999, "w1", 0));
+        // Acknowledged completions show up in the per-worker history.
+        let lines = c.worker_lines();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("w1: 1 unit(s), 16 keys"));
+    }
+
+    #[test]
+    fn expired_leases_are_reassigned_not_lost() {
+        let c = coordinator(0x10, Duration::ZERO);
+        let unit = c.lease_from(8, &BigUint::from(0x80u32), &BigUint::from(0xffu32));
+        let reclaimed = c.reclaim_expired().unwrap();
+        assert_eq!(reclaimed.id, unit.id);
+        assert_eq!(reclaimed.range_start, unit.range_start);
+        // Completion retires the lease for good.
+        assert!(c.complete(unit.id, "w1", 0));
+        let fresh = coordinator(0x10, Duration::from_secs(900));
+        fresh.lease_from(8, &BigUint::from(0x80u32), &BigUint::from(0xffu32));
+        assert!(fresh.reclaim_expired().is_none());
+    }
+}
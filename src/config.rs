@@ -0,0 +1,469 @@
+//! Environment-based configuration.
+//!
+//! Everything is read from the process environment (a `.env` file is loaded
+//! at startup if present). Missing values fall back to the defaults below;
+//! unparseable ones are collected by [`Config::from_env_checked`] so startup
+//! can refuse to run on a typo instead of silently using a default.
+
+use std::env;
+use std::path::PathBuf;
+
+/// Scheduler and search settings.
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Number of worker threads per solving session.
+    pub threads: usize,
+    /// Seconds between solving sessions.
+    pub session_interval_secs: u64,
+    /// Duration of each solving session in seconds.
+    pub session_duration_secs: u64,
+    /// Seconds between periodic stats reports.
+    pub stats_interval_secs: u64,
+    /// Smallest puzzle (bit length) to consider.
+    pub min_bits: u32,
+    /// Largest puzzle (bit length) to consider.
+    pub max_bits: u32,
+    /// Stride scan step: keys are `start + offset + thread_id + n*stride`
+    /// instead of random draws. `0` keeps random mode.
+    pub stride: u64,
+    /// This worker's lattice offset; give each machine a distinct value
+    /// below the stride so a fleet covers disjoint lattices.
+    pub stride_offset: u64,
+    /// Seconds between stride-position checkpoints written to
+    /// `PROGRESS_DIR`, from which a restart resumes the sweep. `0`
+    /// disables checkpointing.
+    pub checkpoint_interval_secs: u64,
+    /// Keys scanned sequentially from each random anchor by the hybrid
+    /// strategy (`HYBRID_WINDOW`); retunable at runtime with `/window`.
+    pub hybrid_window: u64,
+    /// Also check the λ/λ² endomorphism images and negations of every
+    /// candidate key (`ENDOMORPHISM`): five nearly-free extra candidates
+    /// per EC multiplication, landing anywhere on the curve rather than
+    /// inside the puzzle's range.
+    pub endomorphism: bool,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            threads: 4,
+            session_interval_secs: 300,
+            session_duration_secs: 60,
+            stats_interval_secs: 3600,
+            min_bits: 66,
+            max_bits: 80,
+            stride: 0,
+            stride_offset: 0,
+            checkpoint_interval_secs: 30,
+            hybrid_window: 1 << 20,
+            endomorphism: false,
+        }
+    }
+}
+
+/// Top-level application configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub telegram_token: Option<String>,
+    pub telegram_chat_id: Option<i64>,
+    /// User ids seeded as admins in the role store (`TELEGRAM_ADMINS`).
+    pub telegram_admins: Vec<i64>,
+    /// Directory holding all persisted artifacts (solutions, progress
+    /// cursors, snapshots). Relative file settings resolve beneath it.
+    pub data_dir: PathBuf,
+    pub puzzle_file: PathBuf,
+    pub solutions_file: PathBuf,
+    /// Directory holding per-puzzle progress cursor files.
+    pub progress_dir: PathBuf,
+    /// Optional CSV file receiving one stats row per stats interval.
+    pub stats_csv_file: Option<PathBuf>,
+    /// Imported dead zones (`SEARCHED_RANGES_FILE`): JSON or CSV list of
+    /// already-searched sub-ranges every search mode skips.
+    pub searched_ranges_file: Option<PathBuf>,
+    /// Extra target list (`TARGET_SET_FILE`): one address per line; every
+    /// derived key is also checked against this set alongside the puzzles.
+    pub target_set_file: Option<PathBuf>,
+    /// Vanity hunt (`VANITY_PREFIXES`): comma-separated address prefixes
+    /// (`1Love`, `bc1q…`, or their `NETWORK` equivalents) every derived
+    /// key's addresses are tested against alongside the puzzles.
+    pub vanity_prefixes: Vec<String>,
+    /// Megabytes for the in-memory duplicate-draw bloom filter
+    /// (`BLOOM_SIZE_MB`); `0` disables it.
+    pub bloom_size_mb: u64,
+    /// Address for the embedded HTTP server (health probes); disabled when
+    /// unset.
+    pub http_listen: Option<std::net::SocketAddr>,
+    /// Unix domain socket for the local control interface; disabled when
+    /// unset.
+    pub control_socket: Option<PathBuf>,
+    /// Push metrics exporter selection: `influxdb` or `statsd`.
+    pub metrics_exporter: Option<String>,
+    /// Full InfluxDB write endpoint (including bucket query parameters).
+    pub influx_write_url: Option<String>,
+    /// InfluxDB API token, sent as `Authorization: Token …`.
+    pub influx_token: Option<String>,
+    /// statsd `host:port` for UDP gauge datagrams.
+    pub statsd_addr: Option<String>,
+    /// Block explorer backend: `blockstream`, `mempool`, `esplora` or `core`.
+    pub chain_backend: Option<String>,
+    /// Base URL of a self-hosted Esplora instance (`CHAIN_BACKEND=esplora`).
+    pub esplora_url: Option<String>,
+    /// Bitcoin Core JSON-RPC endpoint (`CHAIN_BACKEND=core`).
+    pub core_rpc_url: Option<String>,
+    /// Seconds between checks that eligible prize addresses still hold a
+    /// balance (`SOLVED_SCAN_INTERVAL_SECS`); puzzles found empty were
+    /// swept by someone else and are marked solved. `0` disables the
+    /// scan. Needs a chain backend.
+    pub solved_scan_interval_secs: u64,
+    pub core_rpc_user: Option<String>,
+    pub core_rpc_password: Option<String>,
+    /// Destination address for the automatic sweep of a solved puzzle's
+    /// balance; the sweep builder is disabled when unset.
+    pub sweep_address: Option<String>,
+    /// Fee rate for sweep transactions, in sat/vB.
+    pub sweep_fee_rate: u64,
+    /// Actually broadcast the sweep via the chain backend. Off by default:
+    /// without it the signed transaction is only written to `DATA_DIR`.
+    pub sweep_broadcast: bool,
+    /// Hand out work units to remote workers over the HTTP server.
+    pub cluster_coordinator: bool,
+    /// Accepted worker bearer tokens, mapped to worker names. Empty means
+    /// the cluster endpoints are open (loopback/trusted-network setups).
+    pub cluster_tokens: std::collections::HashMap<String, String>,
+    /// Base URL of a coordinator to lease work units from (worker mode).
+    pub coordinator_url: Option<String>,
+    /// Bearer token this instance presents to the coordinator/primary.
+    pub cluster_token: Option<String>,
+    /// Keys per work unit handed to remote workers.
+    pub work_unit_keys: u64,
+    /// Seconds before an uncompleted work-unit lease is reassigned.
+    pub lease_ttl_secs: u64,
+    /// Shared Redis work queue (`redis://…`); disables local scheduling.
+    pub redis_url: Option<String>,
+    /// Prefix for all Redis keys this bot touches.
+    pub redis_namespace: String,
+    /// Peer instances to exchange coverage summaries with (comma-separated
+    /// base URLs).
+    pub gossip_peers: Vec<String>,
+    /// Seconds between gossip rounds.
+    pub gossip_interval_secs: u64,
+    /// BitCrack/keyhunt-style pool to fetch assigned ranges from.
+    pub pool_url: Option<String>,
+    /// Which puzzle the pool is sweeping (required with `pool_url`).
+    pub pool_puzzle: Option<u32>,
+    /// Base URL of a primary instance to push stats to.
+    pub stats_push_url: Option<String>,
+    /// How this instance identifies itself to the fleet.
+    pub node_name: String,
+    /// Comma-separated price provider fallback order (`none` disables).
+    pub price_providers: Option<String>,
+    /// Fiat currency for price lookups.
+    pub price_fiat: String,
+    /// Seconds a fetched price stays fresh.
+    pub price_cache_secs: u64,
+    /// Throughput watchdog: alert when the rolling rate stays below this
+    /// fraction of the baseline. Zero disables the watchdog.
+    pub watchdog_fraction: f64,
+    /// How long the rate must stay low before the watchdog alerts.
+    pub watchdog_sustain_secs: u64,
+    /// Seed for deterministic key draws (`SEARCH_SEED`): each worker runs
+    /// a ChaCha RNG seeded with this plus its thread id, so runs are
+    /// reproducible and differently-seeded machines provably diverge.
+    /// Unset keeps the OS-seeded thread RNG.
+    pub search_seed: Option<u64>,
+    /// Solver for puzzles with a known public key (`PUBKEY_SOLVER`):
+    /// `kangaroo` (default, near-zero memory) or `bsgs` (deterministic,
+    /// table bounded by `BSGS_MEMORY_MB`).
+    pub pubkey_solver: String,
+    pub scheduler: SchedulerConfig,
+}
+
+/// Where persisted artifacts go when `DATA_DIR` is unset: `data` relative
+/// to the working directory, except on Windows where services start in
+/// `%SystemRoot%\System32` and a per-user location is the only sane default.
+pub(crate) fn default_data_dir() -> PathBuf {
+    #[cfg(windows)]
+    if let Ok(base) = env::var("LOCALAPPDATA") {
+        return PathBuf::from(base).join("btc_lotto_puzzles_bot");
+    }
+    PathBuf::from("data")
+}
+
+/// Parse an env var, recording (not swallowing) malformed values so startup
+/// can refuse to run with a typo'd configuration.
+fn env_parse<T: std::str::FromStr>(key: &str, default: T, problems: &mut Vec<String>) -> T {
+    match env::var(key) {
+        Ok(raw) => match raw.parse() {
+            Ok(value) => value,
+            Err(_) => {
+                problems.push(format!("{key}={raw:?} does not parse"));
+                default
+            }
+        },
+        Err(_) => default,
+    }
+}
+
+/// Like [`env_parse`] for settings without a default.
+fn env_parse_opt<T: std::str::FromStr>(key: &str, problems: &mut Vec<String>) -> Option<T> {
+    match env::var(key) {
+        Ok(raw) => match raw.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                problems.push(format!("{key}={raw:?} does not parse"));
+                None
+            }
+        },
+        Err(_) => None,
+    }
+}
+
+impl Config {
+    /// Build the configuration from the process environment, ignoring any
+    /// problems. For tests that construct a config to mutate; the bot goes
+    /// through [`Config::from_env_checked`].
+    #[cfg(test)]
+    pub fn from_env() -> Self {
+        Self::from_env_checked().0
+    }
+
+    /// Build the configuration from the process environment, collecting
+    /// every malformed value instead of silently falling back to defaults.
+    pub fn from_env_checked() -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let defaults = SchedulerConfig::default();
+        let data_dir = env::var("DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| default_data_dir());
+        // Persisted artifacts live under DATA_DIR unless an absolute path is
+        // given; the puzzle file is read-only input and stays CWD-relative.
+        let under_data = |value: Result<String, env::VarError>, default: &str| -> PathBuf {
+            let path = value.map(PathBuf::from).unwrap_or_else(|_| PathBuf::from(default));
+            if path.is_absolute() {
+                path
+            } else {
+                data_dir.join(path)
+            }
+        };
+        let config = Self {
+            telegram_token: env::var("TELEGRAM_BOT_TOKEN").ok(),
+            telegram_chat_id: env_parse_opt("TELEGRAM_CHAT_ID", &mut problems),
+            telegram_admins: env::var("TELEGRAM_ADMINS")
+                .map(|v| {
+                    v.split(',')
+                        .filter_map(|id| id.trim().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            puzzle_file: env::var("PUZZLE_FILE")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("puzzles.json")),
+            solutions_file: under_data(env::var("SOLUTIONS_FILE"), "puzzle_solutions.log"),
+            progress_dir: under_data(env::var("PROGRESS_DIR"), "progress"),
+            stats_csv_file: env::var("STATS_CSV_FILE")
+                .ok()
+                .map(|v| under_data(Ok(v), "")),
+            // Read-only input like the puzzle file, so CWD-relative.
+            searched_ranges_file: env::var("SEARCHED_RANGES_FILE").ok().map(PathBuf::from),
+            target_set_file: env::var("TARGET_SET_FILE").ok().map(PathBuf::from),
+            vanity_prefixes: env::var("VANITY_PREFIXES")
+                .map(|raw| {
+                    raw.split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            bloom_size_mb: env_parse("BLOOM_SIZE_MB", 16, &mut problems),
+            http_listen: env_parse_opt("HTTP_LISTEN", &mut problems),
+            control_socket: env::var("CONTROL_SOCKET")
+                .ok()
+                .map(|v| under_data(Ok(v), "")),
+            metrics_exporter: env::var("METRICS_EXPORTER").ok(),
+            influx_write_url: env::var("INFLUX_WRITE_URL").ok(),
+            influx_token: env::var("INFLUX_TOKEN").ok(),
+            statsd_addr: env::var("STATSD_ADDR").ok(),
+            chain_backend: env::var("CHAIN_BACKEND").ok(),
+            esplora_url: env::var("ESPLORA_URL").ok(),
+            core_rpc_url: env::var("CORE_RPC_URL").ok(),
+            solved_scan_interval_secs: env_parse("SOLVED_SCAN_INTERVAL_SECS", 3600, &mut problems),
+            core_rpc_user: env::var("CORE_RPC_USER").ok(),
+            core_rpc_password: env::var("CORE_RPC_PASSWORD").ok(),
+            sweep_address: env::var("SWEEP_ADDRESS").ok(),
+            sweep_fee_rate: env_parse("SWEEP_FEE_RATE", 25, &mut problems),
+            sweep_broadcast: env_parse("SWEEP_BROADCAST", false, &mut problems),
+            cluster_coordinator: env_parse("CLUSTER_COORDINATOR", false, &mut problems),
+            // CLUSTER_TOKENS is comma-separated name:token pairs.
+            cluster_tokens: env::var("CLUSTER_TOKENS")
+                .map(|v| {
+                    v.split(',')
+                        .filter_map(|entry| {
+                            let (name, token) = entry.trim().split_once(':')?;
+                            (!name.is_empty() && !token.is_empty())
+                                .then(|| (token.to_string(), name.to_string()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            coordinator_url: env::var("COORDINATOR_URL").ok(),
+            cluster_token: env::var("CLUSTER_TOKEN").ok(),
+            work_unit_keys: env_parse("WORK_UNIT_KEYS", 1 << 22, &mut problems),
+            lease_ttl_secs: env_parse("LEASE_TTL_SECS", 900, &mut problems),
+            redis_url: env::var("REDIS_URL").ok(),
+            redis_namespace: env::var("REDIS_NAMESPACE")
+                .unwrap_or_else(|_| "btclotto".to_string()),
+            gossip_peers: env::var("GOSSIP_PEERS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            gossip_interval_secs: env_parse("GOSSIP_INTERVAL_SECS", 300, &mut problems),
+            pool_url: env::var("POOL_URL").ok(),
+            pool_puzzle: env_parse_opt("POOL_PUZZLE", &mut problems),
+            stats_push_url: env::var("STATS_PUSH_URL").ok(),
+            node_name: env::var("NODE_NAME").unwrap_or_else(|_| {
+                hostname::get()
+                    .ok()
+                    .and_then(|h| h.into_string().ok())
+                    .unwrap_or_else(|| "node".to_string())
+            }),
+            price_providers: env::var("PRICE_PROVIDERS").ok(),
+            price_fiat: env::var("PRICE_FIAT").unwrap_or_else(|_| "usd".to_string()),
+            price_cache_secs: env_parse("PRICE_CACHE_SECS", 300, &mut problems),
+            watchdog_fraction: env_parse("WATCHDOG_FRACTION", 0.5, &mut problems),
+            watchdog_sustain_secs: env_parse("WATCHDOG_SUSTAIN_SECS", 600, &mut problems),
+            search_seed: env_parse_opt("SEARCH_SEED", &mut problems),
+            pubkey_solver: env::var("PUBKEY_SOLVER").unwrap_or_else(|_| "kangaroo".to_string()),
+            data_dir,
+            scheduler: SchedulerConfig {
+                threads: env_parse("THREADS", defaults.threads, &mut problems),
+                session_interval_secs: env_parse("SESSION_INTERVAL_SECS", defaults.session_interval_secs, &mut problems),
+                session_duration_secs: env_parse("SESSION_DURATION_SECS", defaults.session_duration_secs, &mut problems),
+                stats_interval_secs: env_parse("STATS_INTERVAL_SECS", defaults.stats_interval_secs, &mut problems),
+                min_bits: env_parse("MIN_BITS", defaults.min_bits, &mut problems),
+                max_bits: env_parse("MAX_BITS", defaults.max_bits, &mut problems),
+                stride: env_parse("STRIDE", defaults.stride, &mut problems),
+                stride_offset: env_parse("STRIDE_OFFSET", defaults.stride_offset, &mut problems),
+                checkpoint_interval_secs: env_parse(
+                    "CHECKPOINT_INTERVAL_SECS",
+                    defaults.checkpoint_interval_secs,
+                    &mut problems,
+                ),
+                hybrid_window: env_parse("HYBRID_WINDOW", defaults.hybrid_window, &mut problems),
+                endomorphism: env_parse("ENDOMORPHISM", defaults.endomorphism, &mut problems),
+            },
+        };
+        (config, problems)
+    }
+
+    /// Semantic cross-checks over a parsed configuration. Returns one line
+    /// per problem; an empty result means the settings are coherent.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let s = &self.scheduler;
+        if s.threads == 0 {
+            problems.push("THREADS is zero; no worker would run".into());
+        }
+        if s.session_duration_secs == 0 {
+            problems.push("SESSION_DURATION_SECS is zero; sessions would do nothing".into());
+        }
+        if s.session_interval_secs == 0 {
+            problems.push("SESSION_INTERVAL_SECS is zero; the scheduler would spin".into());
+        }
+        if s.min_bits > s.max_bits {
+            problems.push(format!(
+                "MIN_BITS {} is above MAX_BITS {}",
+                s.min_bits, s.max_bits
+            ));
+        }
+        if s.stride > 0 && s.stride_offset >= s.stride {
+            problems.push(format!(
+                "STRIDE_OFFSET {} must be below STRIDE {}; equal offsets modulo the stride scan the same lattice",
+                s.stride_offset, s.stride
+            ));
+        }
+        if s.stride == 0 && s.stride_offset > 0 {
+            problems.push("STRIDE_OFFSET without STRIDE has no effect".into());
+        }
+        if s.hybrid_window == 0 {
+            problems.push("HYBRID_WINDOW is zero; the hybrid strategy would re-anchor every key".into());
+        }
+        if !matches!(self.pubkey_solver.as_str(), "kangaroo" | "bsgs") {
+            problems.push(format!(
+                "PUBKEY_SOLVER {:?} is not one of kangaroo, bsgs",
+                self.pubkey_solver,
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.watchdog_fraction) {
+            problems.push(format!(
+                "WATCHDOG_FRACTION {} is outside 0..=1",
+                self.watchdog_fraction,
+            ));
+        }
+        let modes = [
+            self.coordinator_url.is_some(),
+            self.pool_url.is_some(),
+            self.redis_url.is_some(),
+        ];
+        if modes.iter().filter(|m| **m).count() > 1 {
+            problems.push(
+                "COORDINATOR_URL, POOL_URL and REDIS_URL are mutually exclusive work sources"
+                    .into(),
+            );
+        }
+        if self.pool_url.is_some() && self.pool_puzzle.is_none() {
+            problems.push("POOL_URL needs POOL_PUZZLE".into());
+        }
+        if self.telegram_token.is_some() != self.telegram_chat_id.is_some() {
+            problems.push("TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID must be set together".into());
+        }
+        if self.sweep_broadcast && self.sweep_address.is_none() {
+            problems.push("SWEEP_BROADCAST=true without a SWEEP_ADDRESS".into());
+        }
+        if self.sweep_address.is_some() && self.chain_backend.is_none() {
+            problems.push("SWEEP_ADDRESS needs CHAIN_BACKEND for UTXO lookups".into());
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_aggregates_every_problem() {
+        let mut config = Config::from_env();
+        config.telegram_token = None;
+        config.telegram_chat_id = None;
+        config.scheduler.threads = 0;
+        config.scheduler.min_bits = 80;
+        config.scheduler.max_bits = 66;
+        config.pool_url = Some("http://pool".into());
+        config.pool_puzzle = None;
+        config.coordinator_url = Some("http://coord".into());
+        let problems = config.validate();
+        assert!(problems.iter().any(|p| p.contains("THREADS")));
+        assert!(problems.iter().any(|p| p.contains("MIN_BITS")));
+        assert!(problems.iter().any(|p| p.contains("POOL_PUZZLE")));
+        assert!(problems.iter().any(|p| p.contains("mutually exclusive")));
+    }
+
+    #[test]
+    fn coherent_settings_validate_clean() {
+        let mut config = Config::from_env();
+        config.telegram_token = None;
+        config.telegram_chat_id = None;
+        config.coordinator_url = None;
+        config.pool_url = None;
+        config.redis_url = None;
+        config.sweep_address = None;
+        config.sweep_broadcast = false;
+        assert!(config.validate().is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+//! Local control interface over a Unix domain socket.
+//!
+//! Accepts one JSON object per line (`{"command": "status"}`) and replies
+//! with one JSON object per line, so shell scripts and systemd units can
+//! drive a running instance without any network exposure:
+//!
+//! ```sh
+//! echo '{"command":"stop"}' | socat - UNIX-CONNECT:data/control.sock
+//! ```
+//!
+//! Commands: `status`, `start`, `stop`, `reload` (re-reads the puzzle file),
+//! `export-work` (coordinator only: dump unsearched ranges to a work file).
+//! Enabled by `CONTROL_SOCKET`; the socket is created with 0600 permissions.
+
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use std::sync::Arc;
+
+#[cfg(unix)]
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+#[cfg(unix)]
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::state::AppState;
+
+/// Run one command against the shared state, producing the reply object.
+/// Shared with the stdio JSON-RPC mode, which speaks the same commands.
+pub fn execute(state: &AppState, command: &str) -> Value {
+    let reply = dispatch(state, command);
+    // Mutating commands leave an audit trail; the socket and stdio carry
+    // no user identity, so the actor is just "local".
+    if matches!(command, "start" | "stop" | "reload" | "export-work") {
+        let detail = reply
+            .get("message")
+            .or_else(|| reply.get("error"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if let Err(err) = state.audit.record("local", command, &detail) {
+            tracing::warn!("failed to record audit entry: {err:#}");
+        }
+    }
+    reply
+}
+
+fn dispatch(state: &AppState, command: &str) -> Value {
+    match command {
+        "status" => json!({
+            "ok": true,
+            "running": state.is_running(),
+            "uptime_secs": state.uptime_secs(),
+            "keys_checked": state.stats.total_checked(),
+            "matches_found": state.stats.total_matches(),
+            "sessions_run": state.stats.total_sessions(),
+            "focused_puzzle": state.focused_puzzle(),
+            "active_puzzle": state.active_puzzle(),
+        }),
+        "start" => {
+            state.set_running(true);
+            json!({ "ok": true, "message": "solver started" })
+        }
+        "stop" => {
+            state.set_running(false);
+            json!({ "ok": true, "message": "solver stopped" })
+        }
+        "reload" => match state.reload_puzzles() {
+            Ok(summary) => json!({ "ok": true, "message": summary }),
+            Err(err) => json!({ "ok": false, "error": format!("{err:#}") }),
+        },
+        "export-work" => match crate::cluster::export_work(state) {
+            Ok(path) => json!({
+                "ok": true,
+                "message": format!("work units written to {}", path.display()),
+            }),
+            Err(err) => json!({ "ok": false, "error": format!("{err:#}") }),
+        },
+        other => json!({
+            "ok": false,
+            "error": format!(
+                "unknown command {other:?}; expected status, start, stop, reload or export-work"
+            ),
+        }),
+    }
+}
+
+#[cfg(unix)]
+async fn handle_connection(state: Arc<AppState>, stream: UnixStream) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => match request.get("command").and_then(Value::as_str) {
+                Some(command) => execute(&state, command),
+                None => json!({ "ok": false, "error": "missing \"command\" field" }),
+            },
+            Err(err) => json!({ "ok": false, "error": format!("invalid JSON: {err}") }),
+        };
+        writer.write_all(reply.to_string().as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+    }
+    Ok(())
+}
+
+/// Listen on the control socket until the process exits. Unix only — the
+/// Windows build keeps `execute` (stdio RPC speaks the same commands) but
+/// has no socket.
+#[cfg(unix)]
+pub async fn serve(state: Arc<AppState>, path: &Path) -> Result<()> {
+    // A stale socket from a previous run blocks the bind.
+    let _ = std::fs::remove_file(path);
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("binding {}", path.display()))?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting permissions on {}", path.display()))?;
+    }
+    tracing::info!("control socket listening on {}", path.display());
+    loop {
+        let (stream, _) = listener.accept().await.context("control socket accept")?;
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(state, stream).await {
+                tracing::debug!("control connection ended: {err:#}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::puzzles::PuzzleCollection;
+    use crate::solutions::SolutionStore;
+
+    fn test_state() -> AppState {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::from_env();
+        config.telegram_token = None;
+        config.solutions_file = dir.path().join("solutions.log");
+        config.puzzle_file = dir.path().join("puzzles.json");
+        config.data_dir = dir.path().to_path_buf();
+        std::fs::write(&config.puzzle_file, "[]").unwrap();
+        let puzzles = PuzzleCollection::load(&config.puzzle_file).unwrap();
+        let solutions = SolutionStore::open_from_env(&config.solutions_file).unwrap();
+        AppState::new(config, puzzles, solutions)
+    }
+
+    #[test]
+    fn stop_and_status_round_trip() {
+        let state = test_state();
+        let reply = execute(&state, "stop");
+        assert_eq!(reply["ok"], true);
+        let status = execute(&state, "status");
+        assert_eq!(status["running"], false);
+    }
+
+    #[test]
+    fn unknown_command_is_an_error() {
+        let state = test_state();
+        let reply = execute(&state, "selfdestruct");
+        assert_eq!(reply["ok"], false);
+    }
+}
@@ -0,0 +1,81 @@
+//! Unix daemonization and the PID-file lifecycle.
+//!
+//! `--daemon` detaches the process with the classic double fork before the
+//! async runtime starts, points stdio at `/dev/null`, defaults `LOG_DIR`
+//! so the logs land in files instead of the void, and records the PID
+//! under `DATA_DIR/bot.pid`. The `stop` subcommand reads that file back
+//! and sends `SIGTERM`, which the main loop treats like Ctrl-C.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+/// Where the PID file lives; reads `DATA_DIR` directly because the full
+/// config is not loaded yet when daemonizing.
+pub fn pid_path() -> PathBuf {
+    std::env::var("DATA_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("data"))
+        .join("bot.pid")
+}
+
+/// Detach from the terminal and write the PID file. Must run before the
+/// tokio runtime is built — forking a threaded runtime is undefined.
+pub fn daemonize() -> Result<()> {
+    // Double fork: the first child leads a new session, the second can
+    // never reacquire a controlling terminal.
+    match unsafe { libc::fork() } {
+        -1 => bail!("fork failed: {}", std::io::Error::last_os_error()),
+        0 => {}
+        _ => std::process::exit(0),
+    }
+    if unsafe { libc::setsid() } == -1 {
+        bail!("setsid failed: {}", std::io::Error::last_os_error());
+    }
+    match unsafe { libc::fork() } {
+        -1 => bail!("fork failed: {}", std::io::Error::last_os_error()),
+        0 => {}
+        _ => std::process::exit(0),
+    }
+    // Without a terminal, un-redirected logs would vanish with stdout.
+    if std::env::var("LOG_DIR").is_err() {
+        std::env::set_var("LOG_DIR", "logs");
+    }
+    let null = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/null")
+        .context("opening /dev/null")?;
+    for fd in 0..=2 {
+        if unsafe { libc::dup2(std::os::unix::io::AsRawFd::as_raw_fd(&null), fd) } == -1 {
+            bail!("redirecting fd {fd}: {}", std::io::Error::last_os_error());
+        }
+    }
+    let path = pid_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    std::fs::write(&path, format!("{}\n", std::process::id()))
+        .with_context(|| format!("writing {}", path.display()))?;
+    Ok(())
+}
+
+/// Remove the PID file on clean shutdown.
+pub fn cleanup() {
+    let _ = std::fs::remove_file(pid_path());
+}
+
+/// Signal the instance recorded in the PID file to shut down.
+pub fn stop() -> Result<String> {
+    let path = pid_path();
+    let pid: i32 = std::fs::read_to_string(&path)
+        .with_context(|| format!("reading {} (is the daemon running?)", path.display()))?
+        .trim()
+        .parse()
+        .with_context(|| format!("{} does not hold a PID", path.display()))?;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == -1 {
+        let _ = std::fs::remove_file(&path);
+        bail!("no process with PID {pid}; removed the stale PID file");
+    }
+    Ok(format!("sent SIGTERM to PID {pid}"))
+}
@@ -0,0 +1,99 @@
+//! SMTP email notifications for critical events.
+//!
+//! Configured entirely from the environment (like the solutions passphrase):
+//! `SMTP_HOST`, `SMTP_USERNAME`, `SMTP_PASSWORD` (an app password works),
+//! `EMAIL_FROM` and `EMAIL_TO`; `SMTP_PORT` defaults to 587 with STARTTLS,
+//! or set `SMTP_TLS=implicit` for TLS-on-connect (usually port 465).
+//! Disabled when `SMTP_HOST` is unset. A solve always goes here in addition
+//! to Telegram — an inbox survives a revoked bot token.
+
+use anyhow::{Context, Result};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+/// A configured SMTP sender and its fixed from/to addresses.
+#[derive(Clone)]
+pub struct EmailNotifier {
+    mailer: SmtpTransport,
+    from: Mailbox,
+    to: Mailbox,
+}
+
+impl EmailNotifier {
+    /// Build the notifier from the environment; `None` when `SMTP_HOST` is
+    /// unset, `Err` when set but the rest of the configuration is broken.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(host) = std::env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+        let from: Mailbox = std::env::var("EMAIL_FROM")
+            .context("EMAIL_FROM is required with SMTP_HOST")?
+            .parse()
+            .context("EMAIL_FROM is not a valid address")?;
+        let to: Mailbox = std::env::var("EMAIL_TO")
+            .context("EMAIL_TO is required with SMTP_HOST")?
+            .parse()
+            .context("EMAIL_TO is not a valid address")?;
+        let implicit_tls = std::env::var("SMTP_TLS").is_ok_and(|v| v == "implicit");
+        let mut builder = if implicit_tls {
+            SmtpTransport::relay(&host).context("building SMTP TLS transport")?
+        } else {
+            SmtpTransport::starttls_relay(&host).context("building SMTP STARTTLS transport")?
+        };
+        if let Ok(port) = std::env::var("SMTP_PORT") {
+            builder = builder.port(port.parse().context("SMTP_PORT is not a number")?);
+        }
+        if let (Ok(user), Ok(pass)) = (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD"))
+        {
+            builder = builder.credentials(Credentials::new(user, pass));
+        }
+        Ok(Some(Self {
+            mailer: builder.build(),
+            from,
+            to,
+        }))
+    }
+
+    /// Send one plain-text email; the blocking SMTP exchange runs off the
+    /// async runtime.
+    pub async fn notify(&self, subject: &str, body: &str) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .subject(subject)
+            .body(body.to_string())
+            .context("building email")?;
+        let mailer = self.mailer.clone();
+        tokio::task::spawn_blocking(move || mailer.send(&message))
+            .await
+            .context("email send task failed")?
+            .context("SMTP send failed")?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::notify::Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn retry_attempts(&self) -> u32 {
+        2
+    }
+
+    /// Email only carries critical events; routine reports stay out of the
+    /// inbox.
+    async fn send(&self, event: &crate::notify::Event) -> Result<()> {
+        match event {
+            crate::notify::Event::Solve(result) => {
+                let subject = format!("Puzzle #{} solved", result.puzzle_number);
+                self.notify(&subject, &crate::scheduler::solve_message(result))
+                    .await
+            }
+            crate::notify::Event::Alert(text) => self.notify("Solver alert", text).await,
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Exhaustive sequential sweep for tiny puzzle ranges.
+//!
+//! Random search can never prove a range empty. For sanity-check puzzles
+//! up to [`MAX_BITS`] this mode enumerates every key in order instead,
+//! resuming from the per-puzzle progress cursor across sessions and
+//! restarts. When the cursor passes `range_end` without a match the puzzle
+//! is marked exhausted in state — the scheduler stops picking it — and an
+//! alert goes out that the target address is not derivable from the range,
+//! which is the whole point: a clean end-to-end check of the derivation
+//! pipeline. Selected per puzzle with `"strategy": "exhaustive"` in the
+//! puzzle file.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+use num_traits::One;
+
+use crate::checker::{self, CheckResult};
+use crate::keygen;
+use crate::notify::Event;
+use crate::puzzles::Puzzle;
+use crate::state::AppState;
+
+/// Largest range an exhaustive sweep accepts. 2^32 keys finish in hours
+/// on ordinary hardware; anything bigger belongs to the probabilistic
+/// modes.
+pub const MAX_BITS: u32 = 32;
+
+/// Keys a worker claims from the shared cursor at a time. Small enough
+/// that finishing the in-flight chunk after a stop request takes tens of
+/// milliseconds, which keeps the cursor exact: everything below it has
+/// been checked.
+const CHUNK_KEYS: u64 = 4096;
+
+/// Run one fixed-duration exhaustive session, resuming from the puzzle's
+/// progress cursor.
+pub async fn run_session(state: &Arc<AppState>, puzzle: &Puzzle) -> Vec<CheckResult> {
+    let (range_start, range_end) = match puzzle.range() {
+        Ok(range) => range,
+        Err(err) => {
+            tracing::error!("bad puzzle range: {err:#}");
+            return Vec::new();
+        }
+    };
+    if puzzle.bits() > MAX_BITS {
+        tracing::error!(
+            "puzzle #{} is {} bits; exhaustive strategy is capped at {} — fix the puzzle file",
+            puzzle.number,
+            puzzle.bits(),
+            MAX_BITS
+        );
+        return Vec::new();
+    }
+    let cursor = Arc::new(Mutex::new(resume_position(state, puzzle, &range_start, &range_end)));
+    let duration = std::time::Duration::from_secs(state.config.scheduler.session_duration_secs);
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut handles = Vec::new();
+    for _ in 0..state.config.scheduler.threads {
+        let state = Arc::clone(state);
+        let puzzle = puzzle.clone();
+        let cursor = Arc::clone(&cursor);
+        let range_end = range_end.clone();
+        let stop = Arc::clone(&stop);
+        handles.push(tokio::task::spawn_blocking(move || {
+            worker_loop(&state, &puzzle, &cursor, &range_end, &stop)
+        }));
+    }
+    tokio::time::sleep(duration).await;
+    stop.store(true, Ordering::Relaxed);
+    let mut matches = Vec::new();
+    for handle in handles {
+        match handle.await {
+            Ok(Ok(found)) => matches.extend(found),
+            Ok(Err(err)) => tracing::warn!("exhaustive worker failed: {err:#}"),
+            Err(err) => tracing::warn!("exhaustive worker panicked: {err}"),
+        }
+    }
+    let position = cursor.lock().unwrap().clone();
+    save_position(state, puzzle, &range_start, &range_end, &position);
+    if matches.is_empty() && position > range_end {
+        report_exhausted(state, puzzle).await;
+    }
+    matches
+}
+
+/// The next unchecked key: the saved cursor position when one exists for
+/// this exact range, otherwise the bottom of the range.
+fn resume_position(
+    state: &AppState,
+    puzzle: &Puzzle,
+    range_start: &BigUint,
+    range_end: &BigUint,
+) -> BigUint {
+    let cursors = state.cursors.lock().unwrap();
+    match cursors.get(&puzzle.number) {
+        Some(cursor)
+            if cursor.range_start == *range_start
+                && cursor.range_end == *range_end
+                && cursor.position >= *range_start =>
+        {
+            tracing::info!(
+                "resuming exhaustive sweep of puzzle #{} at {:x}",
+                puzzle.number,
+                cursor.position
+            );
+            cursor.position.clone()
+        }
+        Some(_) => {
+            tracing::warn!(
+                "progress cursor for puzzle #{} covers a different range; starting fresh",
+                puzzle.number
+            );
+            range_start.clone()
+        }
+        None => range_start.clone(),
+    }
+}
+
+/// Store the position in the shared cursor map, from which it reaches the
+/// progress file (and the community-tool formats) on shutdown.
+fn save_position(
+    state: &AppState,
+    puzzle: &Puzzle,
+    range_start: &BigUint,
+    range_end: &BigUint,
+    position: &BigUint,
+) {
+    let mut cursors = state.cursors.lock().unwrap();
+    let entry = cursors
+        .entry(puzzle.number)
+        .or_insert_with(|| crate::progress::ProgressCursor {
+            puzzle_number: puzzle.number,
+            range_start: range_start.clone(),
+            range_end: range_end.clone(),
+            position: position.clone(),
+            format: crate::progress::ProgressFormat::Native,
+        });
+    entry.range_start = range_start.clone();
+    entry.range_end = range_end.clone();
+    entry.position = position.clone();
+}
+
+/// Mark the puzzle exhausted and tell the operator the address is not
+/// derivable from the advertised range.
+async fn report_exhausted(state: &AppState, puzzle: &Puzzle) {
+    if !state.mark_puzzle_exhausted(puzzle.number) {
+        return;
+    }
+    tracing::warn!(
+        "puzzle #{} exhausted: no key in [{}, {}] derives {}",
+        puzzle.number,
+        puzzle.range_start,
+        puzzle.range_end,
+        puzzle.address
+    );
+    if let Some(notifier) = state.notifier() {
+        notifier
+            .dispatch(&Event::Alert(format!(
+                "Puzzle #{} exhausted: every key in [{}, {}] was enumerated and none \
+                 derives {}. The puzzle data is wrong — the scheduler will skip it from \
+                 now on.",
+                puzzle.number, puzzle.range_start, puzzle.range_end, puzzle.address
+            )))
+            .await;
+    }
+}
+
+/// Body of one worker thread: claim chunks off the shared cursor and sweep
+/// them until the range runs out or the session ends.
+fn worker_loop(
+    state: &AppState,
+    puzzle: &Puzzle,
+    cursor: &Mutex<BigUint>,
+    range_end: &BigUint,
+    stop: &AtomicBool,
+) -> Result<Vec<CheckResult>> {
+    let mut found = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        // Claim the next chunk; the cursor always points at the first key
+        // nobody has claimed yet.
+        let (chunk_start, chunk_end) = {
+            let mut position = cursor.lock().unwrap();
+            if *position > *range_end {
+                break;
+            }
+            let chunk_start = position.clone();
+            let chunk_end = (&chunk_start + (CHUNK_KEYS - 1)).min(range_end.clone());
+            *position = &chunk_end + BigUint::one();
+            (chunk_start, chunk_end)
+        };
+        let checked = &chunk_end - &chunk_start + BigUint::one();
+        found.extend(sweep_range(puzzle, &chunk_start, &chunk_end)?);
+        let checked = u64::try_from(checked).expect("chunk fits u64");
+        state.stats.record_checked(checked);
+        state.stats.record_puzzle_checked(puzzle.number, checked);
+        state
+            .metrics
+            .keys_checked
+            .with_label_values(&["exhaustive"])
+            .inc_by(checked);
+        state
+            .metrics
+            .puzzle_keys_checked
+            .with_label_values(&[&puzzle.number.to_string()])
+            .inc_by(checked);
+        if !found.is_empty() {
+            state.stats.record_match();
+            state.metrics.matches.inc();
+            break;
+        }
+    }
+    Ok(found)
+}
+
+/// Check every key in `[start, end]`, in order, against the puzzle. The
+/// public key walks forward by point addition; only the first key of the
+/// interval costs a scalar multiplication.
+fn sweep_range(puzzle: &Puzzle, start: &BigUint, end: &BigUint) -> Result<Vec<CheckResult>> {
+    let mut found = Vec::new();
+    let mut value = start.clone();
+    let mut key = keygen::secret_key_from_biguint(&value)?;
+    let mut walker = checker::IncrementalWalker::new(&key, 1)?;
+    loop {
+        if let Some(result) =
+            checker::check_public_key_against_puzzle(&walker.public_key(), &key, puzzle)?
+        {
+            found.push(result);
+        }
+        if value == *end {
+            break;
+        }
+        value += BigUint::one();
+        key = keygen::secret_key_from_biguint(&value)
+            .with_context(|| format!("key {value:x} left the key space"))?;
+        walker.advance(&key)?;
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle_with_address(address: &str) -> Puzzle {
+        Puzzle {
+            number: 8,
+            address: address.into(),
+            range_start: "80".into(),
+            range_end: "ff".into(),
+            reward_btc: 0.0,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: Some("exhaustive".into()),
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn sweep_finds_a_key_inside_the_interval() {
+        let key = keygen::secret_key_from_biguint(&BigUint::from(0xa7u32)).unwrap();
+        let puzzle = puzzle_with_address(&checker::derive_bitcoin_address(&key, checker::AddressType::Compressed).unwrap());
+        let found = sweep_range(&puzzle, &BigUint::from(0x80u32), &BigUint::from(0xffu32)).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].reveal_private_key().trim_start_matches('0'), "a7");
+    }
+
+    #[test]
+    fn sweep_proves_an_interval_empty() {
+        // Key 0x42's address sits below the sweep interval, so a full pass
+        // must come back empty.
+        let key = keygen::secret_key_from_biguint(&BigUint::from(0x42u32)).unwrap();
+        let puzzle = puzzle_with_address(&checker::derive_bitcoin_address(&key, checker::AddressType::Compressed).unwrap());
+        let found = sweep_range(&puzzle, &BigUint::from(0x80u32), &BigUint::from(0xffu32)).unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn sweep_covers_both_endpoints() {
+        for endpoint in [0x80u32, 0xff] {
+            let key = keygen::secret_key_from_biguint(&BigUint::from(endpoint)).unwrap();
+            let puzzle =
+                puzzle_with_address(&checker::derive_bitcoin_address(&key, checker::AddressType::Uncompressed).unwrap());
+            let found =
+                sweep_range(&puzzle, &BigUint::from(0x80u32), &BigUint::from(0xffu32)).unwrap();
+            assert_eq!(found.len(), 1, "endpoint {endpoint:x} missed");
+        }
+    }
+}
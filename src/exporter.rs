@@ -0,0 +1,164 @@
+//! Push-based metrics export for users who don't scrape Prometheus.
+//!
+//! Selected via `METRICS_EXPORTER=influxdb|statsd`. One measurement is pushed
+//! per stats interval, carrying the same numbers the periodic report shows:
+//! lifetime keys checked, the rolling rate, matches and sessions.
+//!
+//! * InfluxDB: line protocol POSTed to `INFLUX_WRITE_URL` (the full
+//!   `/api/v2/write` endpoint including bucket), with an optional
+//!   `INFLUX_TOKEN`.
+//! * statsd: gauges sent as UDP datagrams to `STATSD_ADDR`.
+
+use std::net::UdpSocket;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::state::AppState;
+
+/// A configured push exporter.
+pub enum Exporter {
+    Influx {
+        client: reqwest::Client,
+        write_url: String,
+        token: Option<String>,
+    },
+    Statsd { socket: UdpSocket, addr: String },
+}
+
+impl Exporter {
+    /// Build the exporter selected by the configuration, if any. Returns
+    /// `None` (with a warning) when the selection is incomplete.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        match config.metrics_exporter.as_deref() {
+            None => None,
+            Some("influxdb") => match &config.influx_write_url {
+                Some(url) => Some(Exporter::Influx {
+                    client: reqwest::Client::new(),
+                    write_url: url.clone(),
+                    token: config.influx_token.clone(),
+                }),
+                None => {
+                    tracing::warn!("METRICS_EXPORTER=influxdb but INFLUX_WRITE_URL is unset");
+                    None
+                }
+            },
+            Some("statsd") => match &config.statsd_addr {
+                Some(addr) => match UdpSocket::bind("0.0.0.0:0") {
+                    Ok(socket) => Some(Exporter::Statsd {
+                        socket,
+                        addr: addr.clone(),
+                    }),
+                    Err(err) => {
+                        tracing::warn!("failed to open statsd socket: {err}");
+                        None
+                    }
+                },
+                None => {
+                    tracing::warn!("METRICS_EXPORTER=statsd but STATSD_ADDR is unset");
+                    None
+                }
+            },
+            Some(other) => {
+                tracing::warn!("unknown METRICS_EXPORTER {other:?}; expected influxdb or statsd");
+                None
+            }
+        }
+    }
+
+    /// Push one measurement; failures are the caller's to log.
+    pub async fn push(&self, state: &AppState, rate: u64) -> Result<()> {
+        let snapshot = MeasurementSnapshot::take(state, rate);
+        match self {
+            Exporter::Influx {
+                client,
+                write_url,
+                token,
+            } => {
+                let mut request = client.post(write_url).body(snapshot.influx_line());
+                if let Some(token) = token {
+                    request = request.header("Authorization", format!("Token {token}"));
+                }
+                let response = request.send().await.context("sending InfluxDB write")?;
+                response
+                    .error_for_status()
+                    .context("InfluxDB rejected the write")?;
+                Ok(())
+            }
+            Exporter::Statsd { socket, addr } => {
+                socket
+                    .send_to(snapshot.statsd_datagram().as_bytes(), addr)
+                    .with_context(|| format!("sending statsd datagram to {addr}"))?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The numbers included in each push, captured at one instant.
+struct MeasurementSnapshot {
+    keys_checked: u64,
+    rate: u64,
+    matches_found: u64,
+    sessions_run: u64,
+    uptime_secs: u64,
+}
+
+impl MeasurementSnapshot {
+    fn take(state: &AppState, rate: u64) -> Self {
+        Self {
+            keys_checked: state.stats.total_checked(),
+            rate,
+            matches_found: state.stats.total_matches(),
+            sessions_run: state.stats.total_sessions(),
+            uptime_secs: state.uptime_secs(),
+        }
+    }
+
+    /// One InfluxDB line-protocol record (timestamp left to the server).
+    fn influx_line(&self) -> String {
+        format!(
+            "btclotto keys_checked={}i,rate={}i,matches_found={}i,sessions_run={}i,uptime_secs={}i",
+            self.keys_checked, self.rate, self.matches_found, self.sessions_run, self.uptime_secs
+        )
+    }
+
+    /// One datagram of newline-separated statsd gauges.
+    fn statsd_datagram(&self) -> String {
+        format!(
+            "btclotto.keys_checked:{}|g\nbtclotto.rate:{}|g\nbtclotto.matches_found:{}|g\nbtclotto.sessions_run:{}|g\nbtclotto.uptime_secs:{}|g",
+            self.keys_checked, self.rate, self.matches_found, self.sessions_run, self.uptime_secs
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> MeasurementSnapshot {
+        MeasurementSnapshot {
+            keys_checked: 5000,
+            rate: 42,
+            matches_found: 1,
+            sessions_run: 3,
+            uptime_secs: 120,
+        }
+    }
+
+    #[test]
+    fn influx_line_is_valid_line_protocol() {
+        assert_eq!(
+            snapshot().influx_line(),
+            "btclotto keys_checked=5000i,rate=42i,matches_found=1i,sessions_run=3i,uptime_secs=120i"
+        );
+    }
+
+    #[test]
+    fn statsd_datagram_has_one_gauge_per_line() {
+        let datagram = snapshot().statsd_datagram();
+        assert_eq!(datagram.lines().count(), 5);
+        assert!(datagram.lines().all(|l| l.ends_with("|g")));
+        assert!(datagram.contains("btclotto.rate:42|g"));
+    }
+}
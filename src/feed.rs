@@ -0,0 +1,181 @@
+//! Event feed for feed readers and automation.
+//!
+//! A [`FeedStore`] sits on the notification fanout like any other sink and
+//! keeps the most recent events in memory; the embedded HTTP server renders
+//! them as RSS 2.0 (`/feed.xml`) and JSON Feed 1.1 (`/feed.json`) so IFTTT
+//! and friends can react to solves and milestones. Solve items carry the
+//! puzzle number and address only — never the private key.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::notify::{Event, Notifier};
+
+/// How many events the feed remembers.
+const CAPACITY: usize = 100;
+
+struct FeedItem {
+    id: u64,
+    title: String,
+    body: String,
+    published: DateTime<Utc>,
+}
+
+/// In-memory ring of recent events, shared between the fanout (writer) and
+/// the HTTP server (reader).
+pub struct FeedStore {
+    /// Next item id and the ring itself, newest last.
+    inner: Mutex<(u64, VecDeque<FeedItem>)>,
+}
+
+/// Escape the five XML-special characters for element content.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Feed title and body for one event (keys redacted by construction).
+fn describe(event: &Event) -> (String, String) {
+    match event {
+        Event::Solve(result) => (
+            format!("Puzzle #{} solved", result.puzzle_number),
+            format!(
+                "Match found for puzzle #{} at address {}. The private key is in the solutions store on the host.",
+                result.puzzle_number, result.address
+            ),
+        ),
+        Event::Alert(text) => ("Alert".to_string(), text.clone()),
+        Event::Report { text, .. } => ("Stats report".to_string(), text.clone()),
+        Event::Lifecycle(text) => ("Lifecycle".to_string(), text.clone()),
+    }
+}
+
+impl Default for FeedStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FeedStore {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new((1, VecDeque::new())),
+        }
+    }
+
+    fn push(&self, title: String, body: String) {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.0;
+        inner.0 += 1;
+        inner.1.push_back(FeedItem {
+            id,
+            title,
+            body,
+            published: Utc::now(),
+        });
+        while inner.1.len() > CAPACITY {
+            inner.1.pop_front();
+        }
+    }
+
+    /// Render the feed as RSS 2.0, newest item first.
+    pub fn rss(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<rss version=\"2.0\"><channel>",
+            "<title>BTC puzzle bot</title>",
+            "<link>https://privatekeys.pw/puzzles/bitcoin-puzzle-tx</link>",
+            "<description>Solver events (keys redacted)</description>",
+        ));
+        for item in inner.1.iter().rev() {
+            xml.push_str(&format!(
+                "<item><guid isPermaLink=\"false\">btclotto-{}</guid><title>{}</title><description>{}</description><pubDate>{}</pubDate></item>",
+                item.id,
+                xml_escape(&item.title),
+                xml_escape(&item.body),
+                item.published.to_rfc2822(),
+            ));
+        }
+        xml.push_str("</channel></rss>");
+        xml
+    }
+
+    /// Render the feed as JSON Feed 1.1, newest item first.
+    pub fn json_feed(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let items: Vec<_> = inner
+            .1
+            .iter()
+            .rev()
+            .map(|item| {
+                serde_json::json!({
+                    "id": format!("btclotto-{}", item.id),
+                    "title": item.title,
+                    "content_text": item.body,
+                    "date_published": item.published.to_rfc3339(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "BTC puzzle bot",
+            "description": "Solver events (keys redacted)",
+            "items": items,
+        })
+        .to_string()
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for FeedStore {
+    fn name(&self) -> &'static str {
+        "feed"
+    }
+
+    async fn send(&self, event: &Event) -> Result<()> {
+        let (title, body) = describe(event);
+        self.push(title, body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_events_newest_first_without_keys() {
+        let feed = FeedStore::new();
+        feed.send(&Event::Lifecycle("started".into())).await.unwrap();
+        feed.send(&Event::Alert("rate dropped".into())).await.unwrap();
+        let rss = feed.rss();
+        assert!(rss.starts_with("<?xml"));
+        assert!(rss.find("rate dropped").unwrap() < rss.find("started").unwrap());
+        let json: serde_json::Value = serde_json::from_str(&feed.json_feed()).unwrap();
+        assert_eq!(json["items"][0]["title"], "Alert");
+        assert_eq!(json["items"][1]["content_text"], "started");
+    }
+
+    #[test]
+    fn escapes_xml_content() {
+        let feed = FeedStore::new();
+        feed.push("a & b".into(), "<tag>".into());
+        let rss = feed.rss();
+        assert!(rss.contains("a &amp; b"));
+        assert!(rss.contains("&lt;tag&gt;"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Crash-safe file writing helpers.
+//!
+//! Every persisted artifact (solutions store, snapshots, future checkpoint
+//! files) goes through these helpers so a crash or power loss mid-write can
+//! never leave a half-written file behind: whole-file writes go to a
+//! temporary file in the same directory, are fsynced, and then renamed over
+//! the target; appends are flushed and fsynced before returning.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Atomically replace `path` with `contents`.
+///
+/// The temporary file lives in the target's directory so the final rename
+/// stays on one filesystem and is atomic.
+pub fn atomic_write(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+    let tmp_path = path.with_extension(format!(
+        "{}.tmp-{}",
+        path.extension().and_then(|e| e.to_str()).unwrap_or(""),
+        std::process::id()
+    ));
+    let mut tmp = File::create(&tmp_path)
+        .with_context(|| format!("creating temporary file {}", tmp_path.display()))?;
+    tmp.write_all(contents)?;
+    tmp.sync_all()
+        .with_context(|| format!("fsync of {}", tmp_path.display()))?;
+    drop(tmp);
+    std::fs::rename(&tmp_path, path)
+        .with_context(|| format!("renaming {} into place", tmp_path.display()))?;
+    // Make the rename itself durable.
+    if let Some(dir) = dir {
+        if let Ok(handle) = File::open(dir) {
+            let _ = handle.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Create `path` (and parents) as a directory only the current user can
+/// enter. Permissions on an already-existing directory are tightened too.
+pub fn ensure_restricted_dir(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path)
+        .with_context(|| format!("creating directory {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))
+            .with_context(|| format!("restricting permissions on {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Append one line to `path` and fsync before returning.
+pub fn append_line_durable(path: &Path, line: &str) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening {} for append", path.display()))?;
+    writeln!(file, "{line}")?;
+    file.sync_all()
+        .with_context(|| format!("fsync of {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_creates_and_replaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        atomic_write(&path, b"first").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"first");
+        atomic_write(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        // No temp file may be left behind.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn append_line_durable_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        append_line_durable(&path, "one").unwrap();
+        append_line_durable(&path, "two").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+    }
+}
@@ -0,0 +1,135 @@
+//! Peer-to-peer coverage gossip.
+//!
+//! Each instance keeps a coarse per-puzzle record of which work-unit-sized
+//! buckets of the keyspace have been fully searched (its own completed
+//! units plus anything learned from peers). The embedded HTTP server
+//! exposes the record on `/gossip/coverage`, and instances listed in
+//! `GOSSIP_PEERS` are polled periodically and merged in. Worker threads
+//! then redraw random keys that land in a bucket some peer has already
+//! exhausted, so a loose fleet stops re-sampling each other's ground
+//! without needing a coordinator or Redis.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+
+use crate::state::AppState;
+
+/// Exhausted-bucket record, bucketed at work-unit granularity from the
+/// puzzle range start.
+pub struct CoverageMap {
+    unit_keys: u64,
+    /// Per-puzzle exhausted bucket indices. `Arc` so the hot sampling loop
+    /// can hold a lock-free snapshot per session.
+    inner: Mutex<HashMap<u32, Arc<BTreeSet<u64>>>>,
+}
+
+impl CoverageMap {
+    pub fn new(unit_keys: u64) -> Self {
+        Self {
+            unit_keys: unit_keys.max(1),
+            inner: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bucket index of `value` within a range starting at `origin`.
+    pub fn bucket_of(&self, origin: &BigUint, value: &BigUint) -> u64 {
+        if value < origin {
+            return 0;
+        }
+        ((value - origin) / self.unit_keys).to_u64().unwrap_or(u64::MAX)
+    }
+
+    /// Record one exhausted bucket.
+    pub fn mark(&self, puzzle_number: u32, index: u64) {
+        self.merge(puzzle_number, std::iter::once(index));
+    }
+
+    /// Merge exhausted buckets learned from a peer (or ourselves).
+    pub fn merge(&self, puzzle_number: u32, indices: impl IntoIterator<Item = u64>) {
+        let mut inner = self.inner.lock().unwrap();
+        let set = inner.entry(puzzle_number).or_default();
+        let set = Arc::make_mut(set);
+        set.extend(indices);
+    }
+
+    /// Snapshot of the exhausted buckets for one puzzle; `None` when
+    /// nothing is recorded, so the sampling loop can skip the check.
+    pub fn exhausted(&self, puzzle_number: u32) -> Option<Arc<BTreeSet<u64>>> {
+        self.inner
+            .lock()
+            .unwrap()
+            .get(&puzzle_number)
+            .filter(|set| !set.is_empty())
+            .cloned()
+    }
+
+    /// The whole record, for serving to peers.
+    pub fn snapshot(&self) -> HashMap<u32, Vec<u64>> {
+        self.inner
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(number, set)| (*number, set.iter().copied().collect()))
+            .collect()
+    }
+}
+
+/// Poll each peer's coverage record and merge it into ours, forever.
+pub async fn run(state: Arc<AppState>, peers: Vec<String>, interval: Duration) {
+    let client = reqwest::Client::new();
+    tracing::info!("gossiping coverage with {} peer(s)", peers.len());
+    loop {
+        for peer in &peers {
+            let url = format!("{}/gossip/coverage", peer.trim_end_matches('/'));
+            let fetched: Result<HashMap<u32, Vec<u64>>, _> = async {
+                client
+                    .get(&url)
+                    .timeout(Duration::from_secs(10))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await
+            }
+            .await;
+            match fetched {
+                Ok(coverage) => {
+                    for (puzzle_number, indices) in coverage {
+                        state.coverage.merge(puzzle_number, indices);
+                    }
+                }
+                Err(err) => tracing::debug!("gossip fetch from {peer} failed: {err}"),
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_and_snapshots_buckets() {
+        let coverage = CoverageMap::new(0x40);
+        assert!(coverage.exhausted(8).is_none());
+        coverage.mark(8, 0);
+        coverage.merge(8, [1, 3]);
+        let set = coverage.exhausted(8).unwrap();
+        assert!(set.contains(&0) && set.contains(&3) && !set.contains(&2));
+        assert_eq!(coverage.snapshot()[&8], vec![0, 1, 3]);
+    }
+
+    #[test]
+    fn buckets_are_relative_to_the_range_origin() {
+        let coverage = CoverageMap::new(0x40);
+        let origin = BigUint::from(0x80u32);
+        assert_eq!(coverage.bucket_of(&origin, &BigUint::from(0x80u32)), 0);
+        assert_eq!(coverage.bucket_of(&origin, &BigUint::from(0xbfu32)), 0);
+        assert_eq!(coverage.bucket_of(&origin, &BigUint::from(0xc0u32)), 1);
+    }
+}
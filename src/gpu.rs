@@ -0,0 +1,374 @@
+//! OpenCL key search offload (behind the `gpu` cargo feature).
+//!
+//! The device runs hash160 (SHA-256 then RIPEMD-160) over batches of
+//! compressed public keys and compares each against the puzzle's target
+//! hash, which is where nearly all of the per-key CPU goes now that the
+//! EC side is a single point addition per key (the host walks the public
+//! key forward incrementally while serializing the batch). Candidate hits
+//! come back as batch indices and are re-verified on the CPU through
+//! `checker` before anything is reported, so a device miscompute can only
+//! cost throughput, never a false solve.
+//!
+//! Enabled at runtime with `GPU=true` on the default OpenCL device; the
+//! GPU worker runs alongside the CPU workers of each session and its
+//! throughput is tracked separately (`/status`, and the `gpu` thread
+//! label on the keys-checked metric).
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use num_bigint::{BigUint, RandBigInt};
+use num_traits::One;
+
+use crate::checker::{self, CheckResult};
+use crate::keygen;
+use crate::metrics::ErrorKind;
+use crate::puzzles::Puzzle;
+use crate::state::AppState;
+
+/// Compressed public keys per device dispatch (`GPU_BATCH` overrides).
+const DEFAULT_BATCH: usize = 1 << 20;
+
+/// Hit slots per dispatch; more matches than this in one batch would mean
+/// the target comparison is broken anyway.
+const MAX_HITS: usize = 64;
+
+/// OpenCL program: SHA-256 and RIPEMD-160 over one 33-byte compressed
+/// public key per work item, then a constant-size compare against the
+/// target hash160.
+const KERNEL_SRC: &str = r#"
+__constant uint SHA_K[64] = {
+    0x428a2f98u, 0x71374491u, 0xb5c0fbcfu, 0xe9b5dba5u,
+    0x3956c25bu, 0x59f111f1u, 0x923f82a4u, 0xab1c5ed5u,
+    0xd807aa98u, 0x12835b01u, 0x243185beu, 0x550c7dc3u,
+    0x72be5d74u, 0x80deb1feu, 0x9bdc06a7u, 0xc19bf174u,
+    0xe49b69c1u, 0xefbe4786u, 0x0fc19dc6u, 0x240ca1ccu,
+    0x2de92c6fu, 0x4a7484aau, 0x5cb0a9dcu, 0x76f988dau,
+    0x983e5152u, 0xa831c66du, 0xb00327c8u, 0xbf597fc7u,
+    0xc6e00bf3u, 0xd5a79147u, 0x06ca6351u, 0x14292967u,
+    0x27b70a85u, 0x2e1b2138u, 0x4d2c6dfcu, 0x53380d13u,
+    0x650a7354u, 0x766a0abbu, 0x81c2c92eu, 0x92722c85u,
+    0xa2bfe8a1u, 0xa81a664bu, 0xc24b8b70u, 0xc76c51a3u,
+    0xd192e819u, 0xd6990624u, 0xf40e3585u, 0x106aa070u,
+    0x19a4c116u, 0x1e376c08u, 0x2748774cu, 0x34b0bcb5u,
+    0x391c0cb3u, 0x4ed8aa4au, 0x5b9cca4fu, 0x682e6ff3u,
+    0x748f82eeu, 0x78a5636fu, 0x84c87814u, 0x8cc70208u,
+    0x90befffau, 0xa4506cebu, 0xbef9a3f7u, 0xc67178f2u
+};
+
+static inline uint rotr32(uint x, uint n) { return (x >> n) | (x << (32u - n)); }
+static inline uint rotl32(uint x, uint n) { return (x << n) | (x >> (32u - n)); }
+
+/* SHA-256 of a 33-byte message: exactly one padded block. */
+static void sha256_33(const uchar *msg, uint *out)
+{
+    uint w[64];
+    uchar block[64];
+    for (int i = 0; i < 64; i++) block[i] = 0u;
+    for (int i = 0; i < 33; i++) block[i] = msg[i];
+    block[33] = 0x80u;
+    /* bit length 264 = 0x108, big-endian in the last two bytes */
+    block[62] = 0x01u;
+    block[63] = 0x08u;
+    for (int i = 0; i < 16; i++) {
+        w[i] = ((uint)block[4 * i] << 24) | ((uint)block[4 * i + 1] << 16) |
+               ((uint)block[4 * i + 2] << 8) | (uint)block[4 * i + 3];
+    }
+    for (int i = 16; i < 64; i++) {
+        uint s0 = rotr32(w[i - 15], 7) ^ rotr32(w[i - 15], 18) ^ (w[i - 15] >> 3);
+        uint s1 = rotr32(w[i - 2], 17) ^ rotr32(w[i - 2], 19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16] + s0 + w[i - 7] + s1;
+    }
+    uint a = 0x6a09e667u, b = 0xbb67ae85u, c = 0x3c6ef372u, d = 0xa54ff53au;
+    uint e = 0x510e527fu, f = 0x9b05688cu, g = 0x1f83d9abu, h = 0x5be0cd19u;
+    for (int i = 0; i < 64; i++) {
+        uint s1 = rotr32(e, 6) ^ rotr32(e, 11) ^ rotr32(e, 25);
+        uint ch = (e & f) ^ (~e & g);
+        uint t1 = h + s1 + ch + SHA_K[i] + w[i];
+        uint s0 = rotr32(a, 2) ^ rotr32(a, 13) ^ rotr32(a, 22);
+        uint maj = (a & b) ^ (a & c) ^ (b & c);
+        uint t2 = s0 + maj;
+        h = g; g = f; f = e; e = d + t1;
+        d = c; c = b; b = a; a = t1 + t2;
+    }
+    out[0] = a + 0x6a09e667u; out[1] = b + 0xbb67ae85u;
+    out[2] = c + 0x3c6ef372u; out[3] = d + 0xa54ff53au;
+    out[4] = e + 0x510e527fu; out[5] = f + 0x9b05688cu;
+    out[6] = g + 0x1f83d9abu; out[7] = h + 0x5be0cd19u;
+}
+
+__constant uchar RL[80] = {
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8,
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12,
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2,
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13
+};
+__constant uchar RR[80] = {
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12,
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2,
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13,
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14,
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11
+};
+__constant uchar SL[80] = {
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8,
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12,
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5,
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12,
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6
+};
+__constant uchar SR[80] = {
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6,
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11,
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5,
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8,
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11
+};
+__constant uint KL[5] = { 0x00000000u, 0x5a827999u, 0x6ed9eba1u, 0x8f1bbcdcu, 0xa953fd4eu };
+__constant uint KR[5] = { 0x50a28be6u, 0x5c4dd124u, 0x6d703ef3u, 0x7a6d76e9u, 0x00000000u };
+
+static inline uint rmd_f(int round, uint x, uint y, uint z)
+{
+    if (round < 16) return x ^ y ^ z;
+    if (round < 32) return (x & y) | (~x & z);
+    if (round < 48) return (x | ~y) ^ z;
+    if (round < 64) return (x & z) | (y & ~z);
+    return x ^ (y | ~z);
+}
+
+/* RIPEMD-160 of a 32-byte message (the SHA-256 digest, given as eight
+   big-endian words): exactly one padded block. */
+static void ripemd160_32(const uint *digest, uchar *out)
+{
+    uint x[16];
+    for (int i = 0; i < 16; i++) x[i] = 0u;
+    /* message words are little-endian */
+    for (int i = 0; i < 8; i++) {
+        uint v = digest[i];
+        x[i] = ((v & 0xffu) << 24) | ((v & 0xff00u) << 8) |
+               ((v >> 8) & 0xff00u) | (v >> 24);
+    }
+    x[8] = 0x80u;
+    x[14] = 256u; /* bit length, low word */
+    uint al = 0x67452301u, bl = 0xefcdab89u, cl = 0x98badcfeu, dl = 0x10325476u, el = 0xc3d2e1f0u;
+    uint ar = al, br = bl, cr = cl, dr = dl, er = el;
+    for (int j = 0; j < 80; j++) {
+        uint t = rotl32(al + rmd_f(j, bl, cl, dl) + x[RL[j]] + KL[j / 16], SL[j]) + el;
+        al = el; el = dl; dl = rotl32(cl, 10); cl = bl; bl = t;
+        t = rotl32(ar + rmd_f(79 - j, br, cr, dr) + x[RR[j]] + KR[j / 16], SR[j]) + er;
+        ar = er; er = dr; dr = rotl32(cr, 10); cr = br; br = t;
+    }
+    uint h0 = 0x67452301u, h1 = 0xefcdab89u, h2 = 0x98badcfeu, h3 = 0x10325476u, h4 = 0xc3d2e1f0u;
+    uint t = h1 + cl + dr;
+    h1 = h2 + dl + er;
+    h2 = h3 + el + ar;
+    h3 = h4 + al + br;
+    h4 = h0 + bl + cr;
+    h0 = t;
+    uint h[5] = { h0, h1, h2, h3, h4 };
+    for (int i = 0; i < 5; i++) {
+        out[4 * i] = h[i] & 0xffu;
+        out[4 * i + 1] = (h[i] >> 8) & 0xffu;
+        out[4 * i + 2] = (h[i] >> 16) & 0xffu;
+        out[4 * i + 3] = (h[i] >> 24) & 0xffu;
+    }
+}
+
+__kernel void hash160_match(
+    __global const uchar *pubkeys,
+    __global const uchar *target,
+    __global uint *hits,
+    __global volatile uint *hit_count,
+    const uint max_hits)
+{
+    size_t gid = get_global_id(0);
+    uchar msg[33];
+    for (int i = 0; i < 33; i++) msg[i] = pubkeys[gid * 33 + i];
+    uint digest[8];
+    sha256_33(msg, digest);
+    uchar h160[20];
+    ripemd160_32(digest, h160);
+    for (int i = 0; i < 20; i++) {
+        if (h160[i] != target[i]) return;
+    }
+    uint slot = atomic_inc(hit_count);
+    if (slot < max_hits) hits[slot] = (uint)gid;
+}
+"#;
+
+/// `ocl`'s error type predates `std::error::Error` adoption; flatten it.
+fn ocl_err(err: ocl::Error) -> anyhow::Error {
+    anyhow::anyhow!("OpenCL: {err}")
+}
+
+/// A compiled program plus queue on the default OpenCL device.
+struct GpuEngine {
+    pro_que: ocl::ProQue,
+    batch: usize,
+}
+
+impl GpuEngine {
+    fn new(batch: usize) -> Result<Self> {
+        let pro_que = ocl::ProQue::builder()
+            .src(KERNEL_SRC)
+            .dims(batch)
+            .build()
+            .map_err(ocl_err)?;
+        tracing::info!(
+            "GPU worker on {} (batch {batch})",
+            pro_que.device().name().map_err(ocl_err)?
+        );
+        Ok(Self { pro_que, batch })
+    }
+
+    /// Hash a batch of serialized compressed public keys on the device and
+    /// return the indices whose hash160 equals `target`.
+    fn scan(&self, pubkeys: &[u8], target: &[u8; 20]) -> Result<Vec<u32>> {
+        let count = pubkeys.len() / 33;
+        let keys_buf = ocl::Buffer::<u8>::builder()
+            .queue(self.pro_que.queue().clone())
+            .len(pubkeys.len())
+            .copy_host_slice(pubkeys)
+            .build()
+            .map_err(ocl_err)?;
+        let target_buf = ocl::Buffer::<u8>::builder()
+            .queue(self.pro_que.queue().clone())
+            .len(target.len())
+            .copy_host_slice(target)
+            .build()
+            .map_err(ocl_err)?;
+        let hits_buf = ocl::Buffer::<u32>::builder()
+            .queue(self.pro_que.queue().clone())
+            .len(MAX_HITS)
+            .fill_val(0u32)
+            .build()
+            .map_err(ocl_err)?;
+        let count_buf = ocl::Buffer::<u32>::builder()
+            .queue(self.pro_que.queue().clone())
+            .len(1)
+            .fill_val(0u32)
+            .build()
+            .map_err(ocl_err)?;
+        let kernel = self
+            .pro_que
+            .kernel_builder("hash160_match")
+            .arg(&keys_buf)
+            .arg(&target_buf)
+            .arg(&hits_buf)
+            .arg(&count_buf)
+            .arg(MAX_HITS as u32)
+            .global_work_size(count)
+            .build()
+            .map_err(ocl_err)?;
+        // Safety: the kernel only reads `pubkeys`/`target` and writes
+        // inside the fixed-size hit buffers.
+        unsafe { kernel.enq().map_err(ocl_err)? };
+        let mut found = vec![0u32; 1];
+        count_buf.read(&mut found).enq().map_err(ocl_err)?;
+        let n = (found[0] as usize).min(MAX_HITS);
+        let mut hits = vec![0u32; MAX_HITS];
+        hits_buf.read(&mut hits).enq().map_err(ocl_err)?;
+        hits.truncate(n);
+        Ok(hits)
+    }
+}
+
+/// The target hash160 from a P2PKH address.
+fn target_hash160(address: &str) -> Result<[u8; 20]> {
+    let payload = bitcoin::base58::decode_check(address)
+        .with_context(|| format!("decoding target address {address}"))?;
+    if payload.len() != 21 {
+        bail!("target address {address} is not P2PKH");
+    }
+    let mut hash = [0u8; 20];
+    hash.copy_from_slice(&payload[1..]);
+    Ok(hash)
+}
+
+/// Spawn the GPU worker for one session, or `None` when `GPU` isn't
+/// `true` or the device fails to initialize (logged, never fatal — the
+/// CPU workers carry the session).
+pub fn spawn_worker(
+    state: Arc<AppState>,
+    puzzle: Puzzle,
+    range: (BigUint, BigUint),
+    stop: Arc<AtomicBool>,
+) -> Option<tokio::task::JoinHandle<Result<Vec<CheckResult>>>> {
+    let enabled = std::env::var("GPU").map(|v| v == "true" || v == "1").unwrap_or(false)
+        || std::env::var("BACKEND").map(|v| v == "opencl").unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+    let batch = std::env::var("GPU_BATCH")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_BATCH);
+    Some(tokio::task::spawn_blocking(move || {
+        let engine = GpuEngine::new(batch)?;
+        worker_loop(&state, &engine, &puzzle, &range, &stop)
+    }))
+}
+
+/// Body of the GPU worker: pick a random block, walk it on the host while
+/// the previous batch hashes on the device would be the next step — kept
+/// simple and synchronous for now — and CPU-verify any hits.
+fn worker_loop(
+    state: &AppState,
+    engine: &GpuEngine,
+    puzzle: &Puzzle,
+    (range_start, range_end): &(BigUint, BigUint),
+    stop: &AtomicBool,
+) -> Result<Vec<CheckResult>> {
+    let target = target_hash160(&puzzle.address)?;
+    let span = range_end - range_start + BigUint::one();
+    let mut found = Vec::new();
+    let mut pubkeys = vec![0u8; engine.batch * 33];
+    let one = bitcoin::secp256k1::Scalar::ONE;
+
+    while !stop.load(Ordering::Relaxed) {
+        let block_start = range_start + rand::thread_rng().gen_biguint_below(&span);
+        let mut key = keygen::secret_key_from_biguint(&block_start)?;
+        let mut walker = checker::IncrementalWalker::new(&key, 1)?;
+        for i in 0..engine.batch {
+            pubkeys[i * 33..(i + 1) * 33].copy_from_slice(&walker.public_key().serialize());
+            if i + 1 < engine.batch {
+                key = key.add_tweak(&one).context("key walk left the curve order")?;
+                walker.advance(&key)?;
+            }
+        }
+        let hits = match engine.scan(&pubkeys, &target) {
+            Ok(hits) => hits,
+            Err(err) => {
+                state.metrics.record_error(ErrorKind::Derivation);
+                return Err(err);
+            }
+        };
+        for index in hits {
+            let value = &block_start + BigUint::from(index);
+            let key = keygen::secret_key_from_biguint(&value)?;
+            if let Some(result) = checker::check_private_key_against_puzzle(&key, puzzle)? {
+                tracing::info!(
+                    "GPU: MATCH on puzzle #{} (key {})",
+                    puzzle.number,
+                    checker::redact_secret(result.reveal_private_key())
+                );
+                state.stats.record_match();
+                state.metrics.matches.inc();
+                found.push(result);
+            } else {
+                tracing::warn!(
+                    "GPU hit at batch index {index} failed CPU verification; check the device"
+                );
+            }
+        }
+        state.stats.record_gpu_checked(engine.batch as u64);
+        state
+            .metrics
+            .keys_checked
+            .with_label_values(&["gpu"])
+            .inc_by(engine.batch as u64);
+    }
+    Ok(found)
+}
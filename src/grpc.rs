@@ -0,0 +1,128 @@
+//! gRPC control service (behind the `grpc` cargo feature).
+//!
+//! Mirrors the Telegram control surface for orchestration systems that
+//! prefer typed RPC: status snapshot, start/stop, runtime settings, and a
+//! stream of solver events. Enabled at runtime by `GRPC_LISTEN`
+//! (e.g. `127.0.0.1:50051`); there is no auth, so bind it to loopback or a
+//! trusted network only.
+
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status};
+
+use crate::state::AppState;
+
+pub mod proto {
+    tonic::include_proto!("btclotto.control");
+}
+
+use proto::control_server::{Control, ControlServer};
+use proto::{CommandReply, Empty, EventMessage, SetConfigRequest, StatusReply};
+
+pub struct ControlService {
+    state: Arc<AppState>,
+}
+
+#[tonic::async_trait]
+impl Control for ControlService {
+    async fn get_status(&self, _: Request<Empty>) -> Result<Response<StatusReply>, Status> {
+        let state = &self.state;
+        Ok(Response::new(StatusReply {
+            running: state.is_running(),
+            uptime_secs: state.uptime_secs(),
+            keys_checked: state.stats.total_checked(),
+            matches_found: state.stats.total_matches(),
+            sessions_run: state.stats.total_sessions(),
+            focused_puzzle: state.focused_puzzle(),
+            active_puzzle: state.active_puzzle(),
+        }))
+    }
+
+    async fn start_solver(&self, _: Request<Empty>) -> Result<Response<CommandReply>, Status> {
+        self.state.set_running(true);
+        Ok(Response::new(CommandReply {
+            ok: true,
+            message: "Solver started.".to_string(),
+        }))
+    }
+
+    async fn stop_solver(&self, _: Request<Empty>) -> Result<Response<CommandReply>, Status> {
+        self.state.set_running(false);
+        Ok(Response::new(CommandReply {
+            ok: true,
+            message: "Solver stopped.".to_string(),
+        }))
+    }
+
+    async fn set_config(
+        &self,
+        request: Request<SetConfigRequest>,
+    ) -> Result<Response<CommandReply>, Status> {
+        let request = request.into_inner();
+        let reply = match request.key.as_str() {
+            // An empty value clears the focus, like /focus without an arg.
+            "focus" => {
+                if request.value.is_empty() {
+                    CommandReply {
+                        ok: true,
+                        message: self.state.set_focus(None),
+                    }
+                } else {
+                    match request.value.parse() {
+                        Ok(number) => CommandReply {
+                            ok: true,
+                            message: self.state.set_focus(Some(number)),
+                        },
+                        Err(_) => CommandReply {
+                            ok: false,
+                            message: format!("not a puzzle number: {:?}", request.value),
+                        },
+                    }
+                }
+            }
+            "loglevel" => match crate::logging::set_filter(&request.value) {
+                Ok(()) => CommandReply {
+                    ok: true,
+                    message: format!("Log filter set to {}.", request.value),
+                },
+                Err(err) => CommandReply {
+                    ok: false,
+                    message: format!("{err:#}"),
+                },
+            },
+            other => CommandReply {
+                ok: false,
+                message: format!("unknown setting {other:?}; supported: focus, loglevel"),
+            },
+        };
+        Ok(Response::new(reply))
+    }
+
+    type StreamEventsStream =
+        Pin<Box<dyn tokio_stream::Stream<Item = Result<EventMessage, Status>> + Send>>;
+
+    async fn stream_events(
+        &self,
+        _: Request<Empty>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let stream = BroadcastStream::new(self.state.events.subscribe())
+            // Lagged receivers just miss events; the stream keeps going.
+            .filter_map(|item| item.ok())
+            .map(|json| Ok(EventMessage { json }));
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Serve the control API until the process exits.
+pub async fn serve(state: Arc<AppState>, listen: std::net::SocketAddr) -> Result<()> {
+    tracing::info!("gRPC control service listening on {listen}");
+    tonic::transport::Server::builder()
+        .add_service(ControlServer::new(ControlService { state }))
+        .serve(listen)
+        .await
+        .context("gRPC server failed")
+}
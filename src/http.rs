@@ -0,0 +1,173 @@
+//! Embedded HTTP server for health checks.
+//!
+//! Enabled by setting `HTTP_LISTEN` (e.g. `127.0.0.1:8080`). Two probes are
+//! served, suitable for container orchestrators and uptime monitors:
+//!
+//! * `/healthz` — liveness: 200 while the scheduler loop is ticking, 503 if
+//!   it has stalled.
+//! * `/readyz` — readiness: additionally requires the last Telegram API call
+//!   to have succeeded (always ready when running without Telegram).
+//!
+//! Both return a JSON body with the underlying details. Also served:
+//! Prometheus metrics on `/metrics` and the event feed on `/feed.xml`
+//! (RSS 2.0) and `/feed.json` (JSON Feed 1.1).
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json};
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+
+use crate::state::AppState;
+
+/// The scheduler is considered stalled once it has missed this many session
+/// intervals in a row.
+const STALL_FACTOR: u64 = 3;
+
+fn scheduler_alive(state: &AppState) -> bool {
+    let scheduler = &state.config.scheduler;
+    let allowed = (scheduler.session_interval_secs + scheduler.session_duration_secs)
+        .saturating_mul(STALL_FACTOR);
+    match state.heartbeat_age_secs() {
+        Some(age) => age <= allowed,
+        // Startup grace: the loop simply hasn't ticked yet.
+        None => state.uptime_secs() <= allowed,
+    }
+}
+
+async fn healthz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let alive = scheduler_alive(&state);
+    let status = if alive {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if alive { "ok" } else { "stalled" },
+        "uptime_secs": state.uptime_secs(),
+        "heartbeat_age_secs": state.heartbeat_age_secs(),
+        "last_session": state.last_session_time().map(|t| t.to_rfc3339()),
+    });
+    (status, Json(body))
+}
+
+async fn readyz(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let alive = scheduler_alive(&state);
+    let telegram_configured = state.config.telegram_token.is_some();
+    let telegram_ok = !telegram_configured || state.telegram_ok();
+    let ready = alive && telegram_ok;
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = json!({
+        "status": if ready { "ready" } else { "not ready" },
+        "scheduler_alive": alive,
+        "telegram_configured": telegram_configured,
+        "telegram_ok": telegram_ok,
+        "last_session": state.last_session_time().map(|t| t.to_rfc3339()),
+    });
+    (status, Json(body))
+}
+
+/// Build the router; split out from [`serve`] so tests can exercise it.
+async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.render()
+}
+
+async fn gossip_coverage(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(serde_json::json!(state.coverage.snapshot()))
+}
+
+async fn feed_rss(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [("content-type", "application/rss+xml; charset=utf-8")],
+        state.feed.rss(),
+    )
+}
+
+async fn feed_json(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [("content-type", "application/feed+json; charset=utf-8")],
+        state.feed.json_feed(),
+    )
+}
+
+pub fn router(state: Arc<AppState>) -> Router {
+    let mut router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(metrics))
+        .route("/feed.xml", get(feed_rss))
+        .route("/feed.json", get(feed_json))
+        .route("/gossip/coverage", get(gossip_coverage))
+        .merge(crate::cluster::stats_routes());
+    if state.coordinator.is_some() {
+        router = router.merge(crate::cluster::routes());
+    }
+    router.with_state(state)
+}
+
+/// Serve the HTTP endpoints until the process exits.
+pub async fn serve(state: Arc<AppState>, listen: std::net::SocketAddr) -> Result<()> {
+    let app = router(state);
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("binding HTTP listener on {listen}"))?;
+    tracing::info!("HTTP server listening on {listen}");
+    axum::serve(listener, app).await.context("HTTP server failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::puzzles::PuzzleCollection;
+    use crate::solutions::SolutionStore;
+    use tower::ServiceExt;
+
+    fn test_state() -> Arc<AppState> {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::from_env();
+        config.telegram_token = None;
+        config.solutions_file = dir.path().join("solutions.log");
+        config.puzzle_file = dir.path().join("puzzles.json");
+        config.data_dir = dir.path().to_path_buf();
+        std::fs::write(&config.puzzle_file, "[]").unwrap();
+        let puzzles = PuzzleCollection::load(&config.puzzle_file).unwrap();
+        let solutions = SolutionStore::open_from_env(&config.solutions_file).unwrap();
+        Arc::new(AppState::new(config, puzzles, solutions))
+    }
+
+    async fn get_status(router: Router, path: &str) -> StatusCode {
+        let response = router
+            .oneshot(
+                axum::http::Request::builder()
+                    .uri(path)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn healthz_ok_with_recent_heartbeat() {
+        let state = test_state();
+        state.heartbeat();
+        assert_eq!(get_status(router(state), "/healthz").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn readyz_ok_without_telegram() {
+        let state = test_state();
+        state.heartbeat();
+        assert_eq!(get_status(router(state), "/readyz").await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,174 @@
+//! Crash-safe journaling of found matches.
+//!
+//! A match is worth more than everything else this bot will ever do, so the
+//! window between "found" and "operator notified" must not be able to lose
+//! it. Every match is appended to an on-disk journal *before* any delivery
+//! attempt, and a delivery acknowledgement is appended once Telegram accepts
+//! the message. Undelivered entries are replayed on the next startup.
+//!
+//! The journal is append-only JSON lines (one object per line), living in
+//! the restricted `DATA_DIR`:
+//!
+//! ```text
+//! {"kind":"match","id":3,"recorded_at":"...","result":{...}}
+//! {"kind":"delivered","id":3}
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::checker::CheckResult;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Entry {
+    Match {
+        id: u64,
+        recorded_at: chrono::DateTime<chrono::Utc>,
+        result: CheckResult,
+    },
+    Delivered {
+        id: u64,
+    },
+}
+
+/// A match recorded in the journal but not yet acknowledged as delivered.
+#[derive(Debug)]
+pub struct PendingMatch {
+    pub id: u64,
+    pub result: CheckResult,
+}
+
+/// Append-only journal of in-flight matches.
+pub struct MatchJournal {
+    path: PathBuf,
+}
+
+impl MatchJournal {
+    pub fn open(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+
+    fn read_entries(&self) -> Result<Vec<Entry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let data = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("reading match journal {}", self.path.display()))?;
+        let mut entries = Vec::new();
+        for (i, line) in data.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                // A torn final line is expected after a crash; anything else
+                // is worth a warning but must not block recovery.
+                Err(err) => tracing::warn!("skipping bad journal line {}: {err}", i + 1),
+            }
+        }
+        Ok(entries)
+    }
+
+    fn append(&self, entry: &Entry) -> Result<()> {
+        let line = serde_json::to_string(entry)?;
+        crate::fsutil::append_line_durable(&self.path, &line)
+            .with_context(|| format!("appending to match journal {}", self.path.display()))
+    }
+
+    /// Record a freshly found match. Returns the journal id to acknowledge
+    /// with [`mark_delivered`](Self::mark_delivered) after delivery succeeds.
+    pub fn record(&self, result: &CheckResult) -> Result<u64> {
+        let next_id = self
+            .read_entries()?
+            .iter()
+            .map(|e| match e {
+                Entry::Match { id, .. } | Entry::Delivered { id } => *id,
+            })
+            .max()
+            .map_or(1, |max| max + 1);
+        self.append(&Entry::Match {
+            id: next_id,
+            recorded_at: chrono::Utc::now(),
+            result: result.clone(),
+        })?;
+        Ok(next_id)
+    }
+
+    /// Acknowledge that the match with `id` reached the operator.
+    pub fn mark_delivered(&self, id: u64) -> Result<()> {
+        self.append(&Entry::Delivered { id })
+    }
+
+    /// Matches recorded but never acknowledged, oldest first.
+    pub fn pending(&self) -> Result<Vec<PendingMatch>> {
+        let entries = self.read_entries()?;
+        let delivered: std::collections::HashSet<u64> = entries
+            .iter()
+            .filter_map(|e| match e {
+                Entry::Delivered { id } => Some(*id),
+                _ => None,
+            })
+            .collect();
+        Ok(entries
+            .into_iter()
+            .filter_map(|e| match e {
+                Entry::Match { id, result, .. } if !delivered.contains(&id) => {
+                    Some(PendingMatch { id, result })
+                }
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::AddressType;
+
+    fn result(n: u32) -> CheckResult {
+        CheckResult::new(n, format!("1Addr{n}"), "abcd".into(), AddressType::Compressed)
+    }
+
+    #[test]
+    fn pending_tracks_unacknowledged_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = MatchJournal::open(&dir.path().join("journal.log"));
+        let id1 = journal.record(&result(8)).unwrap();
+        let id2 = journal.record(&result(9)).unwrap();
+        assert_ne!(id1, id2);
+        assert_eq!(journal.pending().unwrap().len(), 2);
+
+        journal.mark_delivered(id1).unwrap();
+        let pending = journal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, id2);
+        assert_eq!(pending[0].result.puzzle_number, 9);
+
+        journal.mark_delivered(id2).unwrap();
+        assert!(journal.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn torn_trailing_line_does_not_block_recovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.log");
+        let journal = MatchJournal::open(&path);
+        journal.record(&result(8)).unwrap();
+        // Simulate a crash mid-append.
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"kind\":\"match\",\"id\":2,\"recor").unwrap();
+        drop(file);
+        assert_eq!(journal.pending().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn empty_journal_has_no_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = MatchJournal::open(&dir.path().join("journal.log"));
+        assert!(journal.pending().unwrap().is_empty());
+    }
+}
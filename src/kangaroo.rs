@@ -0,0 +1,692 @@
+//! Pollard's kangaroo (lambda) solver and JeanLucPons-Kangaroo work-file
+//! (DP table) compatibility.
+//!
+//! For puzzles whose public key is exposed (an outgoing spend from the
+//! prize address), random search is pointless: the interval discrete-log
+//! algorithms find the key in about `sqrt(range)` group operations. The
+//! solver here walks one tame kangaroo (starting from a known exponent)
+//! and one wild kangaroo (starting from the target point) along the same
+//! pseudo-random jump sequence; when both land on the same distinguished
+//! point, the difference of their accumulated distances is the private
+//! key.
+//!
+//! The GPU community shares partial kangaroo runs as binary "work files":
+//! every distinguished point a tame or wild kangaroo landed on, keyed by
+//! the low 128 bits of the point's X coordinate. This module reads, writes
+//! and merges those files, so tables computed elsewhere can be folded into
+//! this bot's search — set `DP_MERGE_FILES` to merge them at startup. The
+//! layout follows Kangaroo's `SaveWork`: a fixed little-endian header, then
+//! 2^18 hash buckets of 32-byte entries. The distance field packs the
+//! kangaroo type in bit 127 (wild when set), the distance sign in bit 126
+//! and the magnitude in the low 126 bits; a tame/wild pair on the same X
+//! fragment is a collision the solver turns into a private key.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use bitcoin::secp256k1::{self, Secp256k1};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+/// Work-file magic (`HEADW` in Kangaroo's source).
+const MAGIC: u32 = 0xFA6A_8001;
+/// Number of hash buckets in the on-disk table.
+const HASH_SIZE: usize = 1 << 18;
+
+/// Whether a packed distance belongs to a wild kangaroo.
+fn is_wild(d: u128) -> bool {
+    d >> 127 == 1
+}
+
+/// Bucket a DP entry hashes into on disk.
+fn bucket_of(x: u128) -> usize {
+    (x as u64 as usize) & (HASH_SIZE - 1)
+}
+
+/// An in-memory distinguished-point table plus its work-file header.
+pub struct DpTable {
+    /// Distinguished-point mask size in bits.
+    pub dp_bits: u32,
+    /// Inclusive key range the kangaroos walk, as 256-bit little-endian.
+    pub range_start: [u8; 32],
+    pub range_end: [u8; 32],
+    /// Target public key point.
+    pub pubkey_x: [u8; 32],
+    pub pubkey_y: [u8; 32],
+    /// Total kangaroo steps behind the table.
+    pub count: u64,
+    /// Accumulated wall-clock seconds.
+    pub time: f64,
+    /// DP entries: low 128 bits of X → packed distance.
+    points: HashMap<u128, u128>,
+}
+
+/// A tame/wild pair on the same X fragment, found while merging.
+pub struct Collision {
+    pub x: u128,
+    pub tame_d: u128,
+    pub wild_d: u128,
+}
+
+/// What a merge changed: new points, points we already had, collisions.
+pub struct MergeOutcome {
+    pub added: u64,
+    pub duplicates: u64,
+    pub collisions: Vec<Collision>,
+}
+
+/// What recording one solver DP did.
+pub enum DpInsert {
+    New,
+    /// Same X fragment, same kangaroo type: a cycle, not a solution.
+    Duplicate,
+    Collision(Collision),
+}
+
+impl DpTable {
+    /// An empty table for a fresh solver run.
+    pub fn new(
+        dp_bits: u32,
+        range_start: [u8; 32],
+        range_end: [u8; 32],
+        pubkey_x: [u8; 32],
+        pubkey_y: [u8; 32],
+    ) -> Self {
+        Self {
+            dp_bits,
+            range_start,
+            range_end,
+            pubkey_x,
+            pubkey_y,
+            count: 0,
+            time: 0.0,
+            points: HashMap::new(),
+        }
+    }
+
+    /// Number of distinguished points in the table.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Record one solver DP. Unlike [`DpTable::insert`], an existing entry
+    /// of the opposite kangaroo type is surfaced as a collision and a
+    /// same-type entry is reported so the solver can break the cycle.
+    pub fn record(&mut self, x: u128, d: u128) -> DpInsert {
+        match self.points.get(&x) {
+            None => {
+                self.points.insert(x, d);
+                DpInsert::New
+            }
+            Some(&existing) if is_wild(existing) != is_wild(d) => {
+                let (tame_d, wild_d) = if is_wild(d) { (existing, d) } else { (d, existing) };
+                DpInsert::Collision(Collision { x, tame_d, wild_d })
+            }
+            Some(_) => DpInsert::Duplicate,
+        }
+    }
+
+    /// Add one DP entry; `false` when the X fragment was already present.
+    pub fn insert(&mut self, x: u128, d: u128) -> bool {
+        self.points.insert(x, d).is_none()
+    }
+
+    /// Fold another table's points into this one. Points already present
+    /// with the same kangaroo type are duplicates; opposite types on the
+    /// same X fragment are collected as collisions for the solver.
+    pub fn merge(&mut self, other: &DpTable) -> MergeOutcome {
+        let mut outcome = MergeOutcome {
+            added: 0,
+            duplicates: 0,
+            collisions: Vec::new(),
+        };
+        for (&x, &d) in &other.points {
+            match self.points.get(&x) {
+                None => {
+                    self.insert(x, d);
+                    outcome.added += 1;
+                }
+                Some(&existing) if is_wild(existing) != is_wild(d) => {
+                    let (tame_d, wild_d) = if is_wild(d) { (existing, d) } else { (d, existing) };
+                    outcome.collisions.push(Collision { x, tame_d, wild_d });
+                }
+                Some(_) => outcome.duplicates += 1,
+            }
+        }
+        self.count += other.count;
+        self.time += other.time;
+        outcome
+    }
+}
+
+fn read_u32(r: &mut impl Read) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    r.read_exact(&mut buf).context("truncated work file")?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(r: &mut impl Read) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf).context("truncated work file")?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_u128(r: &mut impl Read) -> Result<u128> {
+    let mut buf = [0u8; 16];
+    r.read_exact(&mut buf).context("truncated work file")?;
+    Ok(u128::from_le_bytes(buf))
+}
+
+fn read_32(r: &mut impl Read) -> Result<[u8; 32]> {
+    let mut buf = [0u8; 32];
+    r.read_exact(&mut buf).context("truncated work file")?;
+    Ok(buf)
+}
+
+/// Read a Kangaroo work file into memory.
+pub fn read_work(path: &Path) -> Result<DpTable> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut r = BufReader::new(file);
+    let magic = read_u32(&mut r)?;
+    if magic != MAGIC {
+        bail!("{} is not a Kangaroo work file (bad magic)", path.display());
+    }
+    let version = read_u32(&mut r)?;
+    if version != 0 {
+        bail!("unsupported work file version {version}");
+    }
+    let dp_bits = read_u32(&mut r)?;
+    let range_start = read_32(&mut r)?;
+    let range_end = read_32(&mut r)?;
+    let pubkey_x = read_32(&mut r)?;
+    let pubkey_y = read_32(&mut r)?;
+    let count = read_u64(&mut r)?;
+    let time = f64::from_le_bytes(read_u64(&mut r)?.to_le_bytes());
+    let mut points = HashMap::new();
+    for _ in 0..HASH_SIZE {
+        let items = read_u32(&mut r)?;
+        let _max_items = read_u32(&mut r)?;
+        for _ in 0..items {
+            let x = read_u128(&mut r)?;
+            let d = read_u128(&mut r)?;
+            points.insert(x, d);
+        }
+    }
+    Ok(DpTable {
+        dp_bits,
+        range_start,
+        range_end,
+        pubkey_x,
+        pubkey_y,
+        count,
+        time,
+        points,
+    })
+}
+
+/// Write the table back out in Kangaroo's work-file layout.
+pub fn write_work(table: &DpTable, path: &Path) -> Result<()> {
+    let mut buckets: Vec<Vec<(u128, u128)>> = vec![Vec::new(); HASH_SIZE];
+    for (&x, &d) in &table.points {
+        buckets[bucket_of(x)].push((x, d));
+    }
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    let mut w = BufWriter::new(file);
+    w.write_all(&MAGIC.to_le_bytes())?;
+    w.write_all(&0u32.to_le_bytes())?;
+    w.write_all(&table.dp_bits.to_le_bytes())?;
+    w.write_all(&table.range_start)?;
+    w.write_all(&table.range_end)?;
+    w.write_all(&table.pubkey_x)?;
+    w.write_all(&table.pubkey_y)?;
+    w.write_all(&table.count.to_le_bytes())?;
+    w.write_all(&table.time.to_le_bytes())?;
+    for bucket in &buckets {
+        w.write_all(&(bucket.len() as u32).to_le_bytes())?;
+        w.write_all(&(bucket.len() as u32).to_le_bytes())?;
+        for (x, d) in bucket {
+            w.write_all(&x.to_le_bytes())?;
+            w.write_all(&d.to_le_bytes())?;
+        }
+    }
+    w.flush().context("flushing work file")?;
+    Ok(())
+}
+
+/// Merge DP work files into `out`; the first file's header wins, and the
+/// rest must cover the same key range at the same DP size.
+pub fn merge_files(inputs: &[PathBuf], out: &Path) -> Result<String> {
+    let (first, rest) = inputs.split_first().context("no input work files")?;
+    let mut table = read_work(first)?;
+    let mut collisions = 0usize;
+    for path in rest {
+        let other = read_work(path)?;
+        if other.dp_bits != table.dp_bits
+            || other.range_start != table.range_start
+            || other.range_end != table.range_end
+        {
+            bail!("{} has a different DP size or key range", path.display());
+        }
+        let outcome = table.merge(&other);
+        tracing::info!(
+            "merged {}: {} new point(s), {} duplicate(s)",
+            path.display(),
+            outcome.added,
+            outcome.duplicates,
+        );
+        for c in &outcome.collisions {
+            tracing::warn!(
+                "tame/wild collision at DP {:#x} (tame d {:#x}, wild d {:#x}); \
+                 the kangaroo solver can turn this into a private key",
+                c.x,
+                c.tame_d,
+                c.wild_d,
+            );
+        }
+        collisions += outcome.collisions.len();
+    }
+    write_work(&table, out)?;
+    Ok(format!(
+        "{} distinguished point(s) written to {} ({} tame/wild collision(s))",
+        table.len(),
+        out.display(),
+        collisions,
+    ))
+}
+
+/// Magnitude bits of a packed distance (bit 127 = wild, bit 126 = sign).
+const DISTANCE_MASK: u128 = (1 << 126) - 1;
+
+/// Low 128 bits of a point's X coordinate, the DP table key.
+fn x_fragment(point: &secp256k1::PublicKey) -> u128 {
+    let serialized = point.serialize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&serialized[17..33]);
+    u128::from_be_bytes(bytes)
+}
+
+/// A 256-bit integer in the work-file's little-endian layout.
+fn to_le_32(value: &BigUint) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    let bytes = value.to_bytes_le();
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+/// One kangaroo: its current point and the exponent distance it has
+/// accumulated from its start.
+struct Kangaroo {
+    point: secp256k1::PublicKey,
+    distance: BigUint,
+}
+
+/// In-memory Pollard's lambda run over one puzzle's interval.
+///
+/// The tame kangaroo starts on the known exponent `range_end`, the wild
+/// one on the target point; both follow the jump sequence determined by
+/// their current X coordinate, so once their paths touch they stay
+/// merged and the next distinguished point resolves the key.
+pub struct Solver {
+    secp: Secp256k1<secp256k1::All>,
+    target: secp256k1::PublicKey,
+    /// Jump table: point `2^i * G` and its distance `2^i`.
+    jumps: Vec<(secp256k1::PublicKey, BigUint)>,
+    tame: Kangaroo,
+    wild: Kangaroo,
+    table: DpTable,
+    /// Exponent the tame kangaroo started on.
+    tame_origin: BigUint,
+    dp_mask: u128,
+}
+
+impl Solver {
+    /// Build a solver for a puzzle with a known public key.
+    pub fn new(puzzle: &crate::puzzles::Puzzle) -> Result<Self> {
+        let target = match puzzle.pubkey_point {
+            // Loaded puzzles carry the point pre-parsed.
+            Some(bytes) => secp256k1::PublicKey::from_slice(&bytes)
+                .context("cached public key bytes are not a point")?,
+            None => {
+                let pubkey_hex = puzzle
+                    .public_key
+                    .as_deref()
+                    .with_context(|| format!("puzzle #{} has no public key", puzzle.number))?;
+                secp256k1::PublicKey::from_slice(
+                    &hex::decode(pubkey_hex.trim()).context("public_key is not hex")?,
+                )
+                .context("public_key is not a valid secp256k1 point")?
+            }
+        };
+        let (range_start, range_end) = puzzle.range()?;
+        if range_start > range_end {
+            bail!("puzzle #{}: empty key range", puzzle.number);
+        }
+        let width = &range_end - &range_start + BigUint::one();
+        // A DP density around 2^(bits/2 - 10) keeps the table well below
+        // the expected sqrt(width) walk length.
+        let dp_bits = ((width.bits() / 2).saturating_sub(10) as u32).clamp(2, 28);
+        // Mean jump ~= 2^k / k, aimed at sqrt(width).
+        let jump_count = ((width.bits() / 2 + 2) as usize).clamp(4, 120);
+        let secp = Secp256k1::new();
+        let jumps = (0..jump_count)
+            .map(|i| {
+                let distance = BigUint::one() << i;
+                let point = crate::keygen::secret_key_from_biguint(&distance)?.public_key(&secp);
+                Ok((point, distance))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let tame_origin = range_end.clone();
+        let tame = Kangaroo {
+            point: crate::keygen::secret_key_from_biguint(&tame_origin)?.public_key(&secp),
+            distance: BigUint::zero(),
+        };
+        let wild = Kangaroo {
+            point: target,
+            distance: BigUint::zero(),
+        };
+        let serialized = target.serialize_uncompressed();
+        let mut pubkey_x = [0u8; 32];
+        let mut pubkey_y = [0u8; 32];
+        pubkey_x.copy_from_slice(&serialized[1..33]);
+        pubkey_y.copy_from_slice(&serialized[33..65]);
+        pubkey_x.reverse();
+        pubkey_y.reverse();
+        let table = DpTable::new(
+            dp_bits,
+            to_le_32(&range_start),
+            to_le_32(&range_end),
+            pubkey_x,
+            pubkey_y,
+        );
+        Ok(Self {
+            secp,
+            target,
+            jumps,
+            tame,
+            wild,
+            table,
+            tame_origin,
+            dp_mask: (1u128 << dp_bits) - 1,
+        })
+    }
+
+    /// Advance both kangaroos one jump. Returns the private key when a
+    /// tame/wild collision resolves and verifies against the target point.
+    pub fn step(&mut self) -> Result<Option<secp256k1::SecretKey>> {
+        for wild in [false, true] {
+            if let Some(key) = self.advance(wild)? {
+                return Ok(Some(key));
+            }
+        }
+        Ok(None)
+    }
+
+    fn advance(&mut self, wild: bool) -> Result<Option<secp256k1::SecretKey>> {
+        let frag = x_fragment(if wild { &self.wild.point } else { &self.tame.point });
+        if frag & self.dp_mask == 0 {
+            let distance = if wild { &self.wild.distance } else { &self.tame.distance };
+            // Distances beyond 126 bits don't fit the packed format; no
+            // realistic walk gets anywhere near that.
+            if let Some(d) = distance.to_u128().filter(|d| d & !DISTANCE_MASK == 0) {
+                let packed = if wild { d | (1 << 127) } else { d };
+                match self.table.record(frag, packed) {
+                    DpInsert::New => {}
+                    // The same kangaroo type landing on a known DP means
+                    // the walk entered a cycle; jump it somewhere new.
+                    DpInsert::Duplicate => self.teleport(wild)?,
+                    DpInsert::Collision(collision) => {
+                        if let Some(key) = self.key_from_collision(&collision)? {
+                            return Ok(Some(key));
+                        }
+                    }
+                }
+            }
+        }
+        let (jump_point, jump_distance) = &self.jumps[(frag % self.jumps.len() as u128) as usize];
+        let kangaroo = if wild { &mut self.wild } else { &mut self.tame };
+        match kangaroo.point.combine(jump_point) {
+            Ok(point) => {
+                kangaroo.point = point;
+                kangaroo.distance += jump_distance;
+            }
+            // Landing exactly on the point at infinity is astronomically
+            // unlikely; restart the walk rather than erroring out.
+            Err(_) => self.teleport(wild)?,
+        }
+        self.table.count += 1;
+        Ok(None)
+    }
+
+    /// Move a kangaroo by a random offset to break a detected cycle.
+    fn teleport(&mut self, wild: bool) -> Result<()> {
+        let offset = BigUint::from(rand::random::<u64>() | 1);
+        let jump = crate::keygen::secret_key_from_biguint(&offset)?.public_key(&self.secp);
+        let kangaroo = if wild { &mut self.wild } else { &mut self.tame };
+        kangaroo.point = kangaroo
+            .point
+            .combine(&jump)
+            .context("teleport landed on infinity")?;
+        kangaroo.distance += offset;
+        Ok(())
+    }
+
+    /// Turn a tame/wild collision into the private key, if it is genuine:
+    /// `tame_origin + tame_d = key + wild_d (mod n)`. X-fragment clashes
+    /// between different points resolve to `None`.
+    fn key_from_collision(&self, collision: &Collision) -> Result<Option<secp256k1::SecretKey>> {
+        let n = crate::keygen::curve_order();
+        let tame_d = BigUint::from(collision.tame_d & DISTANCE_MASK);
+        let wild_d = BigUint::from(collision.wild_d & DISTANCE_MASK);
+        let candidate = (&self.tame_origin + tame_d + n - (wild_d % n)) % n;
+        let Ok(key) = crate::keygen::secret_key_from_biguint(&candidate) else {
+            return Ok(None);
+        };
+        if key.public_key(&self.secp) == self.target {
+            Ok(Some(key))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Run one fixed-duration kangaroo session on a pubkey-known puzzle,
+/// resuming the DP table from `DATA_DIR/kangaroo_<n>.work` when present
+/// and writing it back at session end.
+pub async fn run_session(
+    state: &std::sync::Arc<crate::state::AppState>,
+    puzzle: &crate::puzzles::Puzzle,
+) -> Vec<crate::checker::CheckResult> {
+    let duration =
+        std::time::Duration::from_secs(state.config.scheduler.session_duration_secs);
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let worker_state = std::sync::Arc::clone(state);
+    let worker_puzzle = puzzle.clone();
+    let worker_stop = std::sync::Arc::clone(&stop);
+    let handle = tokio::task::spawn_blocking(move || {
+        solver_loop(&worker_state, &worker_puzzle, &worker_stop)
+    });
+    tokio::time::sleep(duration).await;
+    stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    match handle.await {
+        Ok(Ok(found)) => found,
+        Ok(Err(err)) => {
+            tracing::warn!("kangaroo solver failed: {err:#}");
+            Vec::new()
+        }
+        Err(err) => {
+            tracing::warn!("kangaroo solver panicked: {err}");
+            Vec::new()
+        }
+    }
+}
+
+fn solver_loop(
+    state: &crate::state::AppState,
+    puzzle: &crate::puzzles::Puzzle,
+    stop: &std::sync::atomic::AtomicBool,
+) -> Result<Vec<crate::checker::CheckResult>> {
+    use std::sync::atomic::Ordering;
+
+    let mut solver = Solver::new(puzzle)?;
+    let work_path = state
+        .config
+        .data_dir
+        .join(format!("kangaroo_{}.work", puzzle.number));
+    if work_path.exists() {
+        match read_work(&work_path) {
+            Ok(table)
+                if table.range_start == solver.table.range_start
+                    && table.range_end == solver.table.range_end
+                    && table.dp_bits == solver.table.dp_bits =>
+            {
+                tracing::info!(
+                    "resuming kangaroo table with {} DP(s) from {}",
+                    table.len(),
+                    work_path.display()
+                );
+                solver.table = table;
+            }
+            Ok(_) => tracing::warn!(
+                "{} covers a different range or DP size; starting fresh",
+                work_path.display()
+            ),
+            Err(err) => tracing::warn!("ignoring {}: {err:#}", work_path.display()),
+        }
+    }
+    tracing::info!(
+        "kangaroo session on puzzle #{} (dp_bits {}, {} DPs so far)",
+        puzzle.number,
+        solver.table.dp_bits,
+        solver.table.len()
+    );
+    let started = std::time::Instant::now();
+    let mut found = Vec::new();
+    let mut steps = 0u64;
+    while !stop.load(Ordering::Relaxed) {
+        if let Some(key) = solver.step()? {
+            match crate::checker::check_private_key_against_puzzle(&key, puzzle)? {
+                Some(result) => {
+                    tracing::info!(
+                        "kangaroo: MATCH on puzzle #{} (key {})",
+                        puzzle.number,
+                        crate::checker::redact_secret(result.reveal_private_key())
+                    );
+                    state.stats.record_match();
+                    state.metrics.matches.inc();
+                    found.push(result);
+                    break;
+                }
+                None => tracing::warn!(
+                    "kangaroo solved the discrete log for puzzle #{} but the derived \
+                     address does not match; check the puzzle data",
+                    puzzle.number
+                ),
+            }
+        }
+        steps += 2;
+        if steps.is_multiple_of(1000) {
+            state.stats.record_checked(1000);
+            state
+                .metrics
+                .keys_checked
+                .with_label_values(&["kangaroo"])
+                .inc_by(1000);
+        }
+    }
+    solver.table.time += started.elapsed().as_secs_f64();
+    if let Err(err) = write_work(&solver.table, &work_path) {
+        tracing::warn!("failed to save kangaroo table: {err:#}");
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> DpTable {
+        DpTable {
+            dp_bits: 20,
+            range_start: [1; 32],
+            range_end: [2; 32],
+            pubkey_x: [3; 32],
+            pubkey_y: [4; 32],
+            count: 1000,
+            time: 1.5,
+            points: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_the_work_file_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save.work");
+        let mut t = table();
+        // Bit 127 marks a wild kangaroo's distance.
+        assert!(t.insert(7, 42));
+        assert!(t.insert(9, (1u128 << 127) | 42));
+        assert!(!t.insert(7, 43));
+        write_work(&t, &path).unwrap();
+        let back = read_work(&path).unwrap();
+        assert_eq!(back.dp_bits, 20);
+        assert_eq!(back.count, 1000);
+        assert_eq!(back.len(), 2);
+        assert_eq!(back.range_start, [1; 32]);
+    }
+
+    #[test]
+    fn solver_finds_a_small_discrete_log() {
+        let secp = Secp256k1::new();
+        let secret = BigUint::from(0xabcu32);
+        let key = crate::keygen::secret_key_from_biguint(&secret).unwrap();
+        let puzzle = crate::puzzles::Puzzle {
+            number: 12,
+            address: crate::checker::derive_bitcoin_address(
+                &key,
+                crate::checker::AddressType::Compressed,
+            )
+            .unwrap(),
+            range_start: "800".into(),
+            range_end: "fff".into(),
+            reward_btc: 0.0,
+            solved: false,
+            public_key: Some(hex::encode(key.public_key(&secp).serialize())),
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        };
+        let mut solver = Solver::new(&puzzle).unwrap();
+        // ~sqrt(2^12) * a generous constant; far more than the walk needs.
+        for _ in 0..200_000 {
+            if let Some(found) = solver.step().unwrap() {
+                assert_eq!(found.secret_bytes(), key.secret_bytes());
+                return;
+            }
+        }
+        panic!("kangaroo did not converge on a 12-bit interval");
+    }
+
+    #[test]
+    fn merging_detects_tame_wild_collisions() {
+        let mut a = table();
+        a.insert(7, 42);
+        a.insert(8, 5);
+        let mut b = table();
+        b.insert(7, (1u128 << 127) | 99);
+        b.insert(8, 5);
+        b.insert(9, 1);
+        let outcome = a.merge(&b);
+        assert_eq!(outcome.added, 1);
+        assert_eq!(outcome.duplicates, 1);
+        assert_eq!(outcome.collisions.len(), 1);
+        assert_eq!(outcome.collisions[0].tame_d, 42);
+        assert_eq!(outcome.collisions[0].wild_d, (1u128 << 127) | 99);
+        // Step counts accumulate across merges.
+        assert_eq!(a.count, 2000);
+    }
+}
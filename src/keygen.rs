@@ -0,0 +1,474 @@
+//! Random and stride-based private key generation inside a puzzle range.
+
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use bitcoin::secp256k1::SecretKey;
+use num_bigint::BigUint;
+use num_traits::{Num, One};
+
+use crate::u256::U256;
+
+/// The secp256k1 group order as limbs, for hot-path validation (checked
+/// against the parsed constant in the tests).
+const CURVE_ORDER_U256: U256 = U256::from_limbs([
+    0xbfd25e8cd0364141,
+    0xbaaedce6af48a03b,
+    0xfffffffffffffffe,
+    0xffffffffffffffff,
+]);
+
+/// The secp256k1 group order; keys must lie in `[1, order)`.
+pub(crate) fn curve_order() -> &'static BigUint {
+    static ORDER: OnceLock<BigUint> = OnceLock::new();
+    ORDER.get_or_init(|| {
+        BigUint::from_str_radix(
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            16,
+        )
+        .expect("curve order constant parses")
+    })
+}
+
+/// Generate a uniformly random private key in the closed interval
+/// `[range_start, range_end]`.
+///
+/// `gen_biguint_below` samples `[0, n)`, so the range size must include
+/// the +1 or `range_end` itself would be unreachable — puzzle solutions
+/// sit anywhere in the advertised range, including its top key.
+pub fn generate_random_key_in_range(
+    range_start: &BigUint,
+    range_end: &BigUint,
+) -> Result<SecretKey> {
+    generate_random_key_in_range_with(&mut rand::thread_rng(), range_start, range_end)
+}
+
+/// Like [`generate_random_key_in_range`] with a caller-supplied RNG, for
+/// seeded deterministic sessions (`SEARCH_SEED`).
+///
+/// The draw itself runs entirely on stack-allocated [`U256`] values; the
+/// `BigUint` bounds are converted once per call and nothing in the loop
+/// touches the heap.
+pub fn generate_random_key_in_range_with<R: rand::Rng>(
+    rng: &mut R,
+    range_start: &BigUint,
+    range_end: &BigUint,
+) -> Result<SecretKey> {
+    if range_start > range_end {
+        bail!("empty key range: start exceeds end");
+    }
+    let (Some(start), Some(end)) = (
+        U256::from_biguint(range_start),
+        U256::from_biguint(range_end),
+    ) else {
+        bail!("key range exceeds 256 bits");
+    };
+    let span = end.checked_sub(&start).expect("order checked above");
+    let offset = match span.checked_add(&U256::ONE) {
+        Some(size) => U256::random_below(rng, &size),
+        // The span covers every 256-bit value; nothing to reject.
+        None => U256::random(rng),
+    };
+    let key_value = start.checked_add(&offset).expect("offset is within the span");
+    secret_key_from_u256(&key_value)
+}
+
+/// Deterministic lattice walk over a key range: yields
+/// `start + offset`, `start + offset + stride`, … up to `range_end`.
+///
+/// With every worker given a distinct offset below a shared stride, a
+/// fleet of machines covers disjoint lattices of the same puzzle range
+/// with no coordination traffic.
+pub struct StrideScan {
+    next: U256,
+    stride: U256,
+    end: U256,
+    /// Set when the lattice walked past 2^256 (or the range never fit).
+    overflowed: bool,
+}
+
+impl StrideScan {
+    /// A scan starting at `range_start + offset`, stepping by `stride`
+    /// (treated as 1 if zero, to guarantee progress).
+    pub fn new(range_start: &BigUint, range_end: &BigUint, offset: u64, stride: u64) -> Self {
+        let next = U256::from_biguint(range_start)
+            .and_then(|start| start.checked_add(&U256::from_u64(offset)));
+        let end = U256::from_biguint(range_end);
+        Self {
+            next: next.unwrap_or(U256::ZERO),
+            stride: U256::from_u64(stride.max(1)),
+            end: end.unwrap_or(U256::ZERO),
+            overflowed: next.is_none() || end.is_none(),
+        }
+    }
+
+    /// The next unchecked key, i.e. the checkpointing cursor.
+    pub fn position(&self) -> BigUint {
+        self.next.to_biguint()
+    }
+
+    /// Fast-forward to the first lattice point at or past `position`, for
+    /// resuming from a checkpoint. Positions behind the scan are ignored.
+    /// Resume-time only, so the arithmetic can stay on `BigUint`.
+    pub fn seek(&mut self, position: &BigUint) {
+        let current = self.position();
+        if *position > current {
+            let stride = self.stride.to_biguint();
+            let steps = (position - &current + &stride - BigUint::one()) / &stride;
+            match U256::from_biguint(&(current + steps * stride)) {
+                Some(next) => self.next = next,
+                None => self.overflowed = true,
+            }
+        }
+    }
+
+    /// The next key on the lattice, or `None` once the range is exhausted.
+    pub fn next_key(&mut self) -> Option<Result<SecretKey>> {
+        if self.overflowed || self.next > self.end {
+            return None;
+        }
+        let key = secret_key_from_u256(&self.next);
+        match self.next.checked_add(&self.stride) {
+            Some(next) => self.next = next,
+            None => self.overflowed = true,
+        }
+        Some(key)
+    }
+}
+
+/// Pseudorandom walk over a key range: a random starting point, then
+/// forward/backward hops whose direction and length are mixed out of the
+/// current position.
+///
+/// Successive keys land close together, so the walk reuses warm caches
+/// where independent random draws touch cold memory every time, while
+/// the position-derived hops still spread coverage across the range.
+/// Selected per puzzle with `"strategy": "walk"` in `puzzles.json`.
+pub struct RandomWalk {
+    /// Offset of the current position from `range_start`.
+    current: U256,
+    width: U256,
+    range_start: U256,
+}
+
+impl RandomWalk {
+    /// Start a walk at a point drawn from the caller's RNG, so seeded
+    /// sessions stay reproducible.
+    pub fn new<R: rand::Rng>(
+        rng: &mut R,
+        range_start: &BigUint,
+        range_end: &BigUint,
+    ) -> Result<Self> {
+        if range_start > range_end {
+            bail!("empty key range: start exceeds end");
+        }
+        let (Some(start), Some(end)) = (
+            U256::from_biguint(range_start),
+            U256::from_biguint(range_end),
+        ) else {
+            bail!("key range exceeds 256 bits");
+        };
+        let span = end.checked_sub(&start).expect("order checked above");
+        let width = span
+            .checked_add(&U256::ONE)
+            .context("key range covers the whole 256-bit space")?;
+        Ok(Self {
+            current: U256::random_below(rng, &width),
+            width,
+            range_start: start,
+        })
+    }
+
+    /// The key at the current position; afterwards the walk hops to its
+    /// next position, wrapping at the range bounds.
+    pub fn next_key(&mut self) -> Result<SecretKey> {
+        let value = self
+            .range_start
+            .checked_add(&self.current)
+            .expect("position stays inside the range");
+        let key = secret_key_from_u256(&value);
+        // splitmix64 of the position's low limb decides the hop: bit 16
+        // picks the direction, the low 16 bits the length (1..=65536).
+        let mixed = splitmix64(self.current.low_u64());
+        let mut jump = (mixed & 0xffff) + 1;
+        if self.width.bits() <= 64 {
+            // A hop that is a multiple of the width would pin the walk in
+            // place forever (the next hop is derived from the position).
+            jump %= self.width.low_u64();
+            if jump == 0 {
+                jump = 1;
+            }
+        }
+        let jump = U256::from_u64(jump);
+        self.current = if mixed & 0x1_0000 == 0 {
+            match self.current.checked_add(&jump) {
+                Some(sum) if sum < self.width => sum,
+                // Wrapped past the range end (possibly past 2^256): the
+                // overshoot is `jump` short of a full width.
+                Some(sum) => sum.checked_sub(&self.width).expect("sum exceeds width"),
+                None => {
+                    let overshoot = self.width.checked_sub(&jump).expect("jump below width");
+                    self.current.checked_sub(&overshoot).expect("wraps into range")
+                }
+            }
+        } else {
+            match self.current.checked_sub(&jump) {
+                Some(diff) => diff,
+                None => {
+                    let underflow = jump.checked_sub(&self.current).expect("jump above current");
+                    self.width.checked_sub(&underflow).expect("wraps into range")
+                }
+            }
+        };
+        key
+    }
+}
+
+/// Hybrid random+sequential scan: a random draw picks an anchor, then the
+/// next `window` keys are yielded consecutively before the scan re-anchors
+/// somewhere else in the range.
+///
+/// Consecutive keys let the checker walk the public key forward by point
+/// addition — sequential-scan speed — while the random anchors keep the
+/// coverage properties of independent draws. Selected per puzzle with
+/// `"strategy": "hybrid"`; the window comes from `HYBRID_WINDOW` and the
+/// `/window` bot command.
+pub struct HybridScan {
+    range_start: U256,
+    range_end: U256,
+    next: U256,
+    /// Keys left in the current window; 0 forces a re-anchor.
+    remaining: u64,
+}
+
+impl HybridScan {
+    pub fn new(range_start: &BigUint, range_end: &BigUint) -> Result<Self> {
+        if range_start > range_end {
+            bail!("empty key range: start exceeds end");
+        }
+        let (Some(start), Some(end)) = (
+            U256::from_biguint(range_start),
+            U256::from_biguint(range_end),
+        ) else {
+            bail!("key range exceeds 256 bits");
+        };
+        Ok(Self {
+            range_start: start,
+            range_end: end,
+            next: U256::ZERO,
+            remaining: 0,
+        })
+    }
+
+    /// The next key, plus whether the scan just re-anchored (on which the
+    /// caller must re-derive any incrementally-walked point). `window` is
+    /// read per anchor, so a runtime retune applies from the next jump.
+    pub fn next_key<R: rand::Rng>(
+        &mut self,
+        rng: &mut R,
+        window: u64,
+    ) -> Result<(SecretKey, bool)> {
+        let jumped = self.remaining == 0 || self.next > self.range_end;
+        if jumped {
+            let span = self
+                .range_end
+                .checked_sub(&self.range_start)
+                .expect("order checked at construction");
+            let offset = match span.checked_add(&U256::ONE) {
+                Some(size) => U256::random_below(rng, &size),
+                None => U256::random(rng),
+            };
+            self.next = self
+                .range_start
+                .checked_add(&offset)
+                .expect("offset is within the span");
+            self.remaining = window.max(1);
+        }
+        let key = secret_key_from_u256(&self.next);
+        self.remaining -= 1;
+        match self.next.checked_add(&U256::ONE) {
+            Some(next) => self.next = next,
+            // The window ran into 2^256; the next call re-anchors.
+            None => self.remaining = 0,
+        }
+        key.map(|key| (key, jumped))
+    }
+}
+
+/// SplitMix64 finalizer, the mixing function behind the walk's hops.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// Convert a big integer into a 32-byte secp256k1 secret key, rejecting
+/// zero and values at or above the curve order.
+pub fn secret_key_from_biguint(value: &BigUint) -> Result<SecretKey> {
+    let value = U256::from_biguint(value)
+        .context("key value is not below the secp256k1 curve order")?;
+    secret_key_from_u256(&value)
+}
+
+/// [`secret_key_from_biguint`] on the hot-path representation, with no
+/// heap traffic.
+///
+/// The intermediate byte buffer is wiped before returning so the key
+/// lives only inside the returned `SecretKey`.
+pub fn secret_key_from_u256(value: &U256) -> Result<SecretKey> {
+    use zeroize::Zeroize;
+    if value.is_zero() {
+        bail!("zero is not a valid secret key");
+    }
+    if *value >= CURVE_ORDER_U256 {
+        bail!("key value is not below the secp256k1 curve order");
+    }
+    let mut bytes = value.to_be_bytes();
+    let key =
+        SecretKey::from_slice(&bytes).context("key value is not a valid secp256k1 secret key");
+    bytes.zeroize();
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Zero;
+
+    #[test]
+    fn generated_keys_stay_in_range() {
+        let start = BigUint::from_str_radix("80", 16).unwrap();
+        let end = BigUint::from_str_radix("ff", 16).unwrap();
+        for _ in 0..100 {
+            let key = generate_random_key_in_range(&start, &end).unwrap();
+            let value = BigUint::from_bytes_be(&key.secret_bytes());
+            assert!(value >= start);
+            assert!(value <= end);
+        }
+    }
+
+    #[test]
+    fn both_endpoints_are_reachable() {
+        // A two-key range must produce both keys; with 200 draws the odds
+        // of missing one side are 2^-199.
+        let start = BigUint::from(0x80u32);
+        let end = BigUint::from(0x81u32);
+        let mut seen_start = false;
+        let mut seen_end = false;
+        for _ in 0..200 {
+            let value = BigUint::from_bytes_be(
+                &generate_random_key_in_range(&start, &end).unwrap().secret_bytes(),
+            );
+            seen_start |= value == start;
+            seen_end |= value == end;
+        }
+        assert!(seen_start, "range start never sampled");
+        assert!(seen_end, "range end never sampled");
+    }
+
+    #[test]
+    fn degenerate_and_invalid_ranges() {
+        // A single-key range always yields that key.
+        let only = BigUint::from(42u32);
+        let key = generate_random_key_in_range(&only, &only).unwrap();
+        assert_eq!(BigUint::from_bytes_be(&key.secret_bytes()), only);
+        // Inverted and zero-key ranges are errors, not panics.
+        let one = BigUint::one();
+        assert!(generate_random_key_in_range(&only, &one).is_err());
+        let zero = BigUint::zero();
+        assert!(generate_random_key_in_range(&zero, &zero).is_err());
+    }
+
+    #[test]
+    fn rejects_zero_and_out_of_order_values() {
+        assert!(secret_key_from_biguint(&BigUint::zero()).is_err());
+        assert!(secret_key_from_biguint(curve_order()).is_err());
+        let max_valid = curve_order() - BigUint::one();
+        assert!(secret_key_from_biguint(&max_valid).is_ok());
+        // The limb constant must be the same number as the parsed order.
+        assert_eq!(&CURVE_ORDER_U256.to_biguint(), curve_order());
+    }
+
+    #[test]
+    fn seeded_draws_are_reproducible() {
+        use rand::SeedableRng;
+        let start = BigUint::from(0x1000u32);
+        let end = BigUint::from(0xffffu32);
+        let draw = |seed: u64| -> Vec<BigUint> {
+            let mut rng = rand_chacha::ChaCha12Rng::seed_from_u64(seed);
+            (0..16)
+                .map(|_| {
+                    let key =
+                        generate_random_key_in_range_with(&mut rng, &start, &end).unwrap();
+                    BigUint::from_bytes_be(&key.secret_bytes())
+                })
+                .collect()
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+    }
+
+    #[test]
+    fn stride_scan_covers_its_lattice_and_stops() {
+        let start = BigUint::from(0x100u32);
+        let end = BigUint::from(0x10au32);
+        // Offset 1, stride 4 over [0x100, 0x10a] hits 0x101, 0x105, 0x109.
+        let mut scan = StrideScan::new(&start, &end, 1, 4);
+        let mut values = Vec::new();
+        while let Some(key) = scan.next_key() {
+            values.push(BigUint::from_bytes_be(&key.unwrap().secret_bytes()));
+        }
+        let expected: Vec<BigUint> =
+            [0x101u32, 0x105, 0x109].iter().map(|&v| BigUint::from(v)).collect();
+        assert_eq!(values, expected);
+        assert!(scan.next_key().is_none());
+    }
+
+    #[test]
+    fn random_walk_stays_in_range_and_keeps_moving() {
+        use rand::SeedableRng;
+        // Position-derived hops cycle after ~sqrt(width) steps, so use a
+        // range wide enough that 256 steps stay collision-free.
+        let start = BigUint::from(0x4000_0000u32);
+        let end = BigUint::from(0x7fff_ffffu32);
+        let mut rng = rand_chacha::ChaCha12Rng::seed_from_u64(3);
+        let mut walk = RandomWalk::new(&mut rng, &start, &end).unwrap();
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..256 {
+            let value = BigUint::from_bytes_be(&walk.next_key().unwrap().secret_bytes());
+            assert!(value >= start && value <= end);
+            seen.insert(value);
+        }
+        assert!(seen.len() > 250, "walk revisited too much: {}", seen.len());
+    }
+
+    #[test]
+    fn hybrid_scan_anchors_then_runs_sequentially() {
+        use rand::SeedableRng;
+        let start = BigUint::from(0x1000_0000u32);
+        let end = BigUint::from(0x1fff_ffffu32);
+        let mut rng = rand_chacha::ChaCha12Rng::seed_from_u64(11);
+        let mut scan = HybridScan::new(&start, &end).unwrap();
+        let mut previous: Option<BigUint> = None;
+        for i in 0..24 {
+            let (key, jumped) = scan.next_key(&mut rng, 8).unwrap();
+            let value = BigUint::from_bytes_be(&key.secret_bytes());
+            assert!(value >= start && value <= end);
+            assert_eq!(jumped, i % 8 == 0, "window boundary at key {i}");
+            if let Some(previous) = previous {
+                if !jumped {
+                    assert_eq!(value, previous + 1u32, "window is not sequential");
+                }
+            }
+            previous = Some(value);
+        }
+    }
+
+    #[test]
+    fn biguint_round_trips_through_secret_key() {
+        let value = BigUint::from(0xdeadbeefu32);
+        let key = secret_key_from_biguint(&value).unwrap();
+        assert_eq!(BigUint::from_bytes_be(&key.secret_bytes()), value);
+    }
+}
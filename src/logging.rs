@@ -0,0 +1,137 @@
+//! Tracing subscriber setup.
+//!
+//! `RUST_LOG` controls the filter (tracing `EnvFilter` syntax). `LOG_FORMAT`
+//! selects the output format:
+//!
+//! * `text` (default) — the human-readable console format.
+//! * `json` — one structured JSON object per line (timestamp, level, target,
+//!   fields), for ingestion into Loki/ELK and friends.
+//!
+//! When `LOG_DIR` is set the log is additionally written to daily-rotated
+//! files `<LOG_DIR>/bot.log.<date>`; `LOG_KEEP` (default 7) old files are
+//! retained, pruned at startup.
+//!
+//! With the `otel` cargo feature, spans are additionally exported over OTLP
+//! when `OTEL_EXPORTER_OTLP_ENDPOINT` is set (see `otel.rs`).
+
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+const LOG_FILE_PREFIX: &str = "bot.log";
+
+/// Handle for swapping the filter at runtime (see [`set_filter`]).
+static RELOAD_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+fn json_format() -> bool {
+    matches!(
+        std::env::var("LOG_FORMAT")
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_str(),
+        "json"
+    )
+}
+
+/// Delete rotated `bot.log.*` files beyond the retention count.
+fn prune_log_dir(dir: &std::path::Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let prefix = format!("{LOG_FILE_PREFIX}.");
+    let mut logs: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+    logs.sort(); // Date suffixes sort chronologically.
+    while logs.len() > keep {
+        let oldest = logs.remove(0);
+        let _ = std::fs::remove_file(&oldest);
+    }
+}
+
+/// The console or file formatting layer, in the configured format.
+fn fmt_layer<S>(writer: NonBlocking, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    if json_format() {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_writer(writer)
+            .with_ansi(false)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi)
+            .boxed()
+    }
+}
+
+/// Install the global subscriber according to the environment.
+///
+/// The returned guard must stay alive for the process lifetime; dropping it
+/// stops the background log writer.
+pub fn init() -> Option<WorkerGuard> {
+    let log_dir = std::env::var("LOG_DIR").ok().map(std::path::PathBuf::from);
+    let (writer, guard, ansi) = match log_dir {
+        Some(dir) => {
+            let keep = std::env::var("LOG_KEEP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(7);
+            prune_log_dir(&dir, keep);
+            let appender = tracing_appender::rolling::daily(&dir, LOG_FILE_PREFIX);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            (writer, guard, false)
+        }
+        // Stdout belongs to the JSON-RPC protocol in embedding mode.
+        None if crate::stdio::enabled() => {
+            let (writer, guard) = tracing_appender::non_blocking(std::io::stderr());
+            (writer, guard, false)
+        }
+        None => {
+            let (writer, guard) = tracing_appender::non_blocking(std::io::stdout());
+            (writer, guard, true)
+        }
+    };
+
+    let (filter, handle) = reload::Layer::new(env_filter());
+    let _ = RELOAD_HANDLE.set(handle);
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(writer, ansi));
+    #[cfg(feature = "otel")]
+    let registry = registry.with(crate::otel::layer());
+    registry.init();
+    Some(guard)
+}
+
+/// Replace the active log filter without restarting.
+///
+/// `directives` uses the `EnvFilter` syntax, e.g. `debug` or
+/// `info,btc_lotto_puzzles_bot::checker=debug`. The change lasts until the
+/// next restart (which re-reads `RUST_LOG`).
+pub fn set_filter(directives: &str) -> Result<()> {
+    let filter = EnvFilter::try_new(directives)
+        .with_context(|| format!("invalid filter directives {directives:?}"))?;
+    RELOAD_HANDLE
+        .get()
+        .context("logging not initialized")?
+        .reload(filter)
+        .context("swapping the log filter")
+}
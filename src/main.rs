@@ -0,0 +1,447 @@
+mod audit;
+mod backup;
+mod bloom;
+mod bsgs;
+mod buildinfo;
+mod chain;
+mod checker;
+mod cli;
+mod cluster;
+mod config;
+mod control;
+#[cfg(feature = "cuda")]
+mod cuda;
+#[cfg(unix)]
+mod daemon;
+mod email;
+mod exhaustive;
+mod exporter;
+mod feed;
+mod fsutil;
+mod gossip;
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod http;
+mod journal;
+mod kangaroo;
+mod keygen;
+mod logging;
+mod metrics;
+mod mqtt;
+mod notify;
+#[cfg(feature = "otel")]
+mod otel;
+mod planner;
+mod pool;
+mod price;
+mod progress;
+mod puzzles;
+mod redisq;
+mod roles;
+mod rotation;
+mod scheduler;
+mod searched;
+mod secrets;
+#[cfg(windows)]
+mod service;
+mod signal;
+mod snapshot;
+mod solutions;
+mod state;
+mod stdio;
+mod sweep;
+mod telegram;
+mod u256;
+mod watchdog;
+mod webhook;
+mod workfile;
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+use crate::puzzles::PuzzleCollection;
+use crate::state::AppState;
+use crate::telegram::TelegramBot;
+
+fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    // Encrypted secrets become env vars before anything reads the config,
+    // so the rest of the bot never knows where a credential came from.
+    secrets::load_into_env().context("loading encrypted secrets")?;
+    // One-shot subcommands skip the bot entirely: no puzzle file, no
+    // Telegram, no servers.
+    let mut cli = <cli::Cli as clap::Parser>::parse();
+    if let Some(command) = cli.command.take() {
+        match command {
+            // `resume` and `demo` start the full bot after arranging the
+            // environment; everything else is one-shot.
+            cli::Command::Resume(args) => std::env::set_var("SNAPSHOT_IMPORT", &args.snapshot),
+            cli::Command::Demo => cli::prepare_demo()?,
+            command => return cli::run(command),
+        }
+    }
+    // Detaching must happen before the runtime spawns threads.
+    #[cfg(unix)]
+    if cli.daemon {
+        daemon::daemonize()?;
+    }
+    #[cfg(windows)]
+    if cli.daemon {
+        anyhow::bail!("--daemon is Unix-only; use `service install` on Windows");
+    }
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("building the tokio runtime")?
+        .block_on(run_bot(cli))
+}
+
+async fn run_bot(cli: cli::Cli) -> Result<()> {
+    let _log_guard = logging::init();
+
+    tracing::info!(
+        "starting {} v{} ({})",
+        env!("CARGO_PKG_NAME"),
+        buildinfo::VERSION,
+        buildinfo::GIT_HASH
+    );
+    let (config, mut config_problems) = Config::from_env_checked();
+    config_problems.extend(config.validate());
+    if !config_problems.is_empty() {
+        for problem in &config_problems {
+            tracing::error!("config: {problem}");
+        }
+        anyhow::bail!(
+            "refusing to start with {} configuration problem(s); see the log or run `validate`",
+            config_problems.len()
+        );
+    }
+    fsutil::ensure_restricted_dir(&config.data_dir)?;
+    fsutil::ensure_restricted_dir(&config.progress_dir)?;
+
+    // Restore a migrated snapshot before anything touches the state files.
+    let imported = match std::env::var("SNAPSHOT_IMPORT") {
+        Ok(path) => Some(snapshot::import(std::path::Path::new(&path), &config)?),
+        Err(_) => None,
+    };
+
+    let puzzles = PuzzleCollection::load(&config.puzzle_file)?;
+    if cli.dry_run {
+        return cli::dry_run(&config, &puzzles);
+    }
+    let solutions = solutions::SolutionStore::open_from_env(&config.solutions_file)?;
+    tracing::info!(
+        "{} puzzles loaded ({} solved, {} unsolved)",
+        puzzles.all().len(),
+        puzzles.solved_count(),
+        puzzles.unsolved_count()
+    );
+
+    // One-shot planner: write per-machine partitions and exit.
+    if let Ok(spec) = std::env::var("PLAN_MACHINES") {
+        let number: u32 = std::env::var("PLAN_PUZZLE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .context("PLAN_MACHINES needs PLAN_PUZZLE")?;
+        let summary = planner::plan_to_dir(&config, &puzzles, number, &spec)?;
+        tracing::info!("{summary}");
+        return Ok(());
+    }
+
+    let state = Arc::new(AppState::new(config, puzzles, solutions));
+    if state.chain.is_some() {
+        tracing::info!("chain backend enabled (CHAIN_BACKEND)");
+    }
+    // Hand imported keyhunt/BitCrack ranges to the coordinator ahead of
+    // fresh slices.
+    if let Ok(path) = std::env::var("WORK_IMPORT_FILE") {
+        let puzzle = std::env::var("WORK_IMPORT_PUZZLE")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok());
+        match (&state.coordinator, puzzle) {
+            (Some(coordinator), Some(number)) => {
+                let ranges = workfile::read_ranges(std::path::Path::new(&path))?;
+                tracing::info!("imported {} range(s) for puzzle #{number} from {path}", ranges.len());
+                coordinator.queue_import(number, ranges);
+            }
+            (None, _) => tracing::warn!("WORK_IMPORT_FILE needs CLUSTER_COORDINATOR=true; ignored"),
+            (_, None) => tracing::warn!("WORK_IMPORT_FILE needs WORK_IMPORT_PUZZLE; ignored"),
+        }
+    }
+    // Fold community Kangaroo DP tables into one work file under DATA_DIR.
+    if let Ok(files) = std::env::var("DP_MERGE_FILES") {
+        let inputs: Vec<std::path::PathBuf> = files
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(std::path::PathBuf::from)
+            .collect();
+        let out = state.config.data_dir.join("kangaroo.work");
+        match kangaroo::merge_files(&inputs, &out) {
+            Ok(summary) => tracing::info!("{summary}"),
+            Err(err) => tracing::error!("DP table merge failed: {err:#}"),
+        }
+    }
+    // Embedding mode owns stdin/stdout and replaces the other interfaces.
+    let stdio_rpc = stdio::enabled();
+    let bot = match (&state.config.telegram_token, state.config.telegram_chat_id) {
+        _ if stdio_rpc => None,
+        (Some(token), Some(chat_id)) => Some(TelegramBot::new(
+            token.clone(),
+            chat_id,
+            Arc::clone(&state.metrics),
+        )),
+        _ => {
+            tracing::warn!("TELEGRAM_BOT_TOKEN/TELEGRAM_CHAT_ID not set; running without Telegram");
+            None
+        }
+    };
+    {
+        let dir = &state.config.progress_dir;
+        let cursors = progress::load_dir(dir, &state.puzzles())?;
+        tracing::info!("loaded {} progress cursor(s) from {}", cursors.len(), dir.display());
+        *state.cursors.lock().unwrap() = cursors;
+    }
+    if let Some(snapshot) = imported {
+        state.stats.restore(
+            snapshot.stats.keys_checked,
+            snapshot.stats.matches_found,
+            snapshot.stats.sessions_run,
+        );
+        if let Some(number) = snapshot.focused_puzzle {
+            state.set_focus(Some(number));
+        }
+        tracing::info!(
+            "resuming from snapshot created {}: {} keys checked, {} match(es), {} session(s), focus {}",
+            snapshot.created_at,
+            snapshot.stats.keys_checked,
+            snapshot.stats.matches_found,
+            snapshot.stats.sessions_run,
+            snapshot
+                .focused_puzzle
+                .map(|n| format!("puzzle #{n}"))
+                .unwrap_or_else(|| "none".to_string()),
+        );
+    }
+
+    let email = match email::EmailNotifier::from_env() {
+        Ok(Some(notifier)) => {
+            tracing::info!("email notifications enabled");
+            Some(notifier)
+        }
+        Ok(None) => None,
+        Err(err) => {
+            tracing::error!("email notifier misconfigured, continuing without: {err:#}");
+            None
+        }
+    };
+    let webhook = webhook::WebhookNotifier::from_env();
+    if webhook.is_some() {
+        tracing::info!("webhook notifications enabled");
+    }
+    let mut sinks: Vec<Arc<dyn notify::Notifier>> = Vec::new();
+    sinks.push(Arc::new(notify::EventBus(state.events.clone())));
+    sinks.push(Arc::clone(&state.feed) as Arc<dyn notify::Notifier>);
+    if let Some(bot) = &bot {
+        sinks.push(Arc::new(bot.clone()));
+    }
+    if let Some(email) = email {
+        sinks.push(Arc::new(email));
+    }
+    if let Some(webhook) = webhook {
+        sinks.push(Arc::new(webhook));
+    }
+    if let Some(mqtt) = mqtt::MqttPublisher::from_env() {
+        tracing::info!("MQTT publishing enabled");
+        sinks.push(Arc::new(mqtt));
+    }
+    if let Some(signal) = signal::SignalNotifier::from_env() {
+        tracing::info!("Signal notifications enabled");
+        sinks.push(Arc::new(signal));
+    }
+    let notifier = Arc::new(notify::Fanout::new(sinks, Arc::clone(&state.metrics)));
+    state.set_notifier(Arc::clone(&notifier));
+
+    // Sanity-check the key→address pipeline against historically solved
+    // puzzles before burning any CPU on it; a dependency upgrade that
+    // breaks derivation would otherwise fail silently forever.
+    if let Err(err) = checker::self_test() {
+        tracing::error!("checker self-test failed: {err:#}");
+        if !notifier.is_empty() {
+            notifier
+                .dispatch(&notify::Event::Alert(format!(
+                    "🛑 Refusing to start: checker self-test failed: {err:#}"
+                )))
+                .await;
+        }
+        return Err(err.context("checker self-test failed"));
+    }
+
+    if !notifier.is_empty() {
+        #[allow(unused_mut)]
+        let mut started = String::from("🤖 BTC puzzle bot started");
+        #[cfg(feature = "cuda")]
+        if let Some(line) = cuda::startup_line() {
+            started.push_str(&format!("\n{line}"));
+        }
+        notifier
+            .dispatch(&notify::Event::Lifecycle(started))
+            .await;
+    }
+    if bot.is_some() {
+        // Redeliver matches that were found but never acknowledged; the
+        // journal entry is acknowledged on Telegram delivery.
+        match state.journal.pending() {
+            Ok(pending) => {
+                for entry in pending {
+                    tracing::warn!(
+                        "redelivering journaled match for puzzle #{}",
+                        entry.result.puzzle_number
+                    );
+                    if notifier
+                        .delivered_via(&notify::Event::Solve(entry.result.clone()), "telegram")
+                        .await
+                    {
+                        if let Err(err) = state.journal.mark_delivered(entry.id) {
+                            tracing::warn!("failed to acknowledge journal entry: {err:#}");
+                        }
+                    }
+                }
+            }
+            Err(err) => tracing::error!("failed to read match journal: {err:#}"),
+        }
+    }
+    if let Some(bot) = &bot {
+        let bot = bot.clone();
+        let state = Arc::clone(&state);
+        tokio::spawn(async move { bot.run_command_loop(state).await });
+    }
+
+    #[cfg(feature = "grpc")]
+    if let Ok(listen) = std::env::var("GRPC_LISTEN") {
+        let listen: std::net::SocketAddr = listen.parse()?;
+        let grpc_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = grpc::serve(grpc_state, listen).await {
+                tracing::error!("gRPC server exited: {err:#}");
+            }
+        });
+    }
+
+    #[cfg(unix)]
+    if let Some(socket) = state.config.control_socket.clone() {
+        let control_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = control::serve(control_state, &socket).await {
+                tracing::error!("control socket exited: {err:#}");
+            }
+        });
+    }
+
+    if stdio_rpc {
+        let rpc_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = stdio::serve(rpc_state).await {
+                tracing::error!("stdio JSON-RPC loop exited: {err:#}");
+            }
+        });
+    }
+
+    if let Some(listen) = state.config.http_listen.filter(|_| !stdio_rpc) {
+        let http_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(err) = http::serve(http_state, listen).await {
+                tracing::error!("HTTP server exited: {err:#}");
+            }
+        });
+    }
+
+    if !state.config.gossip_peers.is_empty() {
+        let gossip_state = Arc::clone(&state);
+        let peers = state.config.gossip_peers.clone();
+        let interval = std::time::Duration::from_secs(state.config.gossip_interval_secs.max(1));
+        tokio::spawn(async move { gossip::run(gossip_state, peers, interval).await });
+    }
+
+    // SIGHUP re-reads the puzzle file, SIGUSR1 dumps a stats snapshot to the
+    // log — the headless counterparts of /stats and the reload command.
+    #[cfg(unix)]
+    {
+        let signal_state = Arc::clone(&state);
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        let mut usr1 =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined1())?;
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => match signal_state.reload_puzzles() {
+                        Ok(summary) => tracing::info!("SIGHUP: {summary}"),
+                        Err(err) => tracing::error!("SIGHUP reload failed: {err:#}"),
+                    },
+                    _ = usr1.recv() => {
+                        tracing::info!(
+                            "SIGUSR1 stats dump: {}; {}",
+                            signal_state.status_text().replace('\n', ", "),
+                            signal_state.stats_text().replace('\n', ", ")
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    let scheduler_state = Arc::clone(&state);
+    let scheduler_notifier = Arc::clone(&notifier);
+    let scheduler = tokio::spawn(async move {
+        scheduler::run(scheduler_state, scheduler_notifier).await
+    });
+
+    wait_for_shutdown().await?;
+    tracing::info!("shutdown requested");
+    state.request_shutdown();
+    #[cfg(unix)]
+    if cli.daemon {
+        daemon::cleanup();
+    }
+
+    {
+        let cursors = state.cursors.lock().unwrap().clone();
+        if let Err(err) = progress::save_dir(&state.config.progress_dir, &cursors) {
+            tracing::error!("failed to save progress cursors: {err:#}");
+        }
+    }
+
+    if !notifier.is_empty() {
+        let summary = format!("🛑 Bot shutting down\n{}", state.stats_text());
+        notifier.dispatch(&notify::Event::Lifecycle(summary)).await;
+    }
+    scheduler.abort();
+    Ok(())
+}
+
+/// Block until the platform's shutdown signals fire: Ctrl-C or SIGTERM
+/// (what `stop` and service managers send) on Unix, Ctrl-C or Ctrl-Break
+/// on Windows.
+#[cfg(unix)]
+async fn wait_for_shutdown() -> Result<()> {
+    let mut term = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = term.recv() => {}
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+async fn wait_for_shutdown() -> Result<()> {
+    let mut ctrl_break = tokio::signal::windows::ctrl_break()?;
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = ctrl_break.recv() => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,283 @@
+//! Prometheus metrics registry.
+//!
+//! Exposed at `/metrics` on the embedded HTTP server (see `http.rs`). The
+//! per-thread counters make a single underperforming core visible instead of
+//! hiding it in the aggregate rate.
+
+use anyhow::Result;
+use prometheus::{
+    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::buildinfo;
+
+/// Classification for internal error counters.
+///
+/// Everything that used to be only a log warning also increments one of
+/// these, so error rates are visible on dashboards and in `/stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Random key generation failed.
+    Keygen,
+    /// Address derivation / key checking failed.
+    Derivation,
+    /// A Telegram API call failed.
+    Telegram,
+    /// Reading or writing auxiliary files (CSV, cursors) failed.
+    Io,
+    /// Persisting a solution or journal entry failed.
+    Persistence,
+}
+
+impl ErrorKind {
+    const ALL: [ErrorKind; 5] = [
+        ErrorKind::Keygen,
+        ErrorKind::Derivation,
+        ErrorKind::Telegram,
+        ErrorKind::Io,
+        ErrorKind::Persistence,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Keygen => "keygen",
+            ErrorKind::Derivation => "derivation",
+            ErrorKind::Telegram => "telegram",
+            ErrorKind::Io => "io",
+            ErrorKind::Persistence => "persistence",
+        }
+    }
+}
+
+/// All metrics, registered on one registry owned by `AppState`.
+pub struct Metrics {
+    registry: Registry,
+    /// Keys checked, labeled by worker thread id.
+    pub keys_checked: IntCounterVec,
+    /// Solving sessions completed.
+    pub sessions: IntCounter,
+    /// Matches found.
+    pub matches: IntCounter,
+    /// Wall time spent checking one batch of keys (seconds).
+    pub batch_check_seconds: Histogram,
+    /// Wall time spent generating the keys for one batch (seconds).
+    pub batch_keygen_seconds: Histogram,
+    /// Internal errors, labeled by [`ErrorKind`].
+    errors: IntCounterVec,
+    /// Telegram API round-trip time, labeled by method.
+    pub telegram_request_seconds: HistogramVec,
+    /// Telegram API calls, labeled by method and outcome (`ok`/`error`).
+    pub telegram_requests: IntCounterVec,
+    /// Telegram send attempts that had to be retried.
+    pub telegram_retries: IntCounter,
+    /// Messages dropped because they came from a non-whitelisted chat.
+    pub telegram_rejected: IntCounter,
+    /// Keys checked, labeled by puzzle number.
+    pub puzzle_keys_checked: IntCounterVec,
+    /// Advertised reward, labeled by puzzle number.
+    pub puzzle_reward_btc: GaugeVec,
+    /// Share of the keyspace checked so far, in percent.
+    pub puzzle_coverage_percent: GaugeVec,
+    /// Expected seconds to sweep the whole range at the current rate.
+    pub puzzle_eta_seconds: GaugeVec,
+    /// Lifetime keys checked per remote node, from stats pushes.
+    pub node_keys_checked: GaugeVec,
+    /// Current rate per remote node, from stats pushes.
+    pub node_rate: GaugeVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        let keys_checked = IntCounterVec::new(
+            Opts::new("btclotto_keys_checked_total", "Keys checked per worker thread"),
+            &["thread"],
+        )?;
+        let sessions = IntCounter::new("btclotto_sessions_total", "Solving sessions completed")?;
+        let matches = IntCounter::new("btclotto_matches_total", "Matches found")?;
+        // Batches are ~1000 keys; address derivation dominates, keygen is an
+        // order of magnitude cheaper, hence the two bucket ranges.
+        let batch_check_seconds = Histogram::with_opts(
+            HistogramOpts::new(
+                "btclotto_batch_check_seconds",
+                "Wall time checking one batch of keys",
+            )
+            .buckets(prometheus::exponential_buckets(0.001, 2.0, 14)?),
+        )?;
+        let batch_keygen_seconds = Histogram::with_opts(
+            HistogramOpts::new(
+                "btclotto_batch_keygen_seconds",
+                "Wall time generating the keys for one batch",
+            )
+            .buckets(prometheus::exponential_buckets(0.0001, 2.0, 14)?),
+        )?;
+        registry.register(Box::new(keys_checked.clone()))?;
+        registry.register(Box::new(sessions.clone()))?;
+        registry.register(Box::new(matches.clone()))?;
+        let errors = IntCounterVec::new(
+            Opts::new("btclotto_errors_total", "Internal errors by kind"),
+            &["kind"],
+        )?;
+        let telegram_request_seconds = HistogramVec::new(
+            HistogramOpts::new(
+                "btclotto_telegram_request_seconds",
+                "Telegram API round-trip time",
+            )
+            .buckets(prometheus::exponential_buckets(0.05, 2.0, 10)?),
+            &["method"],
+        )?;
+        let telegram_requests = IntCounterVec::new(
+            Opts::new("btclotto_telegram_requests_total", "Telegram API calls"),
+            &["method", "outcome"],
+        )?;
+        let telegram_retries = IntCounter::new(
+            "btclotto_telegram_retries_total",
+            "Telegram send attempts that were retried",
+        )?;
+        let telegram_rejected = IntCounter::new(
+            "btclotto_telegram_rejected_total",
+            "Messages dropped because they came from a non-whitelisted chat",
+        )?;
+        registry.register(Box::new(batch_check_seconds.clone()))?;
+        registry.register(Box::new(batch_keygen_seconds.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+        // Set once at startup; only the registry needs to hold these two.
+        let build_info = IntGaugeVec::new(
+            Opts::new("btclotto_build_info", "Build identification (always 1)"),
+            &["version", "git_hash", "features"],
+        )?;
+        build_info
+            .with_label_values(&[buildinfo::VERSION, buildinfo::GIT_HASH, &buildinfo::features()])
+            .set(1);
+        let start_time_seconds = IntGauge::new(
+            "btclotto_start_time_seconds",
+            "Process start time in Unix seconds",
+        )?;
+        start_time_seconds.set(chrono::Utc::now().timestamp());
+        let puzzle_keys_checked = IntCounterVec::new(
+            Opts::new(
+                "btclotto_puzzle_keys_checked_total",
+                "Keys checked per puzzle",
+            ),
+            &["puzzle"],
+        )?;
+        let puzzle_reward_btc = GaugeVec::new(
+            Opts::new("btclotto_puzzle_reward_btc", "Advertised puzzle reward"),
+            &["puzzle"],
+        )?;
+        let puzzle_coverage_percent = GaugeVec::new(
+            Opts::new(
+                "btclotto_puzzle_coverage_percent",
+                "Share of the puzzle keyspace checked",
+            ),
+            &["puzzle"],
+        )?;
+        let puzzle_eta_seconds = GaugeVec::new(
+            Opts::new(
+                "btclotto_puzzle_eta_seconds",
+                "Expected seconds to sweep the range at the current rate",
+            ),
+            &["puzzle"],
+        )?;
+        registry.register(Box::new(telegram_request_seconds.clone()))?;
+        registry.register(Box::new(telegram_requests.clone()))?;
+        registry.register(Box::new(telegram_retries.clone()))?;
+        registry.register(Box::new(telegram_rejected.clone()))?;
+        registry.register(Box::new(build_info))?;
+        registry.register(Box::new(start_time_seconds))?;
+        registry.register(Box::new(puzzle_keys_checked.clone()))?;
+        registry.register(Box::new(puzzle_reward_btc.clone()))?;
+        registry.register(Box::new(puzzle_coverage_percent.clone()))?;
+        registry.register(Box::new(puzzle_eta_seconds.clone()))?;
+        let node_keys_checked = GaugeVec::new(
+            Opts::new(
+                "btclotto_node_keys_checked",
+                "Lifetime keys checked per remote node",
+            ),
+            &["node"],
+        )?;
+        let node_rate = GaugeVec::new(
+            Opts::new(
+                "btclotto_node_rate_keys_per_sec",
+                "Current rate per remote node",
+            ),
+            &["node"],
+        )?;
+        registry.register(Box::new(node_keys_checked.clone()))?;
+        registry.register(Box::new(node_rate.clone()))?;
+        Ok(Self {
+            registry,
+            keys_checked,
+            sessions,
+            matches,
+            batch_check_seconds,
+            batch_keygen_seconds,
+            errors,
+            telegram_request_seconds,
+            telegram_requests,
+            telegram_retries,
+            telegram_rejected,
+            puzzle_keys_checked,
+            puzzle_reward_btc,
+            puzzle_coverage_percent,
+            puzzle_eta_seconds,
+            node_keys_checked,
+            node_rate,
+        })
+    }
+
+    /// Count one internal error of the given kind.
+    pub fn record_error(&self, kind: ErrorKind) {
+        self.errors.with_label_values(&[kind.as_str()]).inc();
+    }
+
+    /// Per-kind error counts, for the `/stats` report.
+    pub fn error_counts(&self) -> Vec<(&'static str, u64)> {
+        ErrorKind::ALL
+            .iter()
+            .map(|kind| {
+                (
+                    kind.as_str(),
+                    self.errors.with_label_values(&[kind.as_str()]).get(),
+                )
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Render the registry in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        if let Err(err) = encoder.encode(&self.registry.gather(), &mut buffer) {
+            tracing::warn!("failed to encode metrics: {err}");
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_thread_counters_render() {
+        let metrics = Metrics::new().unwrap();
+        metrics.keys_checked.with_label_values(&["0"]).inc_by(10);
+        metrics.keys_checked.with_label_values(&["1"]).inc_by(20);
+        let rendered = metrics.render();
+        assert!(rendered.contains("btclotto_keys_checked_total{thread=\"0\"} 10"));
+        assert!(rendered.contains("btclotto_keys_checked_total{thread=\"1\"} 20"));
+    }
+
+    #[test]
+    fn latency_histograms_render() {
+        let metrics = Metrics::new().unwrap();
+        metrics.batch_check_seconds.observe(0.05);
+        metrics.batch_keygen_seconds.observe(0.002);
+        let rendered = metrics.render();
+        assert!(rendered.contains("btclotto_batch_check_seconds_count 1"));
+        assert!(rendered.contains("btclotto_batch_keygen_seconds_count 1"));
+    }
+}
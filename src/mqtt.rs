@@ -0,0 +1,110 @@
+//! MQTT publisher for stats and events.
+//!
+//! Publishes to `btclotto/<instance>/…` topics so home-automation dashboards
+//! and other machines can react to solver state without polling:
+//!
+//! * `rate`, `keys_checked`, `matches` — plain numbers from each report
+//! * `solve` — JSON solve event (never includes the private key)
+//! * `alert` — watchdog and other alert text
+//!
+//! Enabled by `MQTT_HOST`; `MQTT_PORT` (default 1883), `MQTT_INSTANCE`
+//! (default hostname) and `MQTT_USERNAME`/`MQTT_PASSWORD` are optional.
+
+use anyhow::{Context, Result};
+use rumqttc::{AsyncClient, MqttOptions, QoS};
+use serde_json::json;
+
+use crate::notify::{Event, Notifier};
+
+/// A connected MQTT client and its topic prefix.
+pub struct MqttPublisher {
+    client: AsyncClient,
+    prefix: String,
+}
+
+impl MqttPublisher {
+    /// Build the publisher from the environment; `None` when `MQTT_HOST` is
+    /// unset. Spawns the background task that drives the connection.
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("MQTT_HOST").ok()?;
+        let port = std::env::var("MQTT_PORT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1883);
+        let instance = std::env::var("MQTT_INSTANCE").unwrap_or_else(|_| {
+            hostname::get()
+                .ok()
+                .and_then(|h| h.into_string().ok())
+                .unwrap_or_else(|| "solver".to_string())
+        });
+        let mut options = MqttOptions::new(format!("btclotto-{instance}"), host, port);
+        options.set_keep_alive(std::time::Duration::from_secs(30));
+        if let (Ok(user), Ok(pass)) = (std::env::var("MQTT_USERNAME"), std::env::var("MQTT_PASSWORD"))
+        {
+            options.set_credentials(user, pass);
+        }
+        let (client, mut eventloop) = AsyncClient::new(options, 16);
+        // The event loop must be polled for publishes to go out; it also
+        // handles reconnects.
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = eventloop.poll().await {
+                    tracing::debug!("MQTT connection error (will retry): {err}");
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                }
+            }
+        });
+        Some(Self {
+            client,
+            prefix: format!("btclotto/{instance}"),
+        })
+    }
+
+    async fn publish(&self, topic: &str, payload: String) -> Result<()> {
+        self.client
+            .publish(
+                format!("{}/{topic}", self.prefix),
+                QoS::AtLeastOnce,
+                false,
+                payload,
+            )
+            .await
+            .with_context(|| format!("publishing to {}/{topic}", self.prefix))
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for MqttPublisher {
+    fn name(&self) -> &'static str {
+        "mqtt"
+    }
+
+    async fn send(&self, event: &Event) -> Result<()> {
+        match event {
+            Event::Report {
+                keys_checked,
+                rate,
+                matches_found,
+                ..
+            } => {
+                self.publish("rate", rate.to_string()).await?;
+                self.publish("keys_checked", keys_checked.to_string()).await?;
+                self.publish("matches", matches_found.to_string()).await
+            }
+            Event::Solve(result) => {
+                self.publish(
+                    "solve",
+                    json!({
+                        "puzzle_number": result.puzzle_number,
+                        "address": result.address,
+                        "address_type": result.address_type,
+                    })
+                    .to_string(),
+                )
+                .await
+            }
+            Event::Alert(text) => self.publish("alert", text.clone()).await,
+            Event::Lifecycle(text) => self.publish("status", text.clone()).await,
+        }
+    }
+}
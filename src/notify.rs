@@ -0,0 +1,170 @@
+//! Notification fanout.
+//!
+//! Every sink (Telegram, email, webhook, …) implements [`Notifier`]; the
+//! [`Fanout`] dispatches each event to all configured sinks concurrently, so
+//! a slow webhook endpoint cannot delay the Telegram solve message. Retries
+//! are per sink: each backend declares its own attempt count and the fanout
+//! applies linear backoff between attempts.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::checker::CheckResult;
+use crate::metrics::{ErrorKind, Metrics};
+
+/// An event worth telling the outside world about.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A puzzle was solved.
+    Solve(CheckResult),
+    /// Something needs operator attention (watchdog, degraded backends).
+    Alert(String),
+    /// The periodic stats report.
+    Report {
+        text: String,
+        keys_checked: u64,
+        rate: u64,
+        matches_found: u64,
+        sessions_run: u64,
+        uptime_secs: u64,
+    },
+    /// Startup/shutdown notices.
+    Lifecycle(String),
+}
+
+impl Event {
+    /// JSON form for machine consumers (event bus, gRPC stream). Solve
+    /// events never include the private key here.
+    pub fn to_json(&self) -> String {
+        let value = match self {
+            Event::Solve(result) => serde_json::json!({
+                "event": "solve",
+                "puzzle_number": result.puzzle_number,
+                "address": result.address,
+                "address_type": result.address_type,
+            }),
+            Event::Alert(text) => serde_json::json!({ "event": "alert", "message": text }),
+            Event::Report {
+                keys_checked,
+                rate,
+                matches_found,
+                sessions_run,
+                uptime_secs,
+                ..
+            } => serde_json::json!({
+                "event": "report",
+                "keys_checked": keys_checked,
+                "rate_keys_per_sec": rate,
+                "matches_found": matches_found,
+                "sessions_run": sessions_run,
+                "uptime_secs": uptime_secs,
+            }),
+            Event::Lifecycle(text) => {
+                serde_json::json!({ "event": "lifecycle", "message": text })
+            }
+        };
+        value.to_string()
+    }
+}
+
+/// Forwards every event as JSON onto the in-process broadcast channel, where
+/// streaming consumers (the gRPC control service) pick it up.
+pub struct EventBus(pub tokio::sync::broadcast::Sender<String>);
+
+#[async_trait::async_trait]
+impl Notifier for EventBus {
+    fn name(&self) -> &'static str {
+        "bus"
+    }
+
+    async fn send(&self, event: &Event) -> Result<()> {
+        // A send error just means nobody is listening right now.
+        let _ = self.0.send(event.to_json());
+        Ok(())
+    }
+}
+
+/// One notification backend.
+///
+/// Sinks are free to ignore events that don't suit the medium (email skips
+/// routine reports, for example) by returning `Ok(())`.
+#[async_trait::async_trait]
+pub trait Notifier: Send + Sync {
+    /// Short name used in logs and per-sink bookkeeping.
+    fn name(&self) -> &'static str;
+
+    /// Delivery attempts before the fanout gives up on this sink. Backends
+    /// with internal retries should leave this at 1.
+    fn retry_attempts(&self) -> u32 {
+        1
+    }
+
+    async fn send(&self, event: &Event) -> Result<()>;
+}
+
+/// Dispatches each event to every configured sink concurrently.
+pub struct Fanout {
+    sinks: Vec<Arc<dyn Notifier>>,
+    metrics: Arc<Metrics>,
+}
+
+impl Fanout {
+    pub fn new(sinks: Vec<Arc<dyn Notifier>>, metrics: Arc<Metrics>) -> Self {
+        Self { sinks, metrics }
+    }
+
+    /// Whether any sink is configured at all.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Send `event` to all sinks and wait for every one to finish, returning
+    /// the per-sink outcome (callers that tie follow-up work to a specific
+    /// backend — the match journal acks on Telegram delivery — pick theirs
+    /// out by name).
+    pub async fn dispatch(&self, event: &Event) -> Vec<(&'static str, Result<()>)> {
+        let mut handles = Vec::new();
+        for sink in &self.sinks {
+            let sink = Arc::clone(sink);
+            let event = event.clone();
+            handles.push(tokio::spawn(async move {
+                let mut outcome = Ok(());
+                for attempt in 1..=sink.retry_attempts().max(1) {
+                    outcome = sink.send(&event).await;
+                    if outcome.is_ok() {
+                        break;
+                    }
+                    if attempt < sink.retry_attempts() {
+                        tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+                    }
+                }
+                (sink.name(), outcome)
+            }));
+        }
+        let mut results = Vec::new();
+        for handle in handles {
+            match handle.await {
+                Ok((name, outcome)) => {
+                    if let Err(err) = &outcome {
+                        if name == "telegram" {
+                            self.metrics.record_error(ErrorKind::Telegram);
+                        }
+                        tracing::warn!("{name} notification failed: {err:#}");
+                    }
+                    results.push((name, outcome));
+                }
+                Err(err) => tracing::error!("notification task panicked: {err}"),
+            }
+        }
+        results
+    }
+
+    /// Dispatch and report only whether the named sink delivered.
+    pub async fn delivered_via(&self, event: &Event, sink_name: &str) -> bool {
+        self.dispatch(event)
+            .await
+            .iter()
+            .any(|(name, outcome)| *name == sink_name && outcome.is_ok())
+    }
+}
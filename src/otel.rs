@@ -0,0 +1,48 @@
+//! OpenTelemetry OTLP trace export (behind the `otel` cargo feature).
+//!
+//! Session and worker spans already exist in the scheduler; this module ships
+//! them to an OTLP collector so the solver shows up next to other services in
+//! an existing OpenTelemetry stack. Enabled at runtime by setting the
+//! standard `OTEL_EXPORTER_OTLP_ENDPOINT` variable (gRPC, e.g.
+//! `http://localhost:4317`).
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Build the OTLP tracing layer, or `None` when no endpoint is configured or
+/// the exporter fails to initialize.
+///
+/// Errors go to stderr directly: this runs before the subscriber is
+/// installed, so `tracing` output would be dropped.
+pub fn layer<S>() -> Option<OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok()?;
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(&endpoint)
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(err) => {
+            eprintln!("failed to build OTLP exporter for {endpoint}: {err}");
+            return None;
+        }
+    };
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(env!("CARGO_PKG_NAME"))
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+    opentelemetry::global::set_tracer_provider(provider);
+    Some(tracing_opentelemetry::layer().with_tracer(tracer))
+}
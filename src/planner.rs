@@ -0,0 +1,147 @@
+//! Static range-partitioning planner.
+//!
+//! Given machines and their benchmarked key rates
+//! (`PLAN_MACHINES=rig1:250000000,laptop:8000000` plus `PLAN_PUZZLE`), the
+//! puzzle's range is split into one contiguous slice per machine, sized
+//! proportionally to its rate so everyone finishes at roughly the same
+//! time. One ready-to-use `.env` snippet and one work file per machine
+//! land under `DATA_DIR/plan/`; the process exits after writing them, so
+//! no manual range math is needed. The generated config runs each machine
+//! as its own single-node coordinator that imports its slice.
+
+use std::path::PathBuf;
+
+use anyhow::{ensure, Context, Result};
+use num_bigint::BigUint;
+use num_traits::One;
+
+use crate::config::Config;
+use crate::puzzles::PuzzleCollection;
+
+/// One machine in the plan: a name and its benchmarked keys/second.
+pub struct Machine {
+    pub name: String,
+    pub rate: u64,
+}
+
+/// Parse the `name:rate,name:rate` machine spec.
+pub fn parse_machines(spec: &str) -> Result<Vec<Machine>> {
+    let mut machines = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, rate) = entry
+            .split_once(':')
+            .with_context(|| format!("bad machine entry {entry:?}; expected name:rate"))?;
+        let rate: u64 = rate
+            .parse()
+            .with_context(|| format!("bad rate for machine {name:?}"))?;
+        ensure!(rate > 0, "machine {name:?} has a zero rate");
+        machines.push(Machine {
+            name: name.to_string(),
+            rate,
+        });
+    }
+    ensure!(!machines.is_empty(), "no machines in PLAN_MACHINES");
+    Ok(machines)
+}
+
+/// Split `[start, end]` into contiguous slices proportional to each
+/// machine's rate; the last machine absorbs the rounding remainder.
+pub fn partition(
+    start: &BigUint,
+    end: &BigUint,
+    machines: &[Machine],
+) -> Vec<(String, BigUint, BigUint)> {
+    let size = end - start + 1u32;
+    let total: u64 = machines.iter().map(|m| m.rate).sum();
+    let mut cursor = start.clone();
+    let mut slices = Vec::new();
+    for (i, machine) in machines.iter().enumerate() {
+        if &cursor > end {
+            break;
+        }
+        let remaining = end - &cursor + 1u32;
+        let share = if i + 1 == machines.len() {
+            remaining
+        } else {
+            (&size * machine.rate / total).max(BigUint::one()).min(remaining)
+        };
+        let slice_end = &cursor + &share - 1u32;
+        slices.push((machine.name.clone(), cursor.clone(), slice_end.clone()));
+        cursor = slice_end + 1u32;
+    }
+    slices
+}
+
+/// Run the planner and write per-machine configs under `DATA_DIR/plan/`.
+pub fn plan_to_dir(
+    config: &Config,
+    puzzles: &PuzzleCollection,
+    number: u32,
+    spec: &str,
+) -> Result<String> {
+    let machines = parse_machines(spec)?;
+    let puzzle = puzzles
+        .get(number)
+        .with_context(|| format!("unknown puzzle #{number}"))?;
+    let (start, end) = puzzle.range()?;
+    let dir = config.data_dir.join("plan");
+    std::fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+    let slices = partition(&start, &end, &machines);
+    for (name, slice_start, slice_end) in &slices {
+        let work_path: PathBuf = dir.join(format!("{name}.work.txt"));
+        crate::workfile::write_ranges(
+            &work_path,
+            &[(number, slice_start.clone(), slice_end.clone())],
+        )?;
+        let env_path = dir.join(format!("{name}.env"));
+        std::fs::write(
+            &env_path,
+            format!(
+                "# Partition plan for {name}: puzzle #{number}, {slice_start:x}..{slice_end:x}.\n\
+                 # The machine coordinates itself and works through its imported slice.\n\
+                 NODE_NAME={name}\n\
+                 CLUSTER_COORDINATOR=true\n\
+                 HTTP_LISTEN=127.0.0.1:8080\n\
+                 COORDINATOR_URL=http://127.0.0.1:8080\n\
+                 WORK_IMPORT_FILE={}\n\
+                 WORK_IMPORT_PUZZLE={number}\n",
+                work_path.display(),
+            ),
+        )
+        .with_context(|| format!("writing {}", env_path.display()))?;
+    }
+    Ok(format!(
+        "planned puzzle #{number} across {} machine(s); configs under {}",
+        slices.len(),
+        dir.display(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_machine_specs() {
+        let machines = parse_machines(" rig1:100, rig2:300 ").unwrap();
+        assert_eq!(machines.len(), 2);
+        assert_eq!(machines[0].name, "rig1");
+        assert_eq!(machines[1].rate, 300);
+        assert!(parse_machines("rig1").is_err());
+        assert!(parse_machines("rig1:0").is_err());
+    }
+
+    #[test]
+    fn partitions_proportionally_and_covers_the_whole_range() {
+        let machines = parse_machines("slow:100,fast:300").unwrap();
+        let start = BigUint::from(0u32);
+        let end = BigUint::from(399u32);
+        let slices = partition(&start, &end, &machines);
+        assert_eq!(slices.len(), 2);
+        // The slow machine gets a quarter of the keys, the fast one the rest.
+        assert_eq!(slices[0].1, BigUint::from(0u32));
+        assert_eq!(slices[0].2, BigUint::from(99u32));
+        assert_eq!(slices[1].1, BigUint::from(100u32));
+        assert_eq!(slices[1].2, BigUint::from(399u32));
+    }
+}
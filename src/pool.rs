@@ -0,0 +1,170 @@
+//! Client for community BitCrack/keyhunt-style work servers.
+//!
+//! Those pools speak a minimal HTTP protocol: `GET /getwork/<worker>`
+//! returns the next assigned range as a `start:end` hex pair (or an empty
+//! body when the pool has nothing), and the worker posts the same pair back
+//! to `/submitwork/<worker>` with the keys-checked count appended once the
+//! range is done. `POOL_URL` enables the mode; `POOL_PUZZLE` names the
+//! puzzle the pool is sweeping so the bot knows which address to check
+//! against.
+//!
+//! Found keys are NOT sent to the pool — they go through the normal local
+//! journal/solutions/Telegram pipeline. Until sequential sweeping lands the
+//! bot covers each assigned range with random sessions and reports the
+//! actual keys-checked count, which honest pools accept as partial credit.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::config::Config;
+use crate::notify::Fanout;
+use crate::scheduler;
+use crate::state::AppState;
+
+/// A configured pool connection.
+pub struct PoolClient {
+    client: reqwest::Client,
+    base: String,
+    worker: String,
+    puzzle_number: u32,
+}
+
+/// Parse a `getwork` body: a `start:end` hex pair, or nothing to do.
+fn parse_work(body: &str) -> Result<Option<(BigUint, BigUint)>> {
+    let body = body.trim();
+    if body.is_empty() || body.eq_ignore_ascii_case("wait") {
+        return Ok(None);
+    }
+    let (start, end) = body
+        .split_once(':')
+        .with_context(|| format!("expected start:end hex pair, got {body:?}"))?;
+    let parse = |bound: &str, name: &str| {
+        BigUint::from_str_radix(bound.trim().trim_start_matches("0x"), 16)
+            .with_context(|| format!("bad {name} in pool work {body:?}"))
+    };
+    Ok(Some((parse(start, "start")?, parse(end, "end")?)))
+}
+
+impl PoolClient {
+    /// Build the client when `POOL_URL` is set; `POOL_PUZZLE` is required
+    /// with it.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let url = config.pool_url.as_ref()?;
+        let Some(puzzle_number) = config.pool_puzzle else {
+            tracing::error!("POOL_URL is set but POOL_PUZZLE is not; pool mode disabled");
+            return None;
+        };
+        Some(Self {
+            client: reqwest::Client::new(),
+            base: url.trim_end_matches('/').to_string(),
+            worker: config.node_name.clone(),
+            puzzle_number,
+        })
+    }
+
+    /// Fetch the next assigned range, if the pool has one.
+    async fn get_work(&self) -> Result<Option<(BigUint, BigUint)>> {
+        let body = self
+            .client
+            .get(format!("{}/getwork/{}", self.base, self.worker))
+            .send()
+            .await
+            .context("getwork request failed")?
+            .error_for_status()
+            .context("pool rejected the getwork request")?
+            .text()
+            .await
+            .context("reading getwork body")?;
+        parse_work(&body)
+    }
+
+    /// Report a finished range back to the pool.
+    async fn submit(&self, start: &BigUint, end: &BigUint, keys_checked: u64) -> Result<()> {
+        self.client
+            .post(format!("{}/submitwork/{}", self.base, self.worker))
+            .body(format!("{start:x}:{end:x}:{keys_checked}"))
+            .send()
+            .await
+            .context("submitwork request failed")?
+            .error_for_status()
+            .context("pool rejected the completion report")?;
+        Ok(())
+    }
+}
+
+/// Pool worker loop; replaces the standalone scheduler loop.
+pub async fn run_worker(state: Arc<AppState>, notifier: Arc<Fanout>, pool: PoolClient) {
+    let interval = Duration::from_secs(state.config.scheduler.session_interval_secs);
+    tracing::info!(
+        "pool mode: fetching ranges for puzzle #{} from {} as {:?}",
+        pool.puzzle_number,
+        pool.base,
+        pool.worker
+    );
+    loop {
+        state.heartbeat();
+        if state.shutdown_requested() {
+            return;
+        }
+        if !state.is_running() {
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+        let puzzle = state.puzzles().get(pool.puzzle_number).cloned();
+        let Some(puzzle) = puzzle else {
+            tracing::error!(
+                "POOL_PUZZLE #{} is not in the puzzle file; idling",
+                pool.puzzle_number
+            );
+            tokio::time::sleep(interval).await;
+            continue;
+        };
+        let (start, end) = match pool.get_work().await {
+            Ok(Some(range)) => range,
+            Ok(None) => {
+                tracing::info!("pool has no work; sleeping");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("pool unreachable: {err:#}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        tracing::info!("searching pool range {start:x}..{end:x}");
+        state.set_active_puzzle(Some(puzzle.number));
+        let before = state.stats.total_checked();
+        let matches = scheduler::run_session_in(&state, &puzzle, &start, &end).await;
+        state.mark_session();
+        let checked = state.stats.total_checked() - before;
+        for result in &matches {
+            scheduler::handle_match(&state, &notifier, result).await;
+        }
+        if let Err(err) = pool.submit(&start, &end, checked).await {
+            tracing::warn!("failed to report completed range to pool: {err:#}");
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_work_assignments() {
+        let (start, end) = parse_work("20000000000000000:3ffffffffffffffff\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(start, BigUint::from_str_radix("20000000000000000", 16).unwrap());
+        assert_eq!(end, BigUint::from_str_radix("3ffffffffffffffff", 16).unwrap());
+        assert!(parse_work("").unwrap().is_none());
+        assert!(parse_work("WAIT").unwrap().is_none());
+        assert!(parse_work("not-a-range").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! BTC spot price lookups.
+//!
+//! Tries a configurable list of public price APIs in order (CoinGecko,
+//! Kraken, Coinbase — none need an API key) and caches the result so the
+//! `/price` command and periodic reports don't hammer anyone. The fiat
+//! currency is selectable via `PRICE_FIAT`; `PRICE_PROVIDERS=none`
+//! disables lookups entirely.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Provider {
+    CoinGecko,
+    Kraken,
+    Coinbase,
+}
+
+impl Provider {
+    fn name(self) -> &'static str {
+        match self {
+            Provider::CoinGecko => "coingecko",
+            Provider::Kraken => "kraken",
+            Provider::Coinbase => "coinbase",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "coingecko" => Some(Provider::CoinGecko),
+            "kraken" => Some(Provider::Kraken),
+            "coinbase" => Some(Provider::Coinbase),
+            _ => None,
+        }
+    }
+}
+
+/// Pull the price out of a CoinGecko `/simple/price` response.
+fn parse_coingecko(value: &Value, fiat: &str) -> Result<f64> {
+    value
+        .pointer(&format!("/bitcoin/{fiat}"))
+        .and_then(Value::as_f64)
+        .with_context(|| format!("CoinGecko response has no bitcoin.{fiat}"))
+}
+
+/// Pull the last-trade price out of a Kraken `/public/Ticker` response.
+/// The pair key varies (`XXBTZUSD` vs `XBTUSD`), so take the only entry.
+fn parse_kraken(value: &Value) -> Result<f64> {
+    value
+        .get("result")
+        .and_then(Value::as_object)
+        .and_then(|pairs| pairs.values().next())
+        .and_then(|pair| pair.pointer("/c/0"))
+        .and_then(Value::as_str)
+        .and_then(|price| price.parse().ok())
+        .context("Kraken response has no last-trade price")
+}
+
+/// Pull the amount out of a Coinbase `/prices/<pair>/spot` response.
+fn parse_coinbase(value: &Value) -> Result<f64> {
+    value
+        .pointer("/data/amount")
+        .and_then(Value::as_str)
+        .and_then(|amount| amount.parse().ok())
+        .context("Coinbase response has no data.amount")
+}
+
+/// Cached, multi-provider BTC price client.
+pub struct PriceClient {
+    client: reqwest::Client,
+    providers: Vec<Provider>,
+    /// Lowercase fiat code (`usd`, `eur`, …).
+    fiat: String,
+    ttl: Duration,
+    cache: Mutex<Option<(Instant, f64)>>,
+}
+
+impl PriceClient {
+    /// Build the client; `None` when lookups are disabled.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let spec = config
+            .price_providers
+            .as_deref()
+            .unwrap_or("coingecko,kraken,coinbase");
+        if spec == "none" {
+            return None;
+        }
+        let providers: Vec<Provider> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| {
+                let provider = Provider::from_name(name);
+                if provider.is_none() {
+                    tracing::warn!("unknown price provider {name:?}; skipping");
+                }
+                provider
+            })
+            .collect();
+        if providers.is_empty() {
+            tracing::warn!("PRICE_PROVIDERS names no usable provider; price lookups disabled");
+            return None;
+        }
+        Some(Self {
+            client: reqwest::Client::new(),
+            providers,
+            fiat: config.price_fiat.to_lowercase(),
+            ttl: Duration::from_secs(config.price_cache_secs),
+            cache: Mutex::new(None),
+        })
+    }
+
+    /// Uppercase fiat code for display.
+    pub fn fiat(&self) -> String {
+        self.fiat.to_uppercase()
+    }
+
+    fn cached(&self) -> Option<f64> {
+        self.cache
+            .lock()
+            .unwrap()
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, price)| price)
+    }
+
+    async fn fetch(&self, provider: Provider) -> Result<f64> {
+        let fiat_upper = self.fiat.to_uppercase();
+        let url = match provider {
+            Provider::CoinGecko => format!(
+                "https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies={}",
+                self.fiat
+            ),
+            Provider::Kraken => {
+                format!("https://api.kraken.com/0/public/Ticker?pair=XBT{fiat_upper}")
+            }
+            Provider::Coinbase => {
+                format!("https://api.coinbase.com/v2/prices/BTC-{fiat_upper}/spot")
+            }
+        };
+        let value: Value = self
+            .client
+            .get(url)
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match provider {
+            Provider::CoinGecko => parse_coingecko(&value, &self.fiat),
+            Provider::Kraken => parse_kraken(&value),
+            Provider::Coinbase => parse_coinbase(&value),
+        }
+    }
+
+    /// Current BTC price in the configured fiat, from cache when fresh,
+    /// otherwise from the first provider that answers.
+    pub async fn btc_price(&self) -> Result<f64> {
+        if let Some(price) = self.cached() {
+            return Ok(price);
+        }
+        let mut last_err = anyhow!("no price provider answered");
+        for provider in &self.providers {
+            match self.fetch(*provider).await {
+                Ok(price) => {
+                    *self.cache.lock().unwrap() = Some((Instant::now(), price));
+                    return Ok(price);
+                }
+                Err(err) => {
+                    tracing::debug!("{} price lookup failed: {err:#}", provider.name());
+                    last_err = err.context(format!("{} failed", provider.name()));
+                }
+            }
+        }
+        Err(last_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_provider_format() {
+        let coingecko = serde_json::json!({ "bitcoin": { "usd": 64250.0 } });
+        assert_eq!(parse_coingecko(&coingecko, "usd").unwrap(), 64250.0);
+
+        let kraken = serde_json::json!({
+            "error": [],
+            "result": { "XXBTZUSD": { "c": ["64251.10", "0.01"] } },
+        });
+        assert_eq!(parse_kraken(&kraken).unwrap(), 64251.10);
+
+        let coinbase = serde_json::json!({ "data": { "amount": "64252.2", "currency": "USD" } });
+        assert_eq!(parse_coinbase(&coinbase).unwrap(), 64252.2);
+    }
+
+    #[test]
+    fn missing_fields_are_errors() {
+        assert!(parse_coingecko(&serde_json::json!({}), "usd").is_err());
+        assert!(parse_kraken(&serde_json::json!({ "result": {} })).is_err());
+        assert!(parse_coinbase(&serde_json::json!({ "data": {} })).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! Per-puzzle progress cursors, interoperable with keyhunt and BitCrack.
+//!
+//! A cursor records how far a sequential scan of a puzzle range has
+//! progressed. We read and write three on-disk formats so ranges can be
+//! handed back and forth with the community tools:
+//!
+//! * **Native**: a JSON object with explicit hex fields.
+//! * **BitCrack**: the `--continue` checkpoint format, `key=value` lines
+//!   where `next` is the next unchecked key (BitCrack's cursor semantics:
+//!   everything in `[start, next)` is done).
+//! * **keyhunt**: a single hex line holding the next unchecked key; range
+//!   bounds are taken from the puzzle definition since the file doesn't
+//!   carry them.
+//!
+//! Files live in `PROGRESS_DIR`, named `puzzle_<n>.<ext>`, and are loaded at
+//! startup and written back (in their original format) on shutdown.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+
+use crate::puzzles::PuzzleCollection;
+
+/// On-disk format of a progress file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressFormat {
+    Native,
+    BitCrack,
+    Keyhunt,
+}
+
+/// Scan position inside one puzzle range. `position` is the next unchecked
+/// key (matching BitCrack's `next` semantics); `position > range_end` means
+/// the range is exhausted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgressCursor {
+    pub puzzle_number: u32,
+    pub range_start: BigUint,
+    pub range_end: BigUint,
+    pub position: BigUint,
+    /// Format the cursor was loaded from, so saves round-trip.
+    pub format: ProgressFormat,
+}
+
+fn parse_hex(s: &str) -> Result<BigUint> {
+    BigUint::from_str_radix(s.trim().trim_start_matches("0x"), 16)
+        .with_context(|| format!("bad hex value '{}'", s.trim()))
+}
+
+impl ProgressCursor {
+    /// Parse a progress file, auto-detecting its format.
+    pub fn parse(puzzle_number: u32, contents: &str, puzzles: &PuzzleCollection) -> Result<Self> {
+        let trimmed = contents.trim();
+        if trimmed.starts_with('{') {
+            Self::parse_native(puzzle_number, trimmed)
+        } else if trimmed.contains("next=") {
+            Self::parse_bitcrack(puzzle_number, trimmed)
+        } else {
+            Self::parse_keyhunt(puzzle_number, trimmed, puzzles)
+        }
+    }
+
+    fn parse_native(puzzle_number: u32, contents: &str) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct Native {
+            range_start: String,
+            range_end: String,
+            position: String,
+        }
+        let native: Native =
+            serde_json::from_str(contents).context("parsing native progress JSON")?;
+        Ok(Self {
+            puzzle_number,
+            range_start: parse_hex(&native.range_start)?,
+            range_end: parse_hex(&native.range_end)?,
+            position: parse_hex(&native.position)?,
+            format: ProgressFormat::Native,
+        })
+    }
+
+    fn parse_bitcrack(puzzle_number: u32, contents: &str) -> Result<Self> {
+        let mut fields: HashMap<&str, &str> = HashMap::new();
+        for line in contents.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                fields.insert(key.trim(), value.trim());
+            }
+        }
+        let get = |key: &str| -> Result<BigUint> {
+            let value = fields
+                .get(key)
+                .with_context(|| format!("BitCrack checkpoint missing '{key}'"))?;
+            parse_hex(value)
+        };
+        Ok(Self {
+            puzzle_number,
+            range_start: get("start")?,
+            range_end: get("end")?,
+            position: get("next")?,
+            format: ProgressFormat::BitCrack,
+        })
+    }
+
+    fn parse_keyhunt(
+        puzzle_number: u32,
+        contents: &str,
+        puzzles: &PuzzleCollection,
+    ) -> Result<Self> {
+        let puzzle = puzzles
+            .get(puzzle_number)
+            .with_context(|| format!("keyhunt progress for unknown puzzle #{puzzle_number}"))?;
+        let (range_start, range_end) = puzzle.range()?;
+        let position = parse_hex(
+            contents
+                .lines()
+                .next()
+                .context("empty keyhunt progress file")?,
+        )?;
+        Ok(Self {
+            puzzle_number,
+            range_start,
+            range_end,
+            position,
+            format: ProgressFormat::Keyhunt,
+        })
+    }
+
+    /// Serialize in the given format.
+    pub fn serialize(&self, format: ProgressFormat) -> String {
+        match format {
+            ProgressFormat::Native => serde_json::json!({
+                "range_start": format!("{:x}", self.range_start),
+                "range_end": format!("{:x}", self.range_end),
+                "position": format!("{:x}", self.position),
+            })
+            .to_string(),
+            ProgressFormat::BitCrack => format!(
+                "start={:064x}\nnext={:064x}\nend={:064x}\ncompression=compressed\n",
+                self.range_start, self.position, self.range_end
+            ),
+            ProgressFormat::Keyhunt => format!("{:x}\n", self.position),
+        }
+    }
+
+    /// Write the cursor back to `path` in its original format.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        crate::fsutil::atomic_write(path, self.serialize(self.format).as_bytes())
+            .with_context(|| format!("writing progress file {}", path.display()))
+    }
+}
+
+/// Stride-mode worker checkpoint: every worker thread's next unchecked
+/// lattice key, persisted on a timer so a crash or redeploy resumes the
+/// sweep where it stopped instead of restarting the range.
+///
+/// Unlike [`ProgressCursor`] this is bot-internal (no community-tool
+/// formats): the positions only mean anything under the same `STRIDE`,
+/// `STRIDE_OFFSET` and thread layout, which the file records so a resume
+/// against a different lattice can be rejected.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    pub puzzle_number: u32,
+    /// The exact range the session swept (hex): work-unit modes hand out
+    /// sub-ranges, and positions from one sub-range must not seed another.
+    pub range_start: String,
+    pub range_end: String,
+    pub stride: u64,
+    pub stride_offset: u64,
+    /// Next unchecked key per worker thread, hex; empty for a thread that
+    /// never drew a key.
+    pub positions: Vec<String>,
+}
+
+impl Checkpoint {
+    /// Checkpoint file for one puzzle under `PROGRESS_DIR`.
+    pub fn path(dir: &Path, puzzle_number: u32) -> PathBuf {
+        dir.join(format!("stride_checkpoint_{puzzle_number}.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("reading checkpoint {}", path.display()))?;
+        serde_json::from_str(&data).context("parsing checkpoint JSON")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self).expect("checkpoint serializes");
+        crate::fsutil::atomic_write(path, json.as_bytes())
+            .with_context(|| format!("writing checkpoint {}", path.display()))
+    }
+
+    /// The saved position of one worker thread, if it recorded any.
+    pub fn position(&self, thread_id: usize) -> Option<BigUint> {
+        self.positions
+            .get(thread_id)
+            .filter(|p| !p.is_empty())
+            .and_then(|p| parse_hex(p).ok())
+    }
+}
+
+fn file_extension(format: ProgressFormat) -> &'static str {
+    match format {
+        ProgressFormat::Native => "json",
+        ProgressFormat::BitCrack => "dat",
+        ProgressFormat::Keyhunt => "txt",
+    }
+}
+
+/// Load every `puzzle_<n>.*` progress file under `dir`.
+pub fn load_dir(dir: &Path, puzzles: &PuzzleCollection) -> Result<HashMap<u32, ProgressCursor>> {
+    let mut cursors = HashMap::new();
+    if !dir.is_dir() {
+        bail!("PROGRESS_DIR {} is not a directory", dir.display());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(number) = stem
+            .strip_prefix("puzzle_")
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading progress file {}", path.display()))?;
+        match ProgressCursor::parse(number, &contents, puzzles) {
+            Ok(cursor) => {
+                tracing::info!(
+                    "loaded {:?} progress for puzzle #{number} from {}",
+                    cursor.format,
+                    path.display()
+                );
+                cursors.insert(number, cursor);
+            }
+            Err(err) => tracing::warn!("skipping progress file {}: {err:#}", path.display()),
+        }
+    }
+    Ok(cursors)
+}
+
+/// Write all cursors back to `dir`, each in its original format.
+pub fn save_dir(dir: &Path, cursors: &HashMap<u32, ProgressCursor>) -> Result<()> {
+    for cursor in cursors.values() {
+        let path: PathBuf = dir.join(format!(
+            "puzzle_{}.{}",
+            cursor.puzzle_number,
+            file_extension(cursor.format)
+        ));
+        cursor.save(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::PuzzleCollection;
+
+    fn collection() -> PuzzleCollection {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("puzzles.json");
+        std::fs::write(
+            &path,
+            r#"[{"number":8,"address":"1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH","range_start":"80","range_end":"ff","reward_btc":0.8,"solved":false}]"#,
+        )
+        .unwrap();
+        PuzzleCollection::load(&path).unwrap()
+    }
+
+    #[test]
+    fn bitcrack_checkpoint_round_trips() {
+        let contents = "start=0000000000000000000000000000000000000000000000000000000000000080\nnext=00000000000000000000000000000000000000000000000000000000000000a5\nend=00000000000000000000000000000000000000000000000000000000000000ff\nblocks=32\nthreads=256\n";
+        let cursor = ProgressCursor::parse(8, contents, &collection()).unwrap();
+        assert_eq!(cursor.format, ProgressFormat::BitCrack);
+        assert_eq!(cursor.position, BigUint::from(0xa5u32));
+        let reserialized = cursor.serialize(ProgressFormat::BitCrack);
+        let reparsed = ProgressCursor::parse(8, &reserialized, &collection()).unwrap();
+        assert_eq!(reparsed.position, cursor.position);
+        assert_eq!(reparsed.range_end, cursor.range_end);
+    }
+
+    #[test]
+    fn keyhunt_cursor_takes_range_from_puzzle() {
+        let cursor = ProgressCursor::parse(8, "9c\n", &collection()).unwrap();
+        assert_eq!(cursor.format, ProgressFormat::Keyhunt);
+        assert_eq!(cursor.position, BigUint::from(0x9cu32));
+        assert_eq!(cursor.range_start, BigUint::from(0x80u32));
+        assert_eq!(cursor.range_end, BigUint::from(0xffu32));
+    }
+
+    #[test]
+    fn native_json_round_trips() {
+        let cursor = ProgressCursor {
+            puzzle_number: 8,
+            range_start: BigUint::from(0x80u32),
+            range_end: BigUint::from(0xffu32),
+            position: BigUint::from(0x90u32),
+            format: ProgressFormat::Native,
+        };
+        let json = cursor.serialize(ProgressFormat::Native);
+        let reparsed = ProgressCursor::parse(8, &json, &collection()).unwrap();
+        assert_eq!(reparsed, cursor);
+    }
+
+    #[test]
+    fn checkpoint_round_trips_per_thread_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        let checkpoint = Checkpoint {
+            puzzle_number: 8,
+            range_start: "80".into(),
+            range_end: "ff".into(),
+            stride: 4,
+            stride_offset: 1,
+            positions: vec!["a5".into(), String::new(), "b9".into()],
+        };
+        let path = Checkpoint::path(dir.path(), 8);
+        checkpoint.save(&path).unwrap();
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert_eq!(loaded, checkpoint);
+        assert_eq!(loaded.position(0), Some(BigUint::from(0xa5u32)));
+        assert_eq!(loaded.position(1), None, "thread without a draw");
+        assert_eq!(loaded.position(7), None, "thread beyond the layout");
+    }
+
+    #[test]
+    fn load_and_save_dir_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("puzzle_8.txt"), "9c\n").unwrap();
+        std::fs::write(dir.path().join("unrelated.log"), "noise").unwrap();
+        let cursors = load_dir(dir.path(), &collection()).unwrap();
+        assert_eq!(cursors.len(), 1);
+        save_dir(dir.path(), &cursors).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("puzzle_8.txt")).unwrap(),
+            "9c\n"
+        );
+    }
+}
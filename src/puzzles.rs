@@ -0,0 +1,365 @@
+//! Loading and querying the Bitcoin puzzle list.
+//!
+//! The puzzle list is the well-known "Bitcoin puzzle transaction" set: puzzle
+//! `N` has its private key somewhere in `[2^(N-1), 2^N - 1]`. We ship the data
+//! as `puzzles.json` next to the binary so it can be edited without a rebuild.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::{Deserialize, Serialize};
+
+/// Decoded prize-address target: the raw commitment the checker compares,
+/// computed once at load time so workers never touch Base58 or bech32
+/// parsing in the hot loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// `1…`: pays the hash160 of either pubkey serialization.
+    P2pkh { hash160: [u8; 20] },
+    /// `bc1q…` (v0, 20-byte program): pays the hash160 of the compressed
+    /// pubkey only.
+    P2wpkh { hash160: [u8; 20] },
+    /// `bc1p…` (v1): pays the x-only output key after the BIP341 key-path
+    /// tweak with no script tree.
+    P2tr { output_key: [u8; 32] },
+}
+
+impl Target {
+    /// Decode an address on the configured network into its matchable
+    /// commitment.
+    pub fn decode(address: &str) -> Result<Self> {
+        use bitcoin::hashes::Hash;
+        let parsed: bitcoin::Address<bitcoin::address::NetworkUnchecked> = address
+            .parse()
+            .map_err(|err: bitcoin::address::ParseError| anyhow::anyhow!("{err}"))?;
+        let parsed = parsed
+            .require_network(crate::checker::network())
+            .with_context(|| format!("not a {} address", crate::checker::network()))?;
+        if let Some(hash) = parsed.pubkey_hash() {
+            return Ok(Self::P2pkh {
+                hash160: hash.to_byte_array(),
+            });
+        }
+        if let Some(program) = parsed.witness_program() {
+            if program.is_p2wpkh() {
+                let mut hash160 = [0u8; 20];
+                hash160.copy_from_slice(program.program().as_bytes());
+                return Ok(Self::P2wpkh { hash160 });
+            }
+            if program.is_p2tr() {
+                let mut output_key = [0u8; 32];
+                output_key.copy_from_slice(program.program().as_bytes());
+                return Ok(Self::P2tr { output_key });
+            }
+        }
+        anyhow::bail!("only P2PKH, P2WPKH and P2TR targets are searchable")
+    }
+
+    /// The lowercase name matched against the optional `address_type`
+    /// field in `puzzles.json`.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            Self::P2pkh { .. } => "p2pkh",
+            Self::P2wpkh { .. } => "p2wpkh",
+            Self::P2tr { .. } => "p2tr",
+        }
+    }
+}
+
+/// A single puzzle entry from `puzzles.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Puzzle {
+    /// Puzzle number, which is also the bit length of the key range.
+    pub number: u32,
+    /// The P2PKH address holding the prize.
+    pub address: String,
+    /// Inclusive lower bound of the key range, hex encoded.
+    pub range_start: String,
+    /// Inclusive upper bound of the key range, hex encoded.
+    pub range_end: String,
+    /// Current prize in BTC.
+    pub reward_btc: f64,
+    /// Whether the puzzle has already been solved.
+    pub solved: bool,
+    /// Compressed public key (hex), known for puzzles whose prize address
+    /// has made an outgoing spend. When set, interval algorithms like
+    /// Pollard's kangaroo apply and random search is pointless.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Skip the uncompressed serialization when checking candidates.
+    /// Every puzzle address created this century comes from a compressed
+    /// key, so this halves the hashing per candidate; opt-in because the
+    /// earliest lists do contain uncompressed-key addresses. The global
+    /// `COMPRESSED_ONLY=true` applies the same cut to every puzzle.
+    #[serde(default)]
+    pub compressed_only: bool,
+    /// Search strategy override: `"walk"` replaces independent random
+    /// draws with a pseudorandom walk (random start, jumps derived from
+    /// the current point), which keeps successive keys close together;
+    /// `"hybrid"` scans a sequential window from each random anchor
+    /// (`HYBRID_WINDOW` keys); `"exhaustive"` enumerates every key in
+    /// order (tiny sanity-check ranges only). Absent or `"random"` keeps
+    /// the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<String>,
+    /// Declared target script kind (`"p2pkh"`, `"p2wpkh"` or `"p2tr"`).
+    /// The address itself is authoritative; when this field is present the
+    /// load cross-checks the two and fails on a mismatch, catching a
+    /// pasted-in address of the wrong kind before any keys are burned on it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_type: Option<String>,
+    /// Compressed point bytes of `public_key`, filled at load like
+    /// `target`. When present the checker compares 33 serialized bytes per
+    /// candidate instead of hashing, and the interval solvers skip
+    /// re-parsing the hex.
+    #[serde(skip)]
+    pub pubkey_point: Option<[u8; 33]>,
+    /// Decoded [`Target`], filled in by [`PuzzleCollection::load`] (and by
+    /// hand where puzzles are built programmatically). Never serialized;
+    /// the address string stays the source of truth on disk.
+    #[serde(skip)]
+    pub target: Option<Target>,
+}
+
+impl Puzzle {
+    /// Number of bits in the key range (equal to the puzzle number).
+    pub fn bits(&self) -> u32 {
+        self.number
+    }
+
+    /// Parse the inclusive range bounds into big integers.
+    pub fn range(&self) -> Result<(BigUint, BigUint)> {
+        let start = BigUint::from_str_radix(self.range_start.trim_start_matches("0x"), 16)
+            .with_context(|| format!("puzzle #{}: bad range_start", self.number))?;
+        let end = BigUint::from_str_radix(self.range_end.trim_start_matches("0x"), 16)
+            .with_context(|| format!("puzzle #{}: bad range_end", self.number))?;
+        Ok((start, end))
+    }
+
+    /// Total number of keys in the range.
+    pub fn range_size(&self) -> Result<BigUint> {
+        let (start, end) = self.range()?;
+        Ok(end - start + 1u32)
+    }
+
+    /// Split the key range into up to `n` disjoint sub-ranges, one per
+    /// worker thread, so threads never duplicate draws within a session.
+    pub fn get_subranges(&self, n: usize) -> Result<Vec<(BigUint, BigUint)>> {
+        let (start, end) = self.range()?;
+        Ok(split_range(&start, &end, n))
+    }
+}
+
+/// Parse the hex `public_key` field into compressed point bytes.
+fn parse_public_key(hex_key: &str) -> Result<[u8; 33]> {
+    let bytes = hex::decode(hex_key.trim()).context("not hex")?;
+    let point =
+        bitcoin::secp256k1::PublicKey::from_slice(&bytes).context("not a curve point")?;
+    Ok(point.serialize())
+}
+
+/// Split `[start, end]` into up to `n` contiguous, disjoint slices of
+/// near-equal size; the last slice absorbs the rounding remainder. Ranges
+/// with fewer than `n` keys yield fewer slices rather than empty ones.
+pub fn split_range(start: &BigUint, end: &BigUint, n: usize) -> Vec<(BigUint, BigUint)> {
+    use num_traits::One;
+    if start > end || n == 0 {
+        return Vec::new();
+    }
+    let size = end - start + 1u32;
+    let share = (&size / n).max(BigUint::one());
+    let mut cursor = start.clone();
+    let mut slices = Vec::new();
+    for i in 0..n {
+        if cursor > *end {
+            break;
+        }
+        let slice_end = if i + 1 == n {
+            end.clone()
+        } else {
+            (&cursor + &share - 1u32).min(end.clone())
+        };
+        slices.push((cursor.clone(), slice_end.clone()));
+        cursor = slice_end + 1u32;
+    }
+    slices
+}
+
+/// The full puzzle list plus convenience queries used by the scheduler and
+/// the Telegram commands.
+#[derive(Debug, Clone)]
+pub struct PuzzleCollection {
+    puzzles: Vec<Puzzle>,
+}
+
+impl PuzzleCollection {
+    /// Load the collection from a JSON file, decoding every prize address
+    /// into its [`Target`]. Undecodable addresses fail the whole load —
+    /// a worker sweeping millions of keys against a mistyped target is
+    /// wasted electricity.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut collection = Self::load_lenient(path)?;
+        let mut bad = Vec::new();
+        for puzzle in &mut collection.puzzles {
+            match Target::decode(&puzzle.address) {
+                Ok(target) => match &puzzle.address_type {
+                    Some(declared) if declared != target.kind_name() => bad.push(format!(
+                        "#{} (address_type says {declared}, address decodes as {})",
+                        puzzle.number,
+                        target.kind_name()
+                    )),
+                    _ => puzzle.target = Some(target),
+                },
+                Err(err) => bad.push(format!("#{} ({err:#})", puzzle.number)),
+            }
+            if let Some(hex_key) = &puzzle.public_key {
+                match parse_public_key(hex_key) {
+                    Ok(point) => puzzle.pubkey_point = Some(point),
+                    Err(err) => {
+                        bad.push(format!("#{} (bad public_key: {err:#})", puzzle.number))
+                    }
+                }
+            }
+        }
+        if !bad.is_empty() {
+            anyhow::bail!(
+                "{}: unusable prize address or public key on puzzle(s) {}",
+                path.display(),
+                bad.join(", ")
+            );
+        }
+        tracing::info!(
+            "loaded {} puzzles from {}",
+            collection.puzzles.len(),
+            path.display()
+        );
+        Ok(collection)
+    }
+
+    /// Parse the file without decoding targets. For tooling (the `validate`
+    /// subcommand) that wants to report every problem in one pass instead of
+    /// stopping at the first bad address.
+    pub fn load_lenient(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("reading puzzle file {}", path.display()))?;
+        let puzzles: Vec<Puzzle> =
+            serde_json::from_str(&data).context("parsing puzzle file JSON")?;
+        Ok(Self { puzzles })
+    }
+
+    pub fn all(&self) -> &[Puzzle] {
+        &self.puzzles
+    }
+
+    pub fn get(&self, number: u32) -> Option<&Puzzle> {
+        self.puzzles.iter().find(|p| p.number == number)
+    }
+
+    /// Unsolved puzzles whose bit length falls inside `[min_bits, max_bits]`.
+    pub fn eligible(&self, min_bits: u32, max_bits: u32) -> Vec<&Puzzle> {
+        self.puzzles
+            .iter()
+            .filter(|p| !p.solved && p.bits() >= min_bits && p.bits() <= max_bits)
+            .collect()
+    }
+
+    /// Mark one puzzle solved in memory (the file is left untouched).
+    /// Returns whether it was previously unsolved.
+    pub fn mark_solved(&mut self, number: u32) -> bool {
+        match self.puzzles.iter_mut().find(|p| p.number == number) {
+            Some(puzzle) if !puzzle.solved => {
+                puzzle.solved = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn solved_count(&self) -> usize {
+        self.puzzles.iter().filter(|p| p.solved).count()
+    }
+
+    pub fn unsolved_count(&self) -> usize {
+        self.puzzles.iter().filter(|p| !p.solved).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Puzzle {
+        Puzzle {
+            number: 8,
+            address: "1ABC".into(),
+            range_start: "80".into(),
+            range_end: "ff".into(),
+            reward_btc: 0.08,
+            solved: false,
+            public_key: None,
+            compressed_only: false,
+            strategy: None,
+            address_type: None,
+            pubkey_point: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn target_decode_accepts_searchable_kinds_only() {
+        let legacy = Target::decode("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH").unwrap();
+        assert_eq!(legacy.kind_name(), "p2pkh");
+        let segwit = Target::decode("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+        assert_eq!(segwit.kind_name(), "p2wpkh");
+        let taproot = Target::decode(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        )
+        .unwrap();
+        assert_eq!(taproot.kind_name(), "p2tr");
+        assert!(Target::decode("3P14159f73E4gFr7JterCCQh9QjiTjiZrG").is_err(), "P2SH");
+        assert!(Target::decode("not-an-address").is_err());
+    }
+
+    #[test]
+    fn mark_solved_flips_once() {
+        let mut collection = PuzzleCollection {
+            puzzles: vec![sample()],
+        };
+        let number = collection.all()[0].number;
+        assert!(collection.mark_solved(number));
+        assert!(!collection.mark_solved(number), "already solved");
+        assert!(!collection.mark_solved(9999), "unknown puzzle");
+        assert_eq!(collection.unsolved_count(), 0);
+    }
+
+    #[test]
+    fn range_parses_hex_bounds() {
+        let (start, end) = sample().range().unwrap();
+        assert_eq!(start, BigUint::from(0x80u32));
+        assert_eq!(end, BigUint::from(0xffu32));
+    }
+
+    #[test]
+    fn range_size_is_inclusive() {
+        assert_eq!(sample().range_size().unwrap(), BigUint::from(128u32));
+    }
+
+    #[test]
+    fn subranges_are_disjoint_and_cover_the_range() {
+        let slices = sample().get_subranges(3).unwrap();
+        assert_eq!(slices.len(), 3);
+        assert_eq!(slices[0].0, BigUint::from(0x80u32));
+        assert_eq!(slices[2].1, BigUint::from(0xffu32));
+        for pair in slices.windows(2) {
+            assert_eq!(&pair[0].1 + 1u32, pair[1].0, "gap or overlap");
+        }
+        // More threads than keys: one single-key slice each, no empties.
+        let tiny = split_range(&BigUint::from(5u32), &BigUint::from(6u32), 4);
+        assert_eq!(tiny.len(), 2);
+        assert_eq!(tiny[0], (BigUint::from(5u32), BigUint::from(5u32)));
+        assert_eq!(tiny[1], (BigUint::from(6u32), BigUint::from(6u32)));
+    }
+}
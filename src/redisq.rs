@@ -0,0 +1,256 @@
+//! Redis-backed shared work queue.
+//!
+//! With `REDIS_URL` set, instances stop scheduling their own sessions and
+//! instead allocate work units from Redis: an atomic per-puzzle counter
+//! (`INCR`) hands out disjoint unit indices, and a per-puzzle set records
+//! which units any instance has already searched. No coordinator process is
+//! needed — every instance runs the same binary against the same Redis and
+//! naturally pulls disjoint ranges. Keys live under `REDIS_NAMESPACE`
+//! (default `btclotto`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use redis::AsyncCommands;
+
+use crate::cluster::WorkUnit;
+use crate::config::Config;
+use crate::notify::Fanout;
+use crate::puzzles::Puzzle;
+use crate::scheduler;
+use crate::state::AppState;
+
+/// How many already-searched indices to skip per lease before giving up.
+const LEASE_ATTEMPTS: u32 = 32;
+
+/// Allocates work units and coverage state out of a shared Redis.
+pub struct RedisQueue {
+    client: redis::Client,
+    ns: String,
+    unit_keys: u64,
+    /// Seconds before another instance may take over an unfinished lease.
+    lease_ttl_secs: u64,
+}
+
+/// Seconds since the Unix epoch, for lease timestamps shared via Redis.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Number of units a puzzle range splits into (capped at `u64::MAX` for
+/// ranges too large to ever exhaust).
+fn total_units(size: &BigUint, unit_keys: u64) -> u64 {
+    ((size + unit_keys - 1u32) / unit_keys).to_u64().unwrap_or(u64::MAX)
+}
+
+/// Inclusive bounds of unit `index` within `[start, end]`.
+fn unit_bounds(start: &BigUint, end: &BigUint, unit_keys: u64, index: u64) -> (BigUint, BigUint) {
+    let unit_start = start + unit_keys * index;
+    let mut unit_end = &unit_start + unit_keys - 1u32;
+    if &unit_end > end {
+        unit_end = end.clone();
+    }
+    (unit_start, unit_end)
+}
+
+impl RedisQueue {
+    /// Build the queue client when `REDIS_URL` is set.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let url = config.redis_url.as_ref()?;
+        match redis::Client::open(url.as_str()) {
+            Ok(client) => Some(Self {
+                client,
+                ns: config.redis_namespace.clone(),
+                unit_keys: config.work_unit_keys.max(1),
+                lease_ttl_secs: config.lease_ttl_secs.max(1),
+            }),
+            Err(err) => {
+                tracing::warn!("invalid REDIS_URL, shared queue disabled: {err}");
+                None
+            }
+        }
+    }
+
+    /// Allocate the next unsearched unit of the puzzle, or `None` when the
+    /// whole range is marked searched.
+    pub async fn lease(&self, puzzle: &Puzzle) -> Result<Option<WorkUnit>> {
+        let (start, end) = puzzle.range()?;
+        let units = total_units(&puzzle.range_size()?, self.unit_keys);
+        let mut con = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("connecting to Redis")?;
+        let counter = format!("{}:cursor:{}", self.ns, puzzle.number);
+        let done = format!("{}:done:{}", self.ns, puzzle.number);
+        let leases = format!("{}:leases:{}", self.ns, puzzle.number);
+        let searched: u64 = con.scard(&done).await.context("SCARD failed")?;
+        if searched >= units {
+            return Ok(None);
+        }
+        // Take over a lease whose holder went quiet before drawing fresh
+        // indices, so a crashed instance's unit is reassigned.
+        let held: std::collections::HashMap<String, u64> =
+            con.hgetall(&leases).await.context("HGETALL failed")?;
+        let now = unix_now();
+        for (field, leased_at) in held {
+            if now.saturating_sub(leased_at) < self.lease_ttl_secs {
+                continue;
+            }
+            let Ok(index) = field.parse::<u64>() else {
+                continue;
+            };
+            let already: bool = con
+                .sismember(&done, index)
+                .await
+                .context("SISMEMBER failed")?;
+            if already {
+                let _: () = con.hdel(&leases, &field).await.context("HDEL failed")?;
+                continue;
+            }
+            tracing::info!("taking over expired lease on unit {index}");
+            let _: () = con.hset(&leases, &field, now).await.context("HSET failed")?;
+            let (unit_start, unit_end) = unit_bounds(&start, &end, self.unit_keys, index);
+            return Ok(Some(WorkUnit {
+                id: index,
+                puzzle_number: puzzle.number,
+                range_start: format!("{unit_start:x}"),
+                range_end: format!("{unit_end:x}"),
+            }));
+        }
+        for _ in 0..LEASE_ATTEMPTS {
+            let next: u64 = con.incr(&counter, 1u64).await.context("INCR failed")?;
+            let index = (next - 1) % units;
+            let already: bool = con
+                .sismember(&done, index)
+                .await
+                .context("SISMEMBER failed")?;
+            if already {
+                continue;
+            }
+            let _: () = con
+                .hset(&leases, index.to_string(), unix_now())
+                .await
+                .context("HSET failed")?;
+            let (unit_start, unit_end) = unit_bounds(&start, &end, self.unit_keys, index);
+            return Ok(Some(WorkUnit {
+                id: index,
+                puzzle_number: puzzle.number,
+                range_start: format!("{unit_start:x}"),
+                range_end: format!("{unit_end:x}"),
+            }));
+        }
+        // Everything we drew was already searched; try again next round.
+        Ok(None)
+    }
+
+    /// Record a finished unit in the shared coverage set and fleet totals.
+    pub async fn mark_done(&self, puzzle_number: u32, index: u64, keys_checked: u64) -> Result<()> {
+        let mut con = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("connecting to Redis")?;
+        let done = format!("{}:done:{}", self.ns, puzzle_number);
+        let _: () = con.sadd(&done, index).await.context("SADD failed")?;
+        let leases = format!("{}:leases:{}", self.ns, puzzle_number);
+        let _: () = con
+            .hdel(&leases, index.to_string())
+            .await
+            .context("HDEL failed")?;
+        let total = format!("{}:keys_checked", self.ns);
+        let _: () = con
+            .incr(&total, keys_checked)
+            .await
+            .context("INCRBY failed")?;
+        Ok(())
+    }
+}
+
+/// Worker loop against the shared Redis queue; replaces the standalone
+/// scheduler loop, mirroring the coordinator-mode worker.
+pub async fn run_worker(state: Arc<AppState>, notifier: Arc<Fanout>, queue: RedisQueue) {
+    let interval = Duration::from_secs(state.config.scheduler.session_interval_secs);
+    tracing::info!("shared-queue mode: leasing work units from Redis");
+    loop {
+        state.heartbeat();
+        if state.shutdown_requested() {
+            return;
+        }
+        if !state.is_running() {
+            tokio::time::sleep(interval).await;
+            continue;
+        }
+        let Some(puzzle) = scheduler::pick_puzzle(&state) else {
+            tracing::warn!("no eligible puzzles; check MIN_BITS/MAX_BITS and the puzzle file");
+            tokio::time::sleep(interval).await;
+            continue;
+        };
+        let unit = match queue.lease(&puzzle).await {
+            Ok(Some(unit)) => unit,
+            Ok(None) => {
+                tracing::info!("no unsearched units for puzzle #{}; sleeping", puzzle.number);
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("Redis unavailable: {err:#}");
+                tokio::time::sleep(interval).await;
+                continue;
+            }
+        };
+        let (start, end) = match unit.range() {
+            Ok(range) => range,
+            Err(err) => {
+                tracing::warn!("bad work unit: {err:#}");
+                continue;
+            }
+        };
+        tracing::info!(
+            "searching unit {} (puzzle #{}, {}..{})",
+            unit.id,
+            unit.puzzle_number,
+            unit.range_start,
+            unit.range_end
+        );
+        state.set_active_puzzle(Some(puzzle.number));
+        let before = state.stats.total_checked();
+        let matches = scheduler::run_session_in(&state, &puzzle, &start, &end).await;
+        state.mark_session();
+        let checked = state.stats.total_checked() - before;
+        for result in &matches {
+            scheduler::handle_match(&state, &notifier, result).await;
+        }
+        if let Err(err) = queue.mark_done(unit.puzzle_number, unit.id, checked).await {
+            tracing::warn!("failed to record unit completion in Redis: {err:#}");
+        }
+        // Unit ids are bucket indices, so the gossip record can share them.
+        state.coverage.mark(unit.puzzle_number, unit.id);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_ranges_into_inclusive_units() {
+        let start = BigUint::from(0x80u32);
+        let end = BigUint::from(0xffu32);
+        assert_eq!(total_units(&BigUint::from(128u32), 0x40), 2);
+        assert_eq!(total_units(&BigUint::from(129u32), 0x40), 3);
+        let (a0, a1) = unit_bounds(&start, &end, 0x40, 0);
+        let (b0, b1) = unit_bounds(&start, &end, 0x40, 1);
+        assert_eq!((a0, a1), (BigUint::from(0x80u32), BigUint::from(0xbfu32)));
+        assert_eq!(b0, BigUint::from(0xc0u32));
+        // The last unit clamps to the range end.
+        assert_eq!(b1, BigUint::from(0xffu32));
+    }
+}
@@ -0,0 +1,136 @@
+//! Per-user roles for the Telegram command dispatcher.
+//!
+//! The chat whitelist decides who may talk to the bot at all; roles decide
+//! what they may do. Viewers get the read-only commands (`/status`,
+//! `/stats`, …), admins everything else. Assignments persist as JSON in
+//! `DATA_DIR/roles.json` and are seeded from `TELEGRAM_ADMINS`; while no
+//! assignment exists anywhere the store is open and every whitelisted user
+//! acts as an admin, so existing single-operator setups keep working.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{bail, Context, Result};
+
+/// What a user is allowed to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Read-only commands.
+    Viewer,
+    /// Everything, including start/stop and role assignment.
+    Admin,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "viewer"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "viewer" => Ok(Role::Viewer),
+            "admin" => Ok(Role::Admin),
+            other => bail!("unknown role {other:?}; expected viewer or admin"),
+        }
+    }
+}
+
+/// Persisted user-id → role assignments.
+pub struct RoleStore {
+    path: PathBuf,
+    roles: Mutex<HashMap<i64, Role>>,
+}
+
+impl RoleStore {
+    /// Load the store at `path`, seeding `seed_admins` as admins unless an
+    /// explicit assignment already overrides them. An unreadable file is
+    /// logged and treated as empty rather than taking the bot down.
+    pub fn open(path: &Path, seed_admins: &[i64]) -> Self {
+        let mut roles: HashMap<i64, Role> = match std::fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|err| {
+                tracing::warn!("ignoring malformed role store {}: {err}", path.display());
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        for id in seed_admins {
+            roles.entry(*id).or_insert(Role::Admin);
+        }
+        Self {
+            path: path.to_path_buf(),
+            roles: Mutex::new(roles),
+        }
+    }
+
+    /// The effective role of one user. While the store is completely empty
+    /// everyone is an admin (the historical whitelist-only behavior); once
+    /// any assignment exists, unassigned users fall back to viewer.
+    pub fn role_of(&self, user_id: Option<i64>) -> Role {
+        let roles = self.roles.lock().unwrap();
+        if roles.is_empty() {
+            return Role::Admin;
+        }
+        user_id
+            .and_then(|id| roles.get(&id).copied())
+            .unwrap_or(Role::Viewer)
+    }
+
+    /// Assign and persist a role for one user.
+    pub fn assign(&self, user_id: i64, role: Role) -> Result<()> {
+        let mut roles = self.roles.lock().unwrap();
+        roles.insert(user_id, role);
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let data = serde_json::to_string_pretty(&*roles)?;
+        std::fs::write(&self.path, data)
+            .with_context(|| format!("writing role store {}", self.path.display()))
+    }
+
+    /// One `id: role` line per assignment, sorted by user id.
+    pub fn lines(&self) -> Vec<String> {
+        let roles = self.roles.lock().unwrap();
+        let mut entries: Vec<(i64, Role)> = roles.iter().map(|(id, role)| (*id, *role)).collect();
+        entries.sort_by_key(|(id, _)| *id);
+        entries
+            .into_iter()
+            .map(|(id, role)| format!("{id}: {role}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_store_is_open_and_first_assignment_closes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RoleStore::open(&dir.path().join("roles.json"), &[]);
+        assert_eq!(store.role_of(Some(1)), Role::Admin);
+        assert_eq!(store.role_of(None), Role::Admin);
+        store.assign(1, Role::Admin).unwrap();
+        assert_eq!(store.role_of(Some(1)), Role::Admin);
+        assert_eq!(store.role_of(Some(2)), Role::Viewer);
+        assert_eq!(store.role_of(None), Role::Viewer);
+    }
+
+    #[test]
+    fn assignments_persist_and_override_the_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("roles.json");
+        RoleStore::open(&path, &[7]).assign(7, Role::Viewer).unwrap();
+        let reopened = RoleStore::open(&path, &[7]);
+        assert_eq!(reopened.role_of(Some(7)), Role::Viewer);
+    }
+}
@@ -0,0 +1,141 @@
+//! Size- and age-based rotation of append-only state files.
+//!
+//! Long-running instances grow the solutions/event/CSV files without bound.
+//! Before each append the writer calls [`rotate_if_needed`]; when the file
+//! exceeds the size limit or age limit it is renamed to
+//! `<name>.<UTC stamp>` and a fresh file starts. Rotated files beyond the
+//! retention count are deleted, oldest first.
+//!
+//! Defaults come from the environment: `ROTATE_MAX_BYTES` (default 10 MiB),
+//! `ROTATE_MAX_AGE_DAYS` (default 30), `ROTATE_KEEP` (default 5 rotated
+//! files per target).
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+/// Rotation policy for one file.
+#[derive(Debug, Clone, Copy)]
+pub struct RotationPolicy {
+    pub max_bytes: u64,
+    pub max_age: Duration,
+    pub keep: usize,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_age: Duration::from_secs(30 * 24 * 3600),
+            keep: 5,
+        }
+    }
+}
+
+impl RotationPolicy {
+    /// Policy from the environment, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let parse = |key: &str| std::env::var(key).ok().and_then(|v| v.parse::<u64>().ok());
+        Self {
+            max_bytes: parse("ROTATE_MAX_BYTES").unwrap_or(defaults.max_bytes),
+            max_age: parse("ROTATE_MAX_AGE_DAYS")
+                .map(|d| Duration::from_secs(d * 24 * 3600))
+                .unwrap_or(defaults.max_age),
+            keep: parse("ROTATE_KEEP").map(|k| k as usize).unwrap_or(defaults.keep),
+        }
+    }
+}
+
+/// Rotate `path` if it exceeds the policy's size or age. Returns the rotated
+/// file's path when a rotation happened.
+pub fn rotate_if_needed(path: &Path, policy: &RotationPolicy) -> Result<Option<PathBuf>> {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return Ok(None); // Nothing to rotate yet.
+    };
+    let too_big = metadata.len() >= policy.max_bytes;
+    let too_old = metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .is_some_and(|age| age >= policy.max_age);
+    if !too_big && !too_old {
+        return Ok(None);
+    }
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("rotation target has no file name")?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ");
+    let rotated = path.with_file_name(format!("{file_name}.{stamp}"));
+    std::fs::rename(path, &rotated)
+        .with_context(|| format!("rotating {} to {}", path.display(), rotated.display()))?;
+    tracing::info!("rotated {} to {}", path.display(), rotated.display());
+    prune_rotated(path, file_name, policy.keep)?;
+    Ok(Some(rotated))
+}
+
+/// Delete rotated copies of `path` beyond `keep`, oldest first.
+fn prune_rotated(path: &Path, file_name: &str, keep: usize) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let prefix = format!("{file_name}.");
+    let mut rotated: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                // Rotated files end in a timestamp; don't touch .bak backups.
+                n.strip_prefix(&prefix)
+                    .is_some_and(|rest| rest.ends_with('Z') && !rest.contains('.'))
+            })
+        })
+        .collect();
+    rotated.sort();
+    while rotated.len() > keep {
+        let oldest = rotated.remove(0);
+        if let Err(err) = std::fs::remove_file(&oldest) {
+            tracing::warn!("failed to prune rotated file {}: {err}", oldest.display());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiny_policy(keep: usize) -> RotationPolicy {
+        RotationPolicy {
+            max_bytes: 8,
+            max_age: Duration::from_secs(3600),
+            keep,
+        }
+    }
+
+    #[test]
+    fn small_fresh_file_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        std::fs::write(&path, "hi").unwrap();
+        assert!(rotate_if_needed(&path, &tiny_policy(3)).unwrap().is_none());
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn oversized_file_is_rotated_and_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        for _ in 0..4 {
+            std::fs::write(&path, "0123456789abcdef").unwrap();
+            assert!(rotate_if_needed(&path, &tiny_policy(2)).unwrap().is_some());
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert!(!path.exists());
+        let rotated = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(rotated, 2);
+    }
+}
@@ -0,0 +1,909 @@
+//! Session scheduler: periodically runs a burst of random search across
+//! worker threads and reports the results.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use rand::seq::SliceRandom;
+
+use crate::checker::{self, CheckResult};
+use crate::exporter::Exporter;
+use crate::keygen;
+use crate::metrics::ErrorKind;
+use crate::notify::{Event, Fanout};
+use crate::progress::Checkpoint;
+use crate::puzzles::Puzzle;
+use crate::state::AppState;
+use crate::watchdog::Watchdog;
+
+/// Run the scheduler loop until shutdown is requested.
+pub async fn run(state: Arc<AppState>, notifier: Arc<Fanout>) {
+    // Worker mode: lease units from a coordinator instead of scheduling
+    // sessions locally.
+    if let Some(url) = state.config.coordinator_url.clone() {
+        crate::cluster::run_worker(state, notifier, url).await;
+        return;
+    }
+    // Pool mode: fetch assigned ranges from a community work server.
+    if let Some(pool) = crate::pool::PoolClient::from_config(&state.config) {
+        crate::pool::run_worker(state, notifier, pool).await;
+        return;
+    }
+    // Shared-queue mode: allocate units from Redis instead.
+    if let Some(queue) = crate::redisq::RedisQueue::from_config(&state.config) {
+        crate::redisq::run_worker(state, notifier, queue).await;
+        return;
+    }
+    let interval = Duration::from_secs(state.config.scheduler.session_interval_secs);
+    let stats_interval = Duration::from_secs(state.config.scheduler.stats_interval_secs);
+    let mut last_stats = Instant::now();
+    let mut checked_at_last_stats = state.stats.total_checked();
+    let mut watchdog = Watchdog::from_config(&state.config);
+    let exporter = Exporter::from_config(&state.config);
+    let push_client = crate::cluster::worker_client(&state.config);
+
+    // Drop puzzles someone else already swept before burning compute on
+    // them, then re-check on a timer during the run.
+    let solved_scan = Duration::from_secs(state.config.solved_scan_interval_secs);
+    let mut last_solved_scan = Instant::now();
+    if !solved_scan.is_zero() {
+        scan_solved_puzzles(&state, &notifier).await;
+    }
+
+    loop {
+        state.heartbeat();
+        if state.shutdown_requested() {
+            return;
+        }
+        if state.is_running() {
+            if let Some(puzzle) = pick_puzzle(&state) {
+                state.set_active_puzzle(Some(puzzle.number));
+                tracing::info!(
+                    "starting session on puzzle #{} ({} threads, {} keys in range)",
+                    puzzle.number,
+                    state.config.scheduler.threads,
+                    puzzle
+                        .range_size()
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|_| "?".to_string())
+                );
+                let matches = run_session(&state, &puzzle).await;
+                state.mark_session();
+                for result in matches {
+                    handle_match(&state, &notifier, &result).await;
+                }
+            } else {
+                tracing::warn!("no eligible puzzles; check MIN_BITS/MAX_BITS and the puzzle file");
+            }
+        }
+        if !solved_scan.is_zero() && last_solved_scan.elapsed() >= solved_scan {
+            last_solved_scan = Instant::now();
+            scan_solved_puzzles(&state, &notifier).await;
+        }
+        if last_stats.elapsed() >= stats_interval {
+            let elapsed = last_stats.elapsed().as_secs().max(1);
+            let checked_now = state.stats.total_checked();
+            let rate = (checked_now - checked_at_last_stats) / elapsed;
+            checked_at_last_stats = checked_now;
+            last_stats = Instant::now();
+            report_stats(&state, &notifier, rate).await;
+            update_puzzle_gauges(&state, rate);
+            if let Some(alert) = watchdog.observe(rate) {
+                tracing::warn!("watchdog: {}", alert.replace('\n', ", "));
+                notifier.dispatch(&Event::Alert(alert)).await;
+            }
+            if let Some(exporter) = &exporter {
+                if let Err(err) = exporter.push(&state, rate).await {
+                    tracing::warn!("metrics push failed: {err:#}");
+                }
+            }
+            if let Some(url) = &state.config.stats_push_url {
+                if let Err(err) =
+                    crate::cluster::push_node_stats(&push_client, url, &state, rate).await
+                {
+                    tracing::warn!("stats push to primary failed: {err:#}");
+                }
+            }
+            if let Some(csv) = &state.config.stats_csv_file {
+                if let Err(err) = append_stats_csv(&state, csv, rate) {
+                    state.metrics.record_error(ErrorKind::Io);
+                    tracing::warn!("failed to append stats CSV row: {err:#}");
+                }
+            }
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Check every eligible puzzle's prize address against the chain and mark
+/// the empty ones solved: the balance is the prize, so an empty address
+/// means someone else already claimed it and sessions on it are wasted
+/// compute. Quietly a no-op without a chain backend.
+pub async fn scan_solved_puzzles(state: &AppState, notifier: &Fanout) {
+    let Some(chain) = &state.chain else { return };
+    let scheduler = &state.config.scheduler;
+    let eligible: Vec<Puzzle> = state
+        .puzzles()
+        .eligible(scheduler.min_bits, scheduler.max_bits)
+        .into_iter()
+        .cloned()
+        .collect();
+    for puzzle in eligible {
+        match chain.address_info(&puzzle.address).await {
+            Ok(info) if info.balance_sat() == 0 => {
+                if state.mark_puzzle_solved(puzzle.number) {
+                    tracing::warn!(
+                        "puzzle #{} already swept ({} is empty); dropping it",
+                        puzzle.number,
+                        puzzle.address
+                    );
+                    notifier
+                        .dispatch(&Event::Alert(format!(
+                            "Puzzle #{} looks already solved elsewhere: {} holds no \
+                             balance. Skipping it from now on.",
+                            puzzle.number, puzzle.address
+                        )))
+                        .await;
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                state.metrics.record_error(ErrorKind::Io);
+                tracing::warn!(
+                    "balance check for puzzle #{} failed: {err:#}",
+                    puzzle.number
+                );
+            }
+        }
+    }
+}
+
+/// Pick the puzzle for the next session: the focused one if set, otherwise a
+/// random eligible puzzle. The cluster coordinator uses the same policy to
+/// decide which puzzle's range to slice next.
+pub fn pick_puzzle(state: &AppState) -> Option<Puzzle> {
+    if let Some(number) = state.focused_puzzle() {
+        return state.puzzles().get(number).cloned();
+    }
+    let scheduler = &state.config.scheduler;
+    let puzzles = state.puzzles();
+    let eligible: Vec<_> = puzzles
+        .eligible(scheduler.min_bits, scheduler.max_bits)
+        .into_iter()
+        .filter(|p| !state.is_puzzle_exhausted(p.number))
+        .collect();
+    eligible.choose(&mut rand::thread_rng()).map(|p| (*p).clone())
+}
+
+/// Run one fixed-duration solving session over the puzzle's full range.
+async fn run_session(state: &Arc<AppState>, puzzle: &Puzzle) -> Vec<CheckResult> {
+    // The exhaustive strategy wins over everything else: it exists to
+    // prove a range empty, which no probabilistic mode can.
+    if matches!(puzzle.strategy.as_deref(), Some("exhaustive")) {
+        return crate::exhaustive::run_session(state, puzzle).await;
+    }
+    // An exposed public key makes this an interval discrete-log problem;
+    // the kangaroo solver gets there in ~sqrt(range) group operations
+    // where random search would take half the range. BSGS trades memory
+    // for determinism when asked for.
+    if puzzle.public_key.is_some() {
+        return if state.config.pubkey_solver == "bsgs" {
+            crate::bsgs::run_session(state, puzzle).await
+        } else {
+            crate::kangaroo::run_session(state, puzzle).await
+        };
+    }
+    match puzzle.range() {
+        Ok((start, end)) => run_session_in(state, puzzle, &start, &end).await,
+        Err(err) => {
+            state.metrics.record_error(ErrorKind::Keygen);
+            tracing::error!("bad puzzle range: {err:#}");
+            Vec::new()
+        }
+    }
+}
+
+/// Run one fixed-duration solving session restricted to an explicit key
+/// range (a leased work unit, in distributed mode) on blocking worker
+/// threads.
+pub async fn run_session_in(
+    state: &Arc<AppState>,
+    puzzle: &Puzzle,
+    range_start: &num_bigint::BigUint,
+    range_end: &num_bigint::BigUint,
+) -> Vec<CheckResult> {
+    let session_span = tracing::info_span!(
+        "session",
+        puzzle = puzzle.number,
+        threads = state.config.scheduler.threads
+    );
+    let _session = session_span.enter();
+    if let Some(seed) = state.config.search_seed {
+        tracing::info!("deterministic session: SEARCH_SEED={seed} (thread n uses seed+n)");
+    }
+    let duration = Duration::from_secs(state.config.scheduler.session_duration_secs);
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut handles = Vec::new();
+
+    // Stride mode keeps a live position per worker and checkpoints them to
+    // disk on a timer, so a crash or redeploy resumes mid-sweep.
+    let scheduler_cfg = &state.config.scheduler;
+    let positions: Option<StridePositions> = (scheduler_cfg.stride > 0).then(|| {
+        Arc::new(
+            (0..scheduler_cfg.threads)
+                .map(|_| std::sync::Mutex::new(None))
+                .collect::<Vec<_>>(),
+        )
+    });
+    let resume = positions
+        .as_ref()
+        .and_then(|_| load_checkpoint(state, puzzle, range_start, range_end));
+
+    // Random modes get the range pre-split into one disjoint slice per
+    // thread, so threads never duplicate draws within a session. Stride
+    // mode already partitions by lattice offset and keeps the full range.
+    let subranges = (scheduler_cfg.stride == 0)
+        .then(|| crate::puzzles::split_range(range_start, range_end, scheduler_cfg.threads));
+
+    for thread_id in 0..state.config.scheduler.threads {
+        let state = Arc::clone(state);
+        let puzzle = puzzle.clone();
+        // A range too tiny to slice leaves the spare threads sampling all
+        // of it, which duplicates nothing worth worrying about.
+        let range = subranges
+            .as_ref()
+            .and_then(|slices| slices.get(thread_id).cloned())
+            .unwrap_or_else(|| (range_start.clone(), range_end.clone()));
+        let stop = Arc::clone(&stop);
+        let resume_at = resume.as_ref().and_then(|cp| cp.position(thread_id));
+        let positions = positions.clone();
+        let worker_span =
+            tracing::info_span!(parent: &session_span, "worker", thread_id, puzzle = puzzle.number);
+        handles.push(tokio::task::spawn_blocking(move || {
+            let _worker = worker_span.enter();
+            worker_loop(&state, &puzzle, &range, thread_id, &stop, resume_at, positions)
+        }));
+    }
+
+    // An OpenCL worker joins the session alongside the CPU threads when
+    // the `gpu` feature is compiled in and `GPU=true`.
+    #[cfg(feature = "gpu")]
+    if let Some(handle) = crate::gpu::spawn_worker(
+        Arc::clone(state),
+        puzzle.clone(),
+        (range_start.clone(), range_end.clone()),
+        Arc::clone(&stop),
+    ) {
+        handles.push(handle);
+    }
+    #[cfg(feature = "cuda")]
+    if let Some(handle) = crate::cuda::spawn_worker(
+        Arc::clone(state),
+        puzzle.clone(),
+        (range_start.clone(), range_end.clone()),
+        Arc::clone(&stop),
+    ) {
+        handles.push(handle);
+    }
+
+    let checkpoint_interval = state.config.scheduler.checkpoint_interval_secs;
+    match (&positions, checkpoint_interval) {
+        (Some(slots), secs) if secs > 0 => {
+            let deadline = Instant::now() + duration;
+            let interval = Duration::from_secs(secs);
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                tokio::time::sleep(remaining.min(interval)).await;
+                save_checkpoint(state, puzzle, (range_start, range_end), slots);
+            }
+        }
+        _ => tokio::time::sleep(duration).await,
+    }
+    stop.store(true, Ordering::Relaxed);
+
+    let mut matches = Vec::new();
+    for handle in handles {
+        match handle.await {
+            Ok(Ok(found)) => matches.extend(found),
+            Ok(Err(err)) => tracing::warn!("worker failed: {err:#}"),
+            Err(err) => tracing::warn!("worker panicked: {err}"),
+        }
+    }
+    // One final save after the workers stopped, so the file holds the
+    // exact positions the next session resumes from.
+    if let Some(slots) = &positions {
+        save_checkpoint(state, puzzle, (range_start, range_end), slots);
+    }
+    matches
+}
+
+/// Live stride positions, shared between the workers and the saver.
+type StridePositions = Arc<Vec<std::sync::Mutex<Option<num_bigint::BigUint>>>>;
+
+/// Read the puzzle's stride checkpoint, rejecting one written under a
+/// different lattice (stride or offset) or over a different range.
+fn load_checkpoint(
+    state: &AppState,
+    puzzle: &Puzzle,
+    range_start: &num_bigint::BigUint,
+    range_end: &num_bigint::BigUint,
+) -> Option<Checkpoint> {
+    let scheduler = &state.config.scheduler;
+    let path = Checkpoint::path(&state.config.progress_dir, puzzle.number);
+    if !path.exists() {
+        return None;
+    }
+    let checkpoint = match Checkpoint::load(&path) {
+        Ok(checkpoint) => checkpoint,
+        Err(err) => {
+            tracing::warn!("ignoring {}: {err:#}", path.display());
+            return None;
+        }
+    };
+    if checkpoint.stride != scheduler.stride
+        || checkpoint.stride_offset != scheduler.stride_offset
+        || checkpoint.range_start != format!("{range_start:x}")
+        || checkpoint.range_end != format!("{range_end:x}")
+    {
+        tracing::warn!(
+            "checkpoint {} covers a different lattice or range; starting fresh",
+            path.display(),
+        );
+        return None;
+    }
+    tracing::info!(
+        "resuming puzzle #{} stride sweep from {}",
+        puzzle.number,
+        path.display()
+    );
+    Some(checkpoint)
+}
+
+/// Snapshot the live worker positions into the puzzle's checkpoint file.
+fn save_checkpoint(
+    state: &AppState,
+    puzzle: &Puzzle,
+    (range_start, range_end): (&num_bigint::BigUint, &num_bigint::BigUint),
+    slots: &StridePositions,
+) {
+    let scheduler = &state.config.scheduler;
+    let positions: Vec<String> = slots
+        .iter()
+        .map(|slot| {
+            slot.lock()
+                .unwrap()
+                .as_ref()
+                .map(|position| format!("{position:x}"))
+                .unwrap_or_default()
+        })
+        .collect();
+    if positions.iter().all(String::is_empty) {
+        return;
+    }
+    let checkpoint = Checkpoint {
+        puzzle_number: puzzle.number,
+        range_start: format!("{range_start:x}"),
+        range_end: format!("{range_end:x}"),
+        stride: scheduler.stride,
+        stride_offset: scheduler.stride_offset,
+        positions,
+    };
+    let path = Checkpoint::path(&state.config.progress_dir, puzzle.number);
+    if let Err(err) = checkpoint.save(&path) {
+        tracing::warn!("stride checkpoint not written: {err:#}");
+    }
+}
+
+/// Body of one worker thread: generate keys — randomly, or walking a
+/// stride lattice when `STRIDE` is set — and check them until told to
+/// stop (or, in stride mode, the lattice is exhausted).
+fn worker_loop(
+    state: &AppState,
+    puzzle: &Puzzle,
+    (range_start, range_end): &(num_bigint::BigUint, num_bigint::BigUint),
+    thread_id: usize,
+    stop: &AtomicBool,
+    resume_at: Option<num_bigint::BigUint>,
+    positions: Option<StridePositions>,
+) -> Result<Vec<CheckResult>> {
+    let mut found = Vec::new();
+    let mut checked: u64 = 0;
+    // Keys already flushed into the shared stats/metrics counters.
+    let mut reported: u64 = 0;
+    // Buckets peers report as exhausted; keys landing there are redrawn.
+    let exhausted = state.coverage.exhausted(puzzle.number);
+    let bucket_origin = puzzle.range().map(|(start, _)| start).ok();
+    // Per-batch latency accumulators, reset on every observation.
+    let mut keygen_elapsed = Duration::ZERO;
+    let mut check_elapsed = Duration::ZERO;
+    // Stride mode: each thread walks its own lattice, offset by the
+    // worker-level STRIDE_OFFSET plus its thread id.
+    let scheduler = &state.config.scheduler;
+    let mut stride_scan = (scheduler.stride > 0).then(|| {
+        let mut scan = keygen::StrideScan::new(
+            range_start,
+            range_end,
+            scheduler.stride_offset + thread_id as u64,
+            scheduler.stride,
+        );
+        if let Some(position) = &resume_at {
+            scan.seek(position);
+        }
+        scan
+    });
+    let position_slot = positions
+        .as_deref()
+        .and_then(|slots| slots.get(thread_id));
+    // In stride mode each public key is derived from the previous one by a
+    // single point addition instead of a scalar multiplication from
+    // scratch; reset to `None` whenever the walk skips a key.
+    let mut walker: Option<checker::IncrementalWalker> = None;
+    // Endomorphism mode piggybacks five related candidates on every EC
+    // multiplication; the drawn key plus its images count as checked keys.
+    let endo = scheduler.endomorphism.then(checker::Endomorphism::new);
+    // Endomorphism and target-set checks both want the public key in hand
+    // rather than letting the checker derive it internally.
+    let derive_point = endo.is_some() || state.target_set.is_some() || state.vanity.is_some();
+    let keys_per_iteration: u64 = if endo.is_some() { 6 } else { 1 };
+    // Seeded mode: a deterministic ChaCha stream per thread, so a run is
+    // reproducible and two machines with different seeds never mirror
+    // each other's draws.
+    let mut seeded_rng = state.config.search_seed.map(|seed| {
+        use rand::SeedableRng;
+        rand_chacha::ChaCha12Rng::seed_from_u64(seed.wrapping_add(thread_id as u64))
+    });
+    // Per-puzzle random-walk strategy; stride mode, being a full
+    // deterministic sweep, still wins when both are configured.
+    let mut random_walk = if stride_scan.is_none()
+        && matches!(puzzle.strategy.as_deref(), Some("walk"))
+    {
+        let walk = match &mut seeded_rng {
+            Some(rng) => keygen::RandomWalk::new(rng, range_start, range_end),
+            None => keygen::RandomWalk::new(&mut rand::thread_rng(), range_start, range_end),
+        };
+        match walk {
+            Ok(walk) => Some(walk),
+            Err(err) => {
+                state.metrics.record_error(ErrorKind::Keygen);
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+    // Per-puzzle hybrid strategy: random anchors, each scanned forward for
+    // a window of consecutive keys that the incremental walker can follow.
+    let mut hybrid_scan = if stride_scan.is_none()
+        && matches!(puzzle.strategy.as_deref(), Some("hybrid"))
+    {
+        match keygen::HybridScan::new(range_start, range_end) {
+            Ok(scan) => Some(scan),
+            Err(err) => {
+                state.metrics.record_error(ErrorKind::Keygen);
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+    // Stride and hybrid modes both yield fixed-step key runs the walker
+    // can follow by point addition (hybrid steps by one inside a window).
+    let walker_stride = match &stride_scan {
+        Some(_) => Some(scheduler.stride),
+        None => hybrid_scan.as_ref().map(|_| 1),
+    };
+
+    while !stop.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        let mut key = match &mut stride_scan {
+            Some(scan) => match scan.next_key() {
+                Some(Ok(key)) => {
+                    // Publish the next unchecked key for the checkpointer.
+                    if let Some(slot) = position_slot {
+                        *slot.lock().unwrap() = Some(scan.position());
+                    }
+                    key
+                }
+                Some(Err(err)) => {
+                    // A lattice point outside the valid key space (zero or
+                    // past the curve order) is skipped, not fatal.
+                    state.metrics.record_error(ErrorKind::Keygen);
+                    tracing::debug!("skipping invalid lattice point: {err:#}");
+                    walker = None;
+                    continue;
+                }
+                None => {
+                    tracing::debug!("stride lattice exhausted");
+                    break;
+                }
+            },
+            None => {
+                let drawn = match (&mut hybrid_scan, &mut random_walk, &mut seeded_rng) {
+                    (Some(scan), _, rng) => {
+                        let window = state.hybrid_window();
+                        let stepped = match rng {
+                            Some(rng) => scan.next_key(rng, window),
+                            None => scan.next_key(&mut rand::thread_rng(), window),
+                        };
+                        stepped.map(|(key, jumped)| {
+                            if jumped {
+                                walker = None;
+                            }
+                            key
+                        })
+                    }
+                    (None, Some(walk), _) => walk.next_key(),
+                    (None, None, Some(rng)) => {
+                        keygen::generate_random_key_in_range_with(rng, range_start, range_end)
+                    }
+                    (None, None, None) => {
+                        keygen::generate_random_key_in_range(range_start, range_end)
+                    }
+                };
+                match drawn {
+                    Ok(key) => key,
+                    Err(err) => {
+                        state.metrics.record_error(ErrorKind::Keygen);
+                        return Err(err);
+                    }
+                }
+            }
+        };
+        keygen_elapsed += started.elapsed();
+        if let (Some(set), Some(origin)) = (&exhausted, &bucket_origin) {
+            let value = num_bigint::BigUint::from_bytes_be(&key.secret_bytes());
+            if set.contains(&state.coverage.bucket_of(origin, &value)) {
+                key.non_secure_erase();
+                walker = None;
+                continue;
+            }
+        }
+        // Imported dead zones: keys someone already searched are skipped
+        // in every mode, random and sequential alike.
+        if !state.searched.is_empty() {
+            let value = num_bigint::BigUint::from_bytes_be(&key.secret_bytes());
+            if state.searched.contains(&value) {
+                key.non_secure_erase();
+                walker = None;
+                continue;
+            }
+        }
+        // Random modes re-draw keys sooner than intuition suggests (the
+        // birthday bound); the bloom filter skips the repeats. Stride
+        // mode never repeats and bypasses it.
+        if stride_scan.is_none() {
+            if let Some(filter) = &state.tried {
+                if filter.check_and_insert(&key.secret_bytes()) {
+                    key.non_secure_erase();
+                    walker = None;
+                    continue;
+                }
+            }
+        }
+        let started = Instant::now();
+        // The endomorphism's related candidates are only worth checking
+        // after the drawn key itself missed.
+        let check_related = |point: &bitcoin::secp256k1::PublicKey,
+                             result: Result<Option<CheckResult>>| {
+            match (result, &endo) {
+                (Ok(None), Some(endo)) => endo.check_related(point, &key, puzzle),
+                (result, _) => result,
+            }
+        };
+        // The secondary target set is consulted once the puzzle and any
+        // endomorphism candidates have all missed.
+        let check_targets = |point: &bitcoin::secp256k1::PublicKey,
+                             result: Result<Option<CheckResult>>| {
+            match (result, &state.target_set) {
+                (Ok(None), Some(set)) => Ok(set.check(point, &key)),
+                (result, _) => result,
+            }
+        };
+        // The vanity prefixes come last of all; they render full address
+        // strings, the most expensive comparison of the chain.
+        let check_vanity = |point: &bitcoin::secp256k1::PublicKey,
+                            result: Result<Option<CheckResult>>| {
+            match (result, &state.vanity) {
+                (Ok(None), Some(set)) => Ok(set.check(point, &key)),
+                (result, _) => result,
+            }
+        };
+        let checked_result = if let Some(walk_stride) = walker_stride {
+            let prepared = match walker.take() {
+                Some(mut w) => w.advance(&key).map(|()| w),
+                None => checker::IncrementalWalker::new(&key, walk_stride),
+            };
+            prepared.and_then(|w| {
+                let point = w.public_key();
+                let result = checker::check_public_key_against_puzzle(&point, &key, puzzle);
+                let result = check_vanity(&point, check_targets(&point, check_related(&point, result)));
+                walker = Some(w);
+                result
+            })
+        } else if derive_point {
+            let point = checker::public_key_for(&key);
+            let result = checker::check_public_key_against_puzzle(&point, &key, puzzle);
+            check_vanity(&point, check_targets(&point, check_related(&point, result)))
+        } else {
+            checker::check_private_key_against_puzzle(&key, puzzle)
+        };
+        let result = match checked_result {
+            Ok(result) => result,
+            Err(err) => {
+                state.metrics.record_error(ErrorKind::Derivation);
+                return Err(err);
+            }
+        };
+        // The copy on this stack frame is no longer needed; a match keeps
+        // the key only inside its CheckResult, which zeroizes on drop.
+        key.non_secure_erase();
+        check_elapsed += started.elapsed();
+        if let Some(result) = result {
+            // Puzzle number 0 marks a secondary target-set hit.
+            tracing::info!(
+                "thread {thread_id}: MATCH on puzzle #{} (key {})",
+                result.puzzle_number,
+                checker::redact_secret(result.reveal_private_key())
+            );
+            state.stats.record_match();
+            state.metrics.matches.inc();
+            found.push(result);
+        }
+        checked += keys_per_iteration;
+        if checked - reported >= 1000 {
+            let batch = checked - reported;
+            reported = checked;
+            state.stats.record_checked(batch);
+            state.stats.record_thread_checked(thread_id, batch);
+            state.stats.record_puzzle_checked(puzzle.number, batch);
+            state
+                .metrics
+                .keys_checked
+                .with_label_values(&[&thread_id.to_string()])
+                .inc_by(batch);
+            state
+                .metrics
+                .puzzle_keys_checked
+                .with_label_values(&[&puzzle.number.to_string()])
+                .inc_by(batch);
+            state
+                .metrics
+                .batch_keygen_seconds
+                .observe(keygen_elapsed.as_secs_f64());
+            state
+                .metrics
+                .batch_check_seconds
+                .observe(check_elapsed.as_secs_f64());
+            keygen_elapsed = Duration::ZERO;
+            check_elapsed = Duration::ZERO;
+        }
+    }
+    state.stats.record_checked(checked - reported);
+    state.stats.record_thread_checked(thread_id, checked - reported);
+    state.stats.record_puzzle_checked(puzzle.number, checked - reported);
+    state
+        .metrics
+        .keys_checked
+        .with_label_values(&[&thread_id.to_string()])
+        .inc_by(checked - reported);
+    state
+        .metrics
+        .puzzle_keys_checked
+        .with_label_values(&[&puzzle.number.to_string()])
+        .inc_by(checked - reported);
+    tracing::debug!(keys_checked = checked, "worker finished");
+    Ok(found)
+}
+
+/// The celebration message for a found solution.
+pub fn solve_message(result: &CheckResult) -> String {
+    let wif = |compressed| {
+        result
+            .wif(compressed)
+            .map(|wif| wif.to_string())
+            .unwrap_or_else(|| "?".to_string())
+    };
+    // Puzzle number 0 is the target-set sentinel: a funded address from
+    // `TARGET_SET_FILE`, not an entry in the puzzle list.
+    let headline = if result.vanity {
+        "✨ VANITY MATCH!".to_string()
+    } else if result.puzzle_number == 0 {
+        "🎯 TARGET LIST HIT!".to_string()
+    } else {
+        format!("🎉 PUZZLE #{} SOLVED!", result.puzzle_number)
+    };
+    // The signed claim lets the finder prove ownership in public without
+    // ever pasting the key.
+    let proof = result
+        .proof_signature()
+        .map(|sig| format!("\nProof: \"{}\"\nProof signature: {sig}", result.proof_message()))
+        .unwrap_or_default();
+    format!(
+        "{}\nAddress: {}\nPrivate key (hex): {}\nPublic key: {}\nWIF (compressed): {}\nWIF (uncompressed): {}\nKey type: {}\nNetwork: {}\nFound at: {}{}\nSecure this key immediately.",
+        headline,
+        result.address,
+        result.reveal_private_key(),
+        result.public_key_hex,
+        wif(true),
+        wif(false),
+        result.address_type,
+        result.network,
+        result.found_at.to_rfc3339(),
+        proof
+    )
+}
+
+/// Persist and announce a found solution.
+///
+/// The match is journaled before any delivery attempt so a crash or network
+/// outage between "found" and "notified" can never lose the key; the journal
+/// entry is acknowledged only after Telegram accepts the message. Also the
+/// landing point for matches reported by remote cluster workers.
+pub async fn handle_match(state: &AppState, notifier: &Fanout, result: &CheckResult) {
+    tracing::info!(
+        "solution found for puzzle #{} ({})",
+        result.puzzle_number,
+        result.address_type
+    );
+    // Someone may have beaten us to it: an already-empty address means the
+    // puzzle was solved elsewhere, and a "secure this key" celebration
+    // would be misleading noise. A vanity hit is a freshly minted address,
+    // so an empty balance is expected rather than evidence of a race.
+    if let (false, Some(chain)) = (result.vanity, &state.chain) {
+        match chain.address_info(&result.address).await {
+            Ok(info) if info.balance_sat() == 0 => {
+                tracing::warn!(
+                    "stale puzzle detected: #{} key found but {} is already empty",
+                    result.puzzle_number,
+                    result.address
+                );
+                state.mark_puzzle_solved(result.puzzle_number);
+                if let Err(err) = state.solutions.append(result) {
+                    state.metrics.record_error(ErrorKind::Persistence);
+                    tracing::error!("failed to persist solution: {err:#}");
+                }
+                notifier
+                    .dispatch(&Event::Alert(format!(
+                        "Puzzle #{} was already solved by someone else ({} is empty). \
+                         Marked solved; the key is in the solution store.",
+                        result.puzzle_number, result.address
+                    )))
+                    .await;
+                return;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                tracing::warn!("pre-notification balance check failed; proceeding: {err:#}")
+            }
+        }
+    }
+    let journal_id = match state.journal.record(result) {
+        Ok(id) => Some(id),
+        Err(err) => {
+            state.metrics.record_error(ErrorKind::Persistence);
+            tracing::error!("failed to journal match: {err:#}");
+            None
+        }
+    };
+    if let Err(err) = state.solutions.append(result) {
+        state.metrics.record_error(ErrorKind::Persistence);
+        tracing::error!("failed to persist solution: {err:#}");
+    }
+    // Sweep before announcing: once the key reaches a chat it is racing
+    // every bot watching for exactly this kind of message.
+    if state.config.sweep_address.is_some() {
+        match crate::sweep::run(state, result).await {
+            Ok(outcome) => {
+                tracing::info!("{outcome}");
+                notifier.dispatch(&Event::Alert(format!("🧹 {outcome}"))).await;
+            }
+            Err(err) => {
+                tracing::error!("sweep failed: {err:#}");
+                notifier
+                    .dispatch(&Event::Alert(format!("⚠️ Sweep failed: {err:#}")))
+                    .await;
+            }
+        }
+    }
+    let results = notifier.dispatch(&Event::Solve(result.clone())).await;
+    let telegram_delivered = results
+        .iter()
+        .any(|(name, outcome)| *name == "telegram" && outcome.is_ok());
+    if telegram_delivered {
+        if let Some(id) = journal_id {
+            if let Err(err) = state.journal.mark_delivered(id) {
+                state.metrics.record_error(ErrorKind::Persistence);
+                tracing::warn!("failed to acknowledge journal entry {id}: {err:#}");
+            }
+        }
+    }
+}
+
+/// Append one row to the stats CSV, writing the header on first use.
+fn append_stats_csv(state: &AppState, path: &std::path::Path, rate: u64) -> Result<()> {
+    crate::rotation::rotate_if_needed(path, &crate::rotation::RotationPolicy::from_env())?;
+    if !path.exists() {
+        crate::fsutil::append_line_durable(
+            path,
+            "timestamp,keys_checked,rate_keys_per_sec,matches_found,active_puzzle",
+        )?;
+    }
+    let row = format!(
+        "{},{},{},{},{}",
+        chrono::Utc::now().to_rfc3339(),
+        state.stats.total_checked(),
+        rate,
+        state.stats.total_matches(),
+        state
+            .active_puzzle()
+            .map(|n| n.to_string())
+            .unwrap_or_default(),
+    );
+    crate::fsutil::append_line_durable(path, &row)
+}
+
+/// Refresh the per-puzzle gauge set for every eligible puzzle.
+///
+/// Coverage treats every checked key as unique, which overstates progress for
+/// random search but still shows where effort is concentrated; the ETA is the
+/// expected time to cover the whole range at the current rate.
+fn update_puzzle_gauges(state: &AppState, rate: u64) {
+    use num_traits::ToPrimitive;
+
+    let scheduler = &state.config.scheduler;
+    for puzzle in state.puzzles().eligible(scheduler.min_bits, scheduler.max_bits) {
+        let label = puzzle.number.to_string();
+        state
+            .metrics
+            .puzzle_reward_btc
+            .with_label_values(&[&label])
+            .set(puzzle.reward_btc);
+        let checked = state
+            .metrics
+            .puzzle_keys_checked
+            .with_label_values(&[&label])
+            .get();
+        let Some(size) = puzzle.range_size().ok().and_then(|s| s.to_f64()) else {
+            continue;
+        };
+        state
+            .metrics
+            .puzzle_coverage_percent
+            .with_label_values(&[&label])
+            .set(checked as f64 / size * 100.0);
+        if rate > 0 {
+            state
+                .metrics
+                .puzzle_eta_seconds
+                .with_label_values(&[&label])
+                .set(size / rate as f64);
+        }
+    }
+}
+
+/// Send the periodic stats report.
+async fn report_stats(state: &AppState, notifier: &Fanout, rate: u64) {
+    let mut text = state.stats_text();
+    if let Some(price) = &state.price {
+        match price.btc_price().await {
+            Ok(value) => text.push_str(&format!("\nBTC price: {value:.2} {}", price.fiat())),
+            Err(err) => tracing::debug!("price lookup for stats report failed: {err:#}"),
+        }
+    }
+    tracing::info!("stats: {}", text.replace('\n', ", "));
+    notifier
+        .dispatch(&Event::Report {
+            text,
+            keys_checked: state.stats.total_checked(),
+            rate,
+            matches_found: state.stats.total_matches(),
+            sessions_run: state.stats.total_sessions(),
+            uptime_secs: state.uptime_secs(),
+        })
+        .await;
+}
@@ -0,0 +1,167 @@
+//! Dead-zone exclusion: key sub-ranges already searched elsewhere.
+//!
+//! Community spreadsheets and past runs publish which slices of a puzzle
+//! range were swept without a hit. Importing them lets every search mode
+//! skip keys that are already known misses. `SEARCHED_RANGES_FILE` points
+//! at either a JSON array of `{"start": "…", "end": "…"}` hex pairs or a
+//! CSV with one `start,end` pair per line. Intervals are merged into a
+//! sorted list at load time, so a membership test is one binary search.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+#[derive(Deserialize)]
+struct RawRange {
+    start: String,
+    end: String,
+}
+
+fn parse_hex(value: &str) -> Result<BigUint> {
+    BigUint::from_str_radix(value.trim().trim_start_matches("0x"), 16)
+        .with_context(|| format!("bad hex bound {value:?}"))
+}
+
+/// Merged, sorted set of already-searched key intervals (inclusive).
+#[derive(Debug, Default)]
+pub struct SearchedRanges {
+    ranges: Vec<(BigUint, BigUint)>,
+}
+
+impl SearchedRanges {
+    /// Load the configured dead-zone file; unset config means no dead
+    /// zones. A file that fails to parse is ignored with a warning — a
+    /// stale import list must not keep the bot from searching at all.
+    pub fn from_config(config: &Config) -> Self {
+        let Some(path) = &config.searched_ranges_file else {
+            return Self::default();
+        };
+        match Self::load(path) {
+            Ok(ranges) => {
+                tracing::info!(
+                    "loaded {} searched range(s) from {} ({} keys excluded)",
+                    ranges.ranges.len(),
+                    path.display(),
+                    ranges.covered_keys(),
+                );
+                ranges
+            }
+            Err(err) => {
+                tracing::warn!("ignoring {}: {err:#}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse a JSON or CSV range list (decided by content, not extension,
+    /// so piped temp files work).
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("reading searched ranges {}", path.display()))?;
+        let pairs = if data.trim_start().starts_with('[') {
+            let raw: Vec<RawRange> =
+                serde_json::from_str(&data).context("parsing searched ranges JSON")?;
+            raw.iter()
+                .map(|r| Ok((parse_hex(&r.start)?, parse_hex(&r.end)?)))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            data.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(|line| {
+                    let (start, end) = line
+                        .split_once(',')
+                        .with_context(|| format!("CSV line {line:?} has no comma"))?;
+                    Ok((parse_hex(start)?, parse_hex(end)?))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+        Self::from_pairs(pairs)
+    }
+
+    /// Build the merged interval list from arbitrary inclusive pairs.
+    pub fn from_pairs(mut pairs: Vec<(BigUint, BigUint)>) -> Result<Self> {
+        for (start, end) in &pairs {
+            if start > end {
+                bail!("inverted range {start:x}..{end:x}");
+            }
+        }
+        pairs.sort();
+        let mut ranges: Vec<(BigUint, BigUint)> = Vec::with_capacity(pairs.len());
+        for (start, end) in pairs {
+            match ranges.last_mut() {
+                // Overlapping or adjacent intervals collapse into one.
+                Some((_, last_end)) if start <= &*last_end + 1u32 => {
+                    if end > *last_end {
+                        *last_end = end;
+                    }
+                }
+                _ => ranges.push((start, end)),
+            }
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Whether a candidate key falls inside any searched interval.
+    pub fn contains(&self, value: &BigUint) -> bool {
+        let idx = self.ranges.partition_point(|(start, _)| start <= value);
+        idx > 0 && self.ranges[idx - 1].1 >= *value
+    }
+
+    /// Total number of keys the intervals cover, for the load log line.
+    pub fn covered_keys(&self) -> BigUint {
+        self.ranges
+            .iter()
+            .map(|(start, end)| end - start + 1u32)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_overlaps_and_answers_membership() {
+        let ranges = SearchedRanges::from_pairs(vec![
+            (BigUint::from(0x100u32), BigUint::from(0x1ffu32)),
+            (BigUint::from(0x180u32), BigUint::from(0x2ffu32)),
+            (BigUint::from(0x500u32), BigUint::from(0x5ffu32)),
+        ])
+        .unwrap();
+        assert!(ranges.contains(&BigUint::from(0x100u32)));
+        assert!(ranges.contains(&BigUint::from(0x2ffu32)));
+        assert!(!ranges.contains(&BigUint::from(0x300u32)));
+        assert!(ranges.contains(&BigUint::from(0x550u32)));
+        assert!(!ranges.contains(&BigUint::from(0xffu32)));
+        assert_eq!(ranges.covered_keys(), BigUint::from(0x200u32 + 0x100));
+    }
+
+    #[test]
+    fn loads_both_json_and_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("dead.json");
+        fs::write(&json, r#"[{"start": "0x10", "end": "1f"}]"#).unwrap();
+        let loaded = SearchedRanges::load(&json).unwrap();
+        assert!(loaded.contains(&BigUint::from(0x15u32)));
+
+        let csv = dir.path().join("dead.csv");
+        fs::write(&csv, "# exported 2024-05-01\n10,1f\n40,4f\n").unwrap();
+        let loaded = SearchedRanges::load(&csv).unwrap();
+        assert!(loaded.contains(&BigUint::from(0x40u32)));
+        assert!(!loaded.contains(&BigUint::from(0x20u32)));
+
+        fs::write(&csv, "1f,10\n").unwrap();
+        assert!(SearchedRanges::load(&csv).is_err(), "inverted range");
+    }
+}
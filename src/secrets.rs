@@ -0,0 +1,169 @@
+//! Encrypted secrets file, so credentials can leave the plaintext `.env`.
+//!
+//! `SECRETS_FILE` (default `DATA_DIR/secrets.enc`) holds `KEY=VALUE` lines
+//! sealed as one ChaCha20-Poly1305 blob in the same `enc1:` format as the
+//! solution store, unlocked by `SECRETS_PASSPHRASE` or
+//! `SECRETS_PASSPHRASE_FILE`. At startup every stored key that is not
+//! already set in the environment is exported, so the rest of the bot keeps
+//! reading plain env vars and never learns where they came from. The
+//! `secrets` subcommand sets individual values and migrates existing env
+//! credentials into the file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Env vars the `secrets migrate` helper moves out of `.env`.
+pub const MANAGED_KEYS: &[&str] = &[
+    "TELEGRAM_BOT_TOKEN",
+    "SOLUTIONS_PASSPHRASE",
+    "CORE_RPC_PASSWORD",
+    "CLUSTER_TOKEN",
+    "SMTP_PASSWORD",
+    "MQTT_PASSWORD",
+    "INFLUX_TOKEN",
+    "WEBHOOK_SECRET",
+];
+
+/// Where the secrets file lives: `SECRETS_FILE`, or `secrets.enc` under the
+/// data directory.
+pub fn secrets_path() -> PathBuf {
+    match std::env::var("SECRETS_FILE") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => std::env::var("DATA_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| crate::config::default_data_dir())
+            .join("secrets.enc"),
+    }
+}
+
+/// The unlock passphrase from the environment, if configured.
+pub fn passphrase_from_env() -> Result<Option<String>> {
+    match std::env::var("SECRETS_PASSPHRASE") {
+        Ok(p) if !p.is_empty() => Ok(Some(p)),
+        _ => match std::env::var("SECRETS_PASSPHRASE_FILE") {
+            Ok(file) => {
+                let contents = std::fs::read_to_string(&file)
+                    .with_context(|| format!("reading SECRETS_PASSPHRASE_FILE {file}"))?;
+                let trimmed = contents.trim().to_string();
+                if trimmed.is_empty() {
+                    bail!("SECRETS_PASSPHRASE_FILE {file} is empty");
+                }
+                Ok(Some(trimmed))
+            }
+            Err(_) => Ok(None),
+        },
+    }
+}
+
+/// Decrypt and parse the secrets file. A missing file is an empty map.
+pub fn read_all(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let sealed = std::fs::read_to_string(path)
+        .with_context(|| format!("reading secrets file {}", path.display()))?;
+    let plaintext = crate::solutions::open_sealed(passphrase, sealed.trim())
+        .with_context(|| format!("unlocking secrets file {}", path.display()))?;
+    let mut secrets = BTreeMap::new();
+    for line in plaintext.lines().filter(|l| !l.is_empty()) {
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("malformed entry in secrets file {}", path.display());
+        };
+        secrets.insert(key.to_string(), value.to_string());
+    }
+    Ok(secrets)
+}
+
+/// Seal and write the full secrets map, owner read/write only.
+pub fn write_all(path: &Path, passphrase: &str, secrets: &BTreeMap<String, String>) -> Result<()> {
+    let plaintext = secrets
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let sealed = crate::solutions::seal(passphrase, &plaintext)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    std::fs::write(path, sealed)
+        .with_context(|| format!("writing secrets file {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting permissions on {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Export every stored secret that is not already set in the environment.
+/// Quietly does nothing without a secrets file or passphrase; a file that
+/// exists but cannot be unlocked is an error (better loud than headless).
+pub fn load_into_env() -> Result<usize> {
+    let path = secrets_path();
+    if !path.exists() {
+        return Ok(0);
+    }
+    let Some(passphrase) = passphrase_from_env()? else {
+        bail!(
+            "secrets file {} exists but SECRETS_PASSPHRASE is not set",
+            path.display()
+        );
+    };
+    let mut loaded = 0;
+    for (key, value) in read_all(&path, &passphrase)? {
+        if std::env::var_os(&key).is_none() {
+            std::env::set_var(&key, value);
+            loaded += 1;
+        }
+    }
+    Ok(loaded)
+}
+
+/// Move every managed env var that currently has a value into the secrets
+/// file, returning the migrated names. The caller still has to delete them
+/// from `.env` — this process cannot edit the operator's file safely.
+pub fn migrate(path: &Path, passphrase: &str) -> Result<Vec<String>> {
+    let mut secrets = read_all(path, passphrase)?;
+    let mut moved = Vec::new();
+    for key in MANAGED_KEYS {
+        if let Ok(value) = std::env::var(key) {
+            if !value.is_empty() {
+                secrets.insert(key.to_string(), value);
+                moved.push(key.to_string());
+            }
+        }
+    }
+    if !moved.is_empty() {
+        write_all(path, passphrase, &secrets)?;
+    }
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_stays_sealed_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.enc");
+        let mut secrets = BTreeMap::new();
+        secrets.insert("TELEGRAM_BOT_TOKEN".to_string(), "12345:abc".to_string());
+        write_all(&path, "hunter2", &secrets).unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.starts_with("enc1:"));
+        assert!(!raw.contains("12345"));
+        assert_eq!(read_all(&path, "hunter2").unwrap(), secrets);
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.enc");
+        write_all(&path, "hunter2", &BTreeMap::new()).unwrap();
+        assert!(read_all(&path, "hunter3").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! Windows service integration.
+//!
+//! `service install` registers the bot with the service control manager
+//! (auto-start, running `service run` from the current executable),
+//! `service uninstall` removes it, and `service run` is the entry point
+//! the SCM invokes: it reports Running, starts the bot, and treats a Stop
+//! control like Ctrl-C. Configure through the environment or a `.env`
+//! next to the executable, the same as everywhere else; `DATA_DIR`
+//! defaults under `%LOCALAPPDATA%` on Windows.
+
+use std::ffi::OsString;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use windows_service::service::{
+    ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_dispatcher;
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+
+const SERVICE_NAME: &str = "btc_lotto_puzzles_bot";
+
+/// Register the service with the SCM, auto-starting at boot.
+pub fn install() -> Result<()> {
+    let manager = ServiceManager::local_computer(
+        None::<&str>,
+        ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+    )
+    .context("connecting to the service control manager (run elevated)")?;
+    let info = ServiceInfo {
+        name: OsString::from(SERVICE_NAME),
+        display_name: OsString::from("BTC Lotto Puzzles Bot"),
+        service_type: ServiceType::OWN_PROCESS,
+        start_type: ServiceStartType::AutoStart,
+        error_control: ServiceErrorControl::Normal,
+        executable_path: std::env::current_exe().context("resolving the executable path")?,
+        launch_arguments: vec![OsString::from("service"), OsString::from("run")],
+        dependencies: vec![],
+        account_name: None,
+        account_password: None,
+    };
+    manager
+        .create_service(&info, ServiceAccess::QUERY_STATUS)
+        .context("creating the service")?;
+    println!("installed service {SERVICE_NAME}; start it with `sc start {SERVICE_NAME}`");
+    Ok(())
+}
+
+/// Remove the service registration.
+pub fn uninstall() -> Result<()> {
+    let manager =
+        ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+            .context("connecting to the service control manager (run elevated)")?;
+    let service = manager
+        .open_service(SERVICE_NAME, ServiceAccess::DELETE)
+        .context("opening the service")?;
+    service.delete().context("deleting the service")?;
+    println!("uninstalled service {SERVICE_NAME}");
+    Ok(())
+}
+
+/// SCM entry point: hand control to the dispatcher, which calls
+/// `service_main` on its own thread.
+pub fn run() -> Result<()> {
+    service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+        .context("starting the service dispatcher (SCM-launched only)")
+}
+
+windows_service::define_windows_service!(ffi_service_main, service_main);
+
+fn service_main(_arguments: Vec<OsString>) {
+    if let Err(err) = run_service() {
+        tracing::error!("service exited with an error: {err:#}");
+    }
+}
+
+fn run_service() -> Result<()> {
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    let handler = move |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            let _ = stop_tx.send(());
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let status = service_control_handler::register(SERVICE_NAME, handler)
+        .context("registering the control handler")?;
+    let report = |state, wait_hint| {
+        status.set_service_status(ServiceStatus {
+            service_type: ServiceType::OWN_PROCESS,
+            current_state: state,
+            controls_accepted: ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            exit_code: ServiceExitCode::Win32(0),
+            checkpoint: 0,
+            wait_hint,
+            process_id: None,
+        })
+    };
+    report(ServiceState::Running, Duration::default())?;
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("building the tokio runtime")?;
+    let bot = runtime.spawn(crate::run_bot(crate::cli::Cli {
+        dry_run: false,
+        daemon: false,
+        command: None,
+    }));
+    // Block this SCM thread until a stop control arrives, then shut the
+    // runtime down the same way Ctrl-C would.
+    let _ = stop_rx.recv();
+    report(ServiceState::StopPending, Duration::from_secs(10))?;
+    bot.abort();
+    runtime.shutdown_timeout(Duration::from_secs(10));
+    report(ServiceState::Stopped, Duration::default())?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Signal notifications through a signal-cli daemon.
+//!
+//! Talks JSON-RPC to `signal-cli daemon --http` (typically
+//! `http://localhost:8080/api/v1/rpc`), for users who won't keep Telegram
+//! around but still want solve alerts. Configured by `SIGNAL_RPC_URL` and
+//! `SIGNAL_RECIPIENT` (a phone number or group id); `SIGNAL_ACCOUNT` selects
+//! the sending account when the daemon serves several.
+
+use anyhow::{bail, Context, Result};
+use serde_json::json;
+
+use crate::notify::{Event, Notifier};
+
+/// A signal-cli JSON-RPC endpoint and the recipient of our messages.
+pub struct SignalNotifier {
+    client: reqwest::Client,
+    rpc_url: String,
+    recipient: String,
+    account: Option<String>,
+}
+
+impl SignalNotifier {
+    /// Build the notifier from the environment; `None` when `SIGNAL_RPC_URL`
+    /// or `SIGNAL_RECIPIENT` is unset.
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            client: reqwest::Client::new(),
+            rpc_url: std::env::var("SIGNAL_RPC_URL").ok()?,
+            recipient: std::env::var("SIGNAL_RECIPIENT").ok()?,
+            account: std::env::var("SIGNAL_ACCOUNT").ok(),
+        })
+    }
+
+    /// Issue one `send` JSON-RPC call.
+    async fn send_text(&self, text: &str) -> Result<()> {
+        let mut params = json!({
+            "recipient": [self.recipient],
+            "message": text,
+        });
+        if let Some(account) = &self.account {
+            params["account"] = json!(account);
+        }
+        let response: serde_json::Value = self
+            .client
+            .post(&self.rpc_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "send",
+                "params": params,
+            }))
+            .send()
+            .await
+            .context("signal-cli request failed")?
+            .json()
+            .await
+            .context("signal-cli response was not JSON")?;
+        if let Some(error) = response.get("error") {
+            bail!("signal-cli rejected the send: {error}");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Notifier for SignalNotifier {
+    fn name(&self) -> &'static str {
+        "signal"
+    }
+
+    fn retry_attempts(&self) -> u32 {
+        2
+    }
+
+    /// Like email, Signal only carries critical events.
+    async fn send(&self, event: &Event) -> Result<()> {
+        match event {
+            Event::Solve(result) => self.send_text(&crate::scheduler::solve_message(result)).await,
+            Event::Alert(text) => self.send_text(text).await,
+            _ => Ok(()),
+        }
+    }
+}
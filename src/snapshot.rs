@@ -0,0 +1,188 @@
+//! Export/import of the full solver state as a single archive.
+//!
+//! A long-running search accumulates state that is painful to lose when
+//! moving to new hardware: lifetime statistics and the persisted files
+//! (today the solutions store; more artifacts attach here as they grow).
+//! A snapshot is one self-contained JSON document with embedded file
+//! contents, so it can be scp'd to another machine and restored with
+//! `SNAPSHOT_IMPORT=<path>` before the first session runs.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppState;
+
+/// Bumped whenever the snapshot layout changes incompatibly.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Lifetime counters carried across machines.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub keys_checked: u64,
+    pub matches_found: u64,
+    pub sessions_run: u64,
+}
+
+/// One persisted file embedded in the archive.
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotFile {
+    /// Logical name (the configured role), not the absolute path — the
+    /// importing machine may use different paths.
+    role: String,
+    contents_base64: String,
+}
+
+/// The archive itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub stats: StatsSnapshot,
+    pub focused_puzzle: Option<u32>,
+    /// Settings recorded for the operator's reference; they are not applied
+    /// on import (the target machine keeps its own configuration).
+    pub settings: std::collections::BTreeMap<String, String>,
+    files: Vec<SnapshotFile>,
+}
+
+/// Capture the current state into a snapshot file at `path`.
+pub fn export(state: &AppState, path: &Path) -> Result<Snapshot> {
+    let scheduler = &state.config.scheduler;
+    let mut settings = std::collections::BTreeMap::new();
+    settings.insert("threads".into(), scheduler.threads.to_string());
+    settings.insert("min_bits".into(), scheduler.min_bits.to_string());
+    settings.insert("max_bits".into(), scheduler.max_bits.to_string());
+    settings.insert(
+        "session_interval_secs".into(),
+        scheduler.session_interval_secs.to_string(),
+    );
+    settings.insert(
+        "session_duration_secs".into(),
+        scheduler.session_duration_secs.to_string(),
+    );
+
+    let mut files = Vec::new();
+    if state.config.solutions_file.exists() {
+        let contents = std::fs::read(&state.config.solutions_file)
+            .context("reading solutions file for snapshot")?;
+        files.push(SnapshotFile {
+            role: "solutions".into(),
+            contents_base64: BASE64.encode(contents),
+        });
+    }
+
+    let snapshot = Snapshot {
+        version: SNAPSHOT_VERSION,
+        created_at: chrono::Utc::now(),
+        stats: StatsSnapshot {
+            keys_checked: state.stats.total_checked(),
+            matches_found: state.stats.total_matches(),
+            sessions_run: state.stats.total_sessions(),
+        },
+        focused_puzzle: state.focused_puzzle(),
+        settings,
+        files,
+    };
+    let json = serde_json::to_string_pretty(&snapshot)?;
+    crate::fsutil::atomic_write(path, json.as_bytes())
+        .with_context(|| format!("writing snapshot to {}", path.display()))?;
+    tracing::info!("exported state snapshot to {}", path.display());
+    Ok(snapshot)
+}
+
+/// Read a snapshot file and restore the embedded files to their configured
+/// locations. Returns the snapshot so the caller can seed in-memory state.
+pub fn import(path: &Path, config: &crate::config::Config) -> Result<Snapshot> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("reading snapshot {}", path.display()))?;
+    let snapshot: Snapshot = serde_json::from_str(&data).context("parsing snapshot JSON")?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        bail!(
+            "snapshot version {} not supported (expected {})",
+            snapshot.version,
+            SNAPSHOT_VERSION
+        );
+    }
+    for file in &snapshot.files {
+        let target = match file.role.as_str() {
+            "solutions" => &config.solutions_file,
+            other => {
+                tracing::warn!("snapshot contains unknown file role '{other}'; skipping");
+                continue;
+            }
+        };
+        let contents = BASE64
+            .decode(&file.contents_base64)
+            .with_context(|| format!("decoding snapshot file '{}'", file.role))?;
+        crate::backup::backup_file(target, crate::backup::keep_from_env())
+            .with_context(|| format!("backing up {} before restore", target.display()))?;
+        crate::fsutil::atomic_write(target, &contents)
+            .with_context(|| format!("restoring '{}' to {}", file.role, target.display()))?;
+        tracing::info!("restored '{}' to {}", file.role, target.display());
+    }
+    tracing::info!(
+        "imported snapshot from {} (created {}, {} keys checked)",
+        path.display(),
+        snapshot.created_at,
+        snapshot.stats.keys_checked
+    );
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::puzzles::PuzzleCollection;
+    use crate::solutions::SolutionStore;
+
+    fn test_state(dir: &Path) -> AppState {
+        let mut config = Config::from_env();
+        config.solutions_file = dir.join("solutions.log");
+        config.puzzle_file = dir.join("puzzles.json");
+        std::fs::write(&config.puzzle_file, "[]").unwrap();
+        let puzzles = PuzzleCollection::load(&config.puzzle_file).unwrap();
+        let solutions = SolutionStore::open_from_env(&config.solutions_file).unwrap();
+        AppState::new(config, puzzles, solutions)
+    }
+
+    #[test]
+    fn export_import_round_trips_stats_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path());
+        state.stats.record_checked(12345);
+        state.stats.record_session();
+        std::fs::write(&state.config.solutions_file, "entry-one\n").unwrap();
+
+        let snapshot_path = dir.path().join("snap.json");
+        export(&state, &snapshot_path).unwrap();
+
+        // "Move" to a second machine: new data dir, same snapshot.
+        let dir2 = tempfile::tempdir().unwrap();
+        let state2 = test_state(dir2.path());
+        let restored = import(&snapshot_path, &state2.config).unwrap();
+        assert_eq!(restored.stats.keys_checked, 12345);
+        assert_eq!(restored.stats.sessions_run, 1);
+        assert_eq!(
+            std::fs::read_to_string(&state2.config.solutions_file).unwrap(),
+            "entry-one\n"
+        );
+    }
+
+    #[test]
+    fn import_rejects_unknown_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snap.json");
+        std::fs::write(
+            &path,
+            r#"{"version":99,"created_at":"2024-01-01T00:00:00Z","stats":{"keys_checked":0,"matches_found":0,"sessions_run":0},"focused_puzzle":null,"settings":{},"files":[]}"#,
+        )
+        .unwrap();
+        let config = Config::from_env();
+        assert!(import(&path, &config).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Persistence of found solutions, optionally encrypted at rest.
+//!
+//! A found private key is the whole point of this bot, and leaving it in a
+//! plaintext log on disk is asking for trouble. When a passphrase is
+//! configured (`SOLUTIONS_PASSPHRASE`, or `SOLUTIONS_PASSPHRASE_FILE`
+//! pointing at a key file) every appended entry is sealed with
+//! ChaCha20-Poly1305 under a key derived from the passphrase with Argon2id.
+//! Without a passphrase the store falls back to the historical plaintext
+//! format, with a loud warning at startup.
+//!
+//! Each entry is one line, independently encrypted, so the file stays
+//! append-only and a torn write can at worst lose the last line:
+//!
+//! ```text
+//! enc1:<base64 salt>:<base64 nonce>:<base64 ciphertext>
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::checker::CheckResult;
+
+const ENTRY_PREFIX: &str = "enc1";
+const SALT_LEN: usize = 16;
+
+/// How entries are protected on disk.
+enum Cipher {
+    /// Historical behavior: plaintext lines.
+    Plaintext,
+    /// Each line sealed under a key derived from this passphrase.
+    Passphrase(String),
+}
+
+/// Append-only store for found solutions.
+pub struct SolutionStore {
+    path: PathBuf,
+    cipher: Cipher,
+}
+
+impl SolutionStore {
+    /// Open the store at `path` with the passphrase from the environment.
+    ///
+    /// `SOLUTIONS_PASSPHRASE` takes precedence; `SOLUTIONS_PASSPHRASE_FILE`
+    /// names a file whose (trimmed) contents are the passphrase.
+    pub fn open_from_env(path: &Path) -> Result<Self> {
+        let passphrase = match std::env::var("SOLUTIONS_PASSPHRASE") {
+            Ok(p) if !p.is_empty() => Some(p),
+            _ => match std::env::var("SOLUTIONS_PASSPHRASE_FILE") {
+                Ok(file) => {
+                    let contents = std::fs::read_to_string(&file)
+                        .with_context(|| format!("reading SOLUTIONS_PASSPHRASE_FILE {file}"))?;
+                    let trimmed = contents.trim().to_string();
+                    if trimmed.is_empty() {
+                        bail!("SOLUTIONS_PASSPHRASE_FILE {file} is empty");
+                    }
+                    Some(trimmed)
+                }
+                Err(_) => None,
+            },
+        };
+        let cipher = match passphrase {
+            Some(p) => Cipher::Passphrase(p),
+            None => {
+                tracing::warn!(
+                    "no SOLUTIONS_PASSPHRASE configured; {} will store found keys in PLAINTEXT",
+                    path.display()
+                );
+                Cipher::Plaintext
+            }
+        };
+        let store = Self {
+            path: path.to_path_buf(),
+            cipher,
+        };
+        store.check_permissions()?;
+        Ok(store)
+    }
+
+    /// Refuse to run with a world-readable solutions file: it may eventually
+    /// hold a valuable private key.
+    fn check_permissions(&self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(metadata) = std::fs::metadata(&self.path) {
+                let mode = metadata.permissions().mode();
+                if mode & 0o044 != 0 {
+                    bail!(
+                        "solutions file {} is group/world-readable (mode {:o}); \
+                         run `chmod 600 {}` and restart",
+                        self.path.display(),
+                        mode & 0o777,
+                        self.path.display()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Clamp the store file to owner read/write only.
+    fn restrict_permissions(&self) -> Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("restricting permissions on {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Append one solution to the store.
+    pub fn append(&self, result: &CheckResult) -> Result<()> {
+        // The plaintext line is wiped as soon as it has been sealed (or, in
+        // the plaintext fallback, written out).
+        let wif = |compressed| {
+            result
+                .wif(compressed)
+                .map(|wif| wif.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        };
+        let line = zeroize::Zeroizing::new(format!(
+            "{} puzzle=#{} address={} private_key={} public_key={} \
+             wif_compressed={} wif_uncompressed={} type={} network={} \
+             proof={:?} proof_signature={}",
+            result.found_at.to_rfc3339(),
+            result.puzzle_number,
+            result.address,
+            result.reveal_private_key(),
+            result.public_key_hex,
+            wif(true),
+            wif(false),
+            result.address_type,
+            result.network,
+            result.proof_message(),
+            result.proof_signature().unwrap_or_else(|| "?".to_string())
+        ));
+        let stored = match &self.cipher {
+            Cipher::Plaintext => line,
+            Cipher::Passphrase(passphrase) => zeroize::Zeroizing::new(seal(passphrase, &line)?),
+        };
+        if let Err(err) =
+            crate::rotation::rotate_if_needed(&self.path, &crate::rotation::RotationPolicy::from_env())
+        {
+            tracing::warn!("solutions store rotation failed: {err:#}");
+        }
+        if let Err(err) = crate::backup::backup_file(&self.path, crate::backup::keep_from_env()) {
+            tracing::warn!("solutions store backup failed: {err:#}");
+        }
+        crate::fsutil::append_line_durable(&self.path, &stored)
+            .with_context(|| format!("appending to solutions store {}", self.path.display()))?;
+        self.restrict_permissions()
+    }
+
+    /// Read back every entry, decrypting where necessary.
+    ///
+    /// Plaintext and encrypted lines may be mixed (a store can predate the
+    /// passphrase); both are handled.
+    pub fn read_all(&self) -> Result<Vec<String>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let data = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("reading solutions store {}", self.path.display()))?;
+        let mut entries = Vec::new();
+        for line in data.lines().filter(|l| !l.is_empty()) {
+            if line.starts_with(ENTRY_PREFIX) {
+                let Cipher::Passphrase(passphrase) = &self.cipher else {
+                    bail!(
+                        "{} contains encrypted entries but no passphrase is configured",
+                        self.path.display()
+                    );
+                };
+                entries.push(open_sealed(passphrase, line)?);
+            } else {
+                entries.push(line.to_string());
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Derive a cipher key from `passphrase` and `salt` with Argon2id.
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("argon2 key derivation failed: {e}"))?;
+    Ok(key)
+}
+
+/// Encrypt one entry line. Also used by the encrypted secrets file, which
+/// shares the `enc1:` format.
+pub(crate) fn seal(passphrase: &str, plaintext: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from(nonce_bytes);
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(&key);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|e| anyhow::anyhow!("encrypting solution entry failed: {e}"))?;
+    Ok(format!(
+        "{ENTRY_PREFIX}:{}:{}:{}",
+        BASE64.encode(salt),
+        BASE64.encode(nonce),
+        BASE64.encode(ciphertext)
+    ))
+}
+
+/// Decrypt one `enc1:` line.
+pub(crate) fn open_sealed(passphrase: &str, line: &str) -> Result<String> {
+    let parts: Vec<&str> = line.split(':').collect();
+    if parts.len() != 4 || parts[0] != ENTRY_PREFIX {
+        bail!("malformed encrypted solution entry");
+    }
+    let salt = BASE64.decode(parts[1]).context("bad salt encoding")?;
+    let nonce_bytes = BASE64.decode(parts[2]).context("bad nonce encoding")?;
+    let ciphertext = BASE64.decode(parts[3]).context("bad ciphertext encoding")?;
+    let nonce: [u8; 12] = nonce_bytes
+        .as_slice()
+        .try_into()
+        .context("bad nonce length")?;
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = ChaCha20Poly1305::new(&key);
+    let plaintext = cipher
+        .decrypt(&Nonce::from(nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("decryption failed: wrong passphrase or corrupt entry"))?;
+    String::from_utf8(plaintext).context("decrypted entry is not UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::AddressType;
+
+    fn sample_result() -> CheckResult {
+        CheckResult::new(9, "1Addr".into(), "01d3".into(), AddressType::Compressed)
+    }
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let sealed = seal("hunter2", "secret line").unwrap();
+        assert!(sealed.starts_with("enc1:"));
+        assert!(!sealed.contains("secret"));
+        assert_eq!(open_sealed("hunter2", &sealed).unwrap(), "secret line");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let sealed = seal("hunter2", "secret line").unwrap();
+        assert!(open_sealed("hunter3", &sealed).is_err());
+    }
+
+    #[test]
+    fn encrypted_store_round_trips_and_hides_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solutions.log");
+        let store = SolutionStore {
+            path: path.clone(),
+            cipher: Cipher::Passphrase("hunter2".into()),
+        };
+        store.append(&sample_result()).unwrap();
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("01d3"), "private key must not appear on disk");
+        let entries = store.read_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].contains("private_key=01d3"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_readable_store_is_refused() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solutions.log");
+        std::fs::write(&path, "entry\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(SolutionStore::open_from_env(&path).is_err());
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(SolutionStore::open_from_env(&path).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn append_clamps_mode_to_0600() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solutions.log");
+        let store = SolutionStore {
+            path: path.clone(),
+            cipher: Cipher::Plaintext,
+        };
+        store.append(&sample_result()).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn plaintext_store_still_works() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("solutions.log");
+        let store = SolutionStore {
+            path,
+            cipher: Cipher::Plaintext,
+        };
+        store.append(&sample_result()).unwrap();
+        let entries = store.read_all().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].contains("puzzle=#9"));
+    }
+}
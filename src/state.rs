@@ -0,0 +1,434 @@
+//! Shared runtime state between the scheduler and the Telegram command loop.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+
+use std::collections::HashMap;
+
+use crate::audit::AuditLog;
+use crate::bloom::KeyFilter;
+use crate::chain::ChainClient;
+use crate::checker::CheckStats;
+use crate::cluster::{Coordinator, NodeStats};
+use crate::config::Config;
+use crate::feed::FeedStore;
+use crate::gossip::CoverageMap;
+use crate::journal::MatchJournal;
+use crate::metrics::Metrics;
+use crate::notify::Fanout;
+use crate::price::PriceClient;
+use crate::progress::ProgressCursor;
+use crate::puzzles::PuzzleCollection;
+use crate::roles::RoleStore;
+use crate::searched::SearchedRanges;
+use crate::solutions::SolutionStore;
+
+/// Everything long-lived tasks need to share, behind one `Arc`.
+pub struct AppState {
+    pub config: Config,
+    /// Behind a lock so the puzzle file can be reloaded at runtime.
+    puzzles: RwLock<PuzzleCollection>,
+    pub stats: CheckStats,
+    pub solutions: SolutionStore,
+    pub journal: MatchJournal,
+    /// Shared with the Telegram client so it can record request metrics.
+    pub metrics: std::sync::Arc<Metrics>,
+    /// In-process event stream (JSON), fed by the notification fanout.
+    pub events: tokio::sync::broadcast::Sender<String>,
+    /// Block explorer client; `None` when no backend is configured.
+    pub chain: Option<ChainClient>,
+    /// BTC price client; `None` when lookups are disabled.
+    pub price: Option<PriceClient>,
+    /// Recent events for the HTTP feed; also registered as a fanout sink.
+    pub feed: std::sync::Arc<FeedStore>,
+    /// Work-unit coordinator; `Some` only with `CLUSTER_COORDINATOR=true`.
+    pub coordinator: Option<Coordinator>,
+    /// The notification fanout, set once main has assembled the sinks.
+    notifier: std::sync::OnceLock<std::sync::Arc<Fanout>>,
+    /// Latest stats pushed by remote instances, keyed by node name.
+    nodes: Mutex<HashMap<String, (NodeStats, Instant)>>,
+    /// Coarse exhausted-bucket record, local plus gossiped from peers.
+    pub coverage: CoverageMap,
+    /// Per-user role assignments for the Telegram dispatcher.
+    pub roles: RoleStore,
+    /// Hash-chained log of control actions.
+    pub audit: AuditLog,
+    /// Imported dead zones; candidate keys inside them are skipped.
+    pub searched: SearchedRanges,
+    /// Duplicate-draw filter over this process's tried keys; `None` when
+    /// `BLOOM_SIZE_MB=0`.
+    pub tried: Option<KeyFilter>,
+    /// Extra targets every derived key is checked against; `Some` only
+    /// with `TARGET_SET_FILE`.
+    pub target_set: Option<crate::checker::TargetSet>,
+    /// Vanity-prefix matcher from `VANITY_PREFIXES`, if configured.
+    pub vanity: Option<crate::checker::VanitySet>,
+    started_at: Instant,
+    running: AtomicBool,
+    shutdown: AtomicBool,
+    focus: Mutex<Option<u32>>,
+    /// Puzzles whose whole range was enumerated without a match (the
+    /// exhaustive strategy); the scheduler skips them.
+    exhausted_puzzles: Mutex<std::collections::HashSet<u32>>,
+    /// Sequential window of the hybrid strategy, seeded from
+    /// `HYBRID_WINDOW` and retunable with the `/window` command.
+    hybrid_window: AtomicU64,
+    last_session: Mutex<Option<DateTime<Utc>>>,
+    /// Puzzle the most recent session ran against.
+    active_puzzle: Mutex<Option<u32>>,
+    /// Sequential-scan cursors, keyed by puzzle number.
+    pub cursors: Mutex<HashMap<u32, ProgressCursor>>,
+    /// Last time the scheduler loop ticked, for liveness probes.
+    last_heartbeat: Mutex<Option<Instant>>,
+    /// Whether the most recent Telegram API call succeeded.
+    telegram_ok: AtomicBool,
+}
+
+impl AppState {
+    pub fn new(config: Config, puzzles: PuzzleCollection, solutions: SolutionStore) -> Self {
+        let journal = MatchJournal::open(&config.data_dir.join("match_journal.log"));
+        let chain = ChainClient::from_config(&config);
+        let price = PriceClient::from_config(&config);
+        let coordinator = Coordinator::from_config(&config);
+        let coverage = CoverageMap::new(config.work_unit_keys);
+        let roles = RoleStore::open(&config.data_dir.join("roles.json"), &config.telegram_admins);
+        let audit = AuditLog::open(&config.data_dir.join("audit.log"));
+        let searched = SearchedRanges::from_config(&config);
+        let tried = KeyFilter::new(config.bloom_size_mb);
+        let target_set = crate::checker::TargetSet::from_config(&config);
+        let vanity = crate::checker::VanitySet::from_config(&config);
+        let hybrid_window = AtomicU64::new(config.scheduler.hybrid_window);
+        Self {
+            config,
+            puzzles: RwLock::new(puzzles),
+            stats: CheckStats::default(),
+            solutions,
+            journal,
+            metrics: std::sync::Arc::new(
+                Metrics::new().expect("metric registration on a fresh registry"),
+            ),
+            events: tokio::sync::broadcast::channel(64).0,
+            chain,
+            price,
+            feed: std::sync::Arc::new(FeedStore::new()),
+            coordinator,
+            notifier: std::sync::OnceLock::new(),
+            nodes: Mutex::new(HashMap::new()),
+            coverage,
+            roles,
+            audit,
+            searched,
+            tried,
+            target_set,
+            vanity,
+            started_at: Instant::now(),
+            running: AtomicBool::new(true),
+            shutdown: AtomicBool::new(false),
+            focus: Mutex::new(None),
+            exhausted_puzzles: Mutex::new(std::collections::HashSet::new()),
+            hybrid_window,
+            last_session: Mutex::new(None),
+            active_puzzle: Mutex::new(None),
+            cursors: Mutex::new(HashMap::new()),
+            last_heartbeat: Mutex::new(None),
+            telegram_ok: AtomicBool::new(false),
+        }
+    }
+
+    /// Make the fanout reachable from HTTP handlers and the like. Called
+    /// once from main; later calls are ignored.
+    pub fn set_notifier(&self, notifier: std::sync::Arc<Fanout>) {
+        let _ = self.notifier.set(notifier);
+    }
+
+    /// The notification fanout, if main has installed it yet.
+    pub fn notifier(&self) -> Option<&std::sync::Arc<Fanout>> {
+        self.notifier.get()
+    }
+
+    /// Store the latest stats push from a remote instance.
+    pub fn record_node_stats(&self, stats: NodeStats) {
+        self.nodes
+            .lock()
+            .unwrap()
+            .insert(stats.node.clone(), (stats, Instant::now()));
+    }
+
+    /// Number of remote instances that have pushed stats.
+    pub fn node_count(&self) -> usize {
+        self.nodes.lock().unwrap().len()
+    }
+
+    /// Read access to the puzzle collection.
+    pub fn puzzles(&self) -> RwLockReadGuard<'_, PuzzleCollection> {
+        self.puzzles.read().unwrap()
+    }
+
+    /// Re-read the puzzle file and swap the collection in place.
+    pub fn reload_puzzles(&self) -> anyhow::Result<String> {
+        let reloaded = PuzzleCollection::load(&self.config.puzzle_file)?;
+        let summary = format!(
+            "Reloaded {} puzzles ({} solved, {} unsolved) from {}.",
+            reloaded.all().len(),
+            reloaded.solved_count(),
+            reloaded.unsolved_count(),
+            self.config.puzzle_file.display(),
+        );
+        *self.puzzles.write().unwrap() = reloaded;
+        Ok(summary)
+    }
+
+    /// Mark a puzzle solved in the live collection, e.g. after detecting
+    /// its address was already swept. Returns whether anything changed.
+    pub fn mark_puzzle_solved(&self, number: u32) -> bool {
+        self.puzzles.write().unwrap().mark_solved(number)
+    }
+
+    /// Record that an exhaustive sweep enumerated the puzzle's whole range
+    /// without a match. Returns whether it was newly recorded.
+    pub fn mark_puzzle_exhausted(&self, number: u32) -> bool {
+        self.exhausted_puzzles.lock().unwrap().insert(number)
+    }
+
+    pub fn is_puzzle_exhausted(&self, number: u32) -> bool {
+        self.exhausted_puzzles.lock().unwrap().contains(&number)
+    }
+
+    /// Keys the hybrid strategy scans sequentially per random anchor.
+    pub fn hybrid_window(&self) -> u64 {
+        self.hybrid_window.load(Ordering::Relaxed)
+    }
+
+    /// Retune the hybrid window; workers pick it up at their next anchor.
+    pub fn set_hybrid_window(&self, window: u64) {
+        self.hybrid_window.store(window.max(1), Ordering::Relaxed);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::Relaxed);
+    }
+
+    pub fn request_shutdown(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    pub fn focused_puzzle(&self) -> Option<u32> {
+        *self.focus.lock().unwrap()
+    }
+
+    /// Set (or clear) the focused puzzle, returning a user-facing reply.
+    pub fn set_focus(&self, number: Option<u32>) -> String {
+        match number {
+            Some(n) if self.puzzles().get(n).is_none() => {
+                format!("Unknown puzzle #{n}; focus unchanged.")
+            }
+            Some(n) => {
+                *self.focus.lock().unwrap() = Some(n);
+                format!("Focusing on puzzle #{n}.")
+            }
+            None => {
+                *self.focus.lock().unwrap() = None;
+                "Focus cleared.".to_string()
+            }
+        }
+    }
+
+    /// Record a scheduler loop tick.
+    pub fn heartbeat(&self) {
+        *self.last_heartbeat.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Seconds since the scheduler loop last ticked, if it has at all.
+    pub fn heartbeat_age_secs(&self) -> Option<u64> {
+        self.last_heartbeat
+            .lock()
+            .unwrap()
+            .map(|t| t.elapsed().as_secs())
+    }
+
+    pub fn set_telegram_ok(&self, ok: bool) {
+        self.telegram_ok.store(ok, Ordering::Relaxed);
+    }
+
+    pub fn telegram_ok(&self) -> bool {
+        self.telegram_ok.load(Ordering::Relaxed)
+    }
+
+    pub fn set_active_puzzle(&self, number: Option<u32>) {
+        *self.active_puzzle.lock().unwrap() = number;
+    }
+
+    pub fn active_puzzle(&self) -> Option<u32> {
+        *self.active_puzzle.lock().unwrap()
+    }
+
+    pub fn mark_session(&self) {
+        *self.last_session.lock().unwrap() = Some(Utc::now());
+        self.stats.record_session();
+        self.metrics.sessions.inc();
+    }
+
+    pub fn last_session_time(&self) -> Option<DateTime<Utc>> {
+        *self.last_session.lock().unwrap()
+    }
+
+    pub fn uptime_secs(&self) -> u64 {
+        self.started_at.elapsed().as_secs()
+    }
+
+    pub fn status_text(&self) -> String {
+        let last = self
+            .last_session_time()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        let mut text = format!(
+            "Solver: {}\nUptime: {}s\nSessions run: {}\nLast session: {}\nFocus: {}\nProgress cursors: {}",
+            if self.is_running() { "running" } else { "stopped" },
+            self.uptime_secs(),
+            self.stats.total_sessions(),
+            last,
+            self.focused_puzzle()
+                .map(|n| format!("puzzle #{n}"))
+                .unwrap_or_else(|| "none".to_string()),
+            self.cursors.lock().unwrap().len(),
+        );
+        #[cfg(any(feature = "gpu", feature = "cuda"))]
+        {
+            let gpu = self.stats.total_gpu_checked();
+            if gpu > 0 {
+                text.push_str(&format!(
+                    "\nGPU keys checked: {gpu} ({} keys/s avg)",
+                    gpu / self.uptime_secs().max(1)
+                ));
+            }
+        }
+        let nodes = self.node_count();
+        if nodes > 0 {
+            text.push_str(&format!("\nNodes reporting: {nodes}"));
+        }
+        text
+    }
+
+    pub fn stats_text(&self) -> String {
+        let checked = self.stats.total_checked();
+        let uptime = self.uptime_secs().max(1);
+        let mut text = format!(
+            "Keys checked: {}\nMatches found: {}\nAverage rate: {} keys/s\nEligible puzzles: {}",
+            checked,
+            self.stats.total_matches(),
+            checked / uptime,
+            self.puzzles()
+                .eligible(self.config.scheduler.min_bits, self.config.scheduler.max_bits)
+                .len(),
+        );
+        let per_thread = self.stats.per_thread_totals();
+        if !per_thread.is_empty() {
+            text.push_str("\nPer thread:");
+            for (thread_id, keys) in per_thread.iter().enumerate() {
+                text.push_str(&format!(
+                    "\n  #{thread_id}: {keys} keys ({} keys/s avg)",
+                    keys / uptime
+                ));
+            }
+        }
+        let per_puzzle = self.stats.per_puzzle_totals();
+        if !per_puzzle.is_empty() {
+            text.push_str("\nPer puzzle:");
+            for (number, stat) in per_puzzle {
+                text.push_str(&format!(
+                    "\n  #{number}: {} keys, last active {}",
+                    stat.keys_checked,
+                    stat.last_checked.format("%Y-%m-%d %H:%M:%S UTC"),
+                ));
+            }
+        }
+        if let Some(filter) = &self.tried {
+            if filter.inserted() > 0 {
+                text.push_str(&format!(
+                    "\nDuplicate draws: {} (~{:.2}% of {})",
+                    filter.duplicates(),
+                    filter.duplicate_rate() * 100.0,
+                    filter.inserted(),
+                ));
+            }
+        }
+        let errors = self.metrics.error_counts();
+        if !errors.is_empty() {
+            text.push_str("\nErrors:");
+            for (kind, count) in errors {
+                text.push_str(&format!("\n  {kind}: {count}"));
+            }
+        }
+        if let Some(coordinator) = &self.coordinator {
+            let workers = coordinator.worker_lines();
+            if !workers.is_empty() {
+                text.push_str("\nWorkers:");
+                for line in workers {
+                    text.push_str(&format!("\n  {line}"));
+                }
+            }
+        }
+        let nodes = self.nodes.lock().unwrap();
+        if !nodes.is_empty() {
+            let mut remote_keys = 0u64;
+            let mut remote_matches = 0u64;
+            let mut names: Vec<_> = nodes.keys().collect();
+            names.sort();
+            text.push_str("\nFleet:");
+            for name in names {
+                let (stats, updated) = &nodes[name];
+                remote_keys += stats.keys_checked;
+                remote_matches += stats.matches_found;
+                text.push_str(&format!(
+                    "\n  {name}: {} keys, {} keys/s ({}s ago)",
+                    stats.keys_checked,
+                    stats.rate,
+                    updated.elapsed().as_secs(),
+                ));
+            }
+            text.push_str(&format!(
+                "\n  combined: {} keys, {} matches",
+                checked + remote_keys,
+                self.stats.total_matches() + remote_matches,
+            ));
+        }
+        text
+    }
+
+    pub fn config_text(&self) -> String {
+        let s = &self.config.scheduler;
+        let mut text = format!(
+            "Threads: {}\nSession: every {}s for {}s\nStats interval: {}s\nBits: {}..={}\nPuzzle file: {}\nData dir: {}",
+            s.threads,
+            s.session_interval_secs,
+            s.session_duration_secs,
+            s.stats_interval_secs,
+            s.min_bits,
+            s.max_bits,
+            self.config.puzzle_file.display(),
+            self.config.data_dir.display(),
+        );
+        if s.stride > 0 {
+            text.push_str(&format!(
+                "\nScan mode: stride {} offset {}",
+                s.stride, s.stride_offset
+            ));
+        }
+        text.push_str(&format!("\nHybrid window: {} keys", self.hybrid_window()));
+        if let Some(seed) = self.config.search_seed {
+            text.push_str(&format!("\nSearch seed: {seed}"));
+        }
+        text
+    }
+}
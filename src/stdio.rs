@@ -0,0 +1,154 @@
+//! JSON-RPC 2.0 over stdin/stdout for embedding.
+//!
+//! With `STDIO_RPC=true` the solver runs as a child process driven by its
+//! parent: one JSON-RPC request per stdin line, one response per stdout
+//! line, and solver events pushed as `event` notifications in between.
+//! Telegram and the HTTP server are disabled in this mode and logs move to
+//! stderr so stdout stays a clean protocol channel:
+//!
+//! ```sh
+//! echo '{"jsonrpc":"2.0","id":1,"method":"status"}' | STDIO_RPC=true ./btc_lotto_puzzles_bot
+//! ```
+//!
+//! Methods: `status`, `start`, `stop`, `reload` (shared with the control
+//! socket), plus `focus` (`{"puzzle": 66}`, omit to clear) and `stats`.
+//! EOF on stdin requests shutdown, so the solver dies with its parent.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::control;
+use crate::state::AppState;
+
+/// Whether stdio embedding mode is enabled.
+pub fn enabled() -> bool {
+    matches!(
+        std::env::var("STDIO_RPC").as_deref(),
+        Ok("true") | Ok("1")
+    )
+}
+
+/// Run one method, producing either a result or a JSON-RPC error object.
+fn dispatch(state: &AppState, method: &str, params: &Value) -> Result<Value, Value> {
+    match method {
+        "status" | "start" | "stop" | "reload" => Ok(control::execute(state, method)),
+        "focus" => {
+            let puzzle = params.get("puzzle").and_then(Value::as_u64);
+            let message = state.set_focus(puzzle.map(|n| n as u32));
+            Ok(json!({ "ok": true, "message": message }))
+        }
+        "stats" => Ok(json!({ "ok": true, "message": state.stats_text() })),
+        other => Err(json!({
+            "code": -32601,
+            "message": format!("method {other:?} not found"),
+        })),
+    }
+}
+
+/// Build the response line for one request line.
+fn respond(state: &AppState, line: &str) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(err) => {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": format!("parse error: {err}") },
+            })
+        }
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": -32600, "message": "missing \"method\" field" },
+        });
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    match dispatch(state, method, &params) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
+
+/// Speak JSON-RPC on stdin/stdout until stdin closes.
+pub async fn serve(state: Arc<AppState>) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut out = tokio::io::stdout();
+    let mut events = state.events.subscribe();
+    tracing::info!("stdio JSON-RPC mode active");
+    loop {
+        let reply = tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) if !line.trim().is_empty() => respond(&state, &line),
+                Some(_) => continue,
+                None => {
+                    tracing::info!("stdin closed; requesting shutdown");
+                    state.request_shutdown();
+                    return Ok(());
+                }
+            },
+            event = events.recv() => match event {
+                // Push events as JSON-RPC notifications (no id).
+                Ok(event) => json!({
+                    "jsonrpc": "2.0",
+                    "method": "event",
+                    "params": serde_json::from_str::<Value>(&event)
+                        .unwrap_or(Value::String(event)),
+                }),
+                // Lagged receivers just miss a few events.
+                Err(_) => continue,
+            },
+        };
+        out.write_all(reply.to_string().as_bytes()).await?;
+        out.write_all(b"\n").await?;
+        out.flush().await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::puzzles::PuzzleCollection;
+    use crate::solutions::SolutionStore;
+
+    fn test_state() -> AppState {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::from_env();
+        config.telegram_token = None;
+        config.solutions_file = dir.path().join("solutions.log");
+        config.puzzle_file = dir.path().join("puzzles.json");
+        config.data_dir = dir.path().to_path_buf();
+        std::fs::write(&config.puzzle_file, "[]").unwrap();
+        let puzzles = PuzzleCollection::load(&config.puzzle_file).unwrap();
+        let solutions = SolutionStore::open_from_env(&config.solutions_file).unwrap();
+        AppState::new(config, puzzles, solutions)
+    }
+
+    #[test]
+    fn answers_status_requests() {
+        let state = test_state();
+        let reply = respond(&state, r#"{"jsonrpc":"2.0","id":7,"method":"status"}"#);
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["result"]["ok"], true);
+    }
+
+    #[test]
+    fn unknown_method_is_a_jsonrpc_error() {
+        let state = test_state();
+        let reply = respond(&state, r#"{"jsonrpc":"2.0","id":1,"method":"explode"}"#);
+        assert_eq!(reply["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn garbage_input_is_a_parse_error() {
+        let state = test_state();
+        let reply = respond(&state, "not json");
+        assert_eq!(reply["error"]["code"], -32700);
+    }
+}
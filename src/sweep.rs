@@ -0,0 +1,210 @@
+//! Automatic sweep of a solved puzzle's balance.
+//!
+//! A private key announced over Telegram is a race against bots watching
+//! the mempool, so on a solve the bot can immediately build a transaction
+//! spending the puzzle address to a pre-configured safe destination
+//! (`SWEEP_ADDRESS`). The signed hex is always written to `DATA_DIR` for
+//! manual broadcast; only with the explicit `SWEEP_BROADCAST=true` opt-in
+//! is it also pushed through the configured chain backend.
+
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use bitcoin::absolute::LockTime;
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+use bitcoin::sighash::SighashCache;
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Address, Amount, EcdsaSighashType, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, Witness,
+};
+
+use crate::chain::Utxo;
+use crate::checker::{AddressType, CheckResult};
+use crate::state::AppState;
+
+/// Outputs below this are unrelayable anyway.
+const DUST_SAT: u64 = 546;
+
+/// Rough size of a one-output sweep, for fee estimation: 10 bytes of
+/// overhead, one 34-byte P2PKH output and ~148/180 bytes per signed input.
+fn estimated_vbytes(inputs: usize, address_type: AddressType) -> u64 {
+    let input = match address_type {
+        AddressType::Compressed => 148,
+        AddressType::Uncompressed => 180,
+        // Witness-discounted inputs: ~68 vbytes signed P2WPKH, ~58 vbytes
+        // for a taproot key-path spend.
+        AddressType::P2wpkh => 68,
+        AddressType::P2tr => 58,
+    };
+    10 + 34 + inputs as u64 * input
+}
+
+/// Build and sign a transaction spending every `utxo` of the solved
+/// address to `destination`, with the fee taken off the single output.
+pub fn build_sweep_tx(
+    result: &CheckResult,
+    utxos: &[Utxo],
+    destination: &str,
+    fee_rate: u64,
+) -> Result<Transaction> {
+    let secret = SecretKey::from_slice(
+        &hex::decode(result.reveal_private_key()).context("solution key is not hex")?,
+    )
+    .context("solution key is not a valid secret key")?;
+    let secp = Secp256k1::new();
+    let inner = secret.public_key(&secp);
+    let public_key = match result.address_type {
+        AddressType::Compressed => PublicKey::new(inner),
+        AddressType::Uncompressed => PublicKey::new_uncompressed(inner),
+        // Spending a witness output needs BIP143/BIP341 sighashes, not the
+        // legacy signing below; the key is in the solution log, sweep by hand.
+        AddressType::P2wpkh | AddressType::P2tr => {
+            bail!("sweeping a segwit solve is not supported yet")
+        }
+    };
+    let source_script = Address::from_str(&result.address)
+        .context("solved address does not parse")?
+        .require_network(crate::checker::network())
+        .with_context(|| format!("solved address is not on the {} network", crate::checker::network()))?
+        .script_pubkey();
+    let destination = Address::from_str(destination)
+        .context("SWEEP_ADDRESS does not parse")?
+        .require_network(crate::checker::network())
+        .with_context(|| format!("SWEEP_ADDRESS is not on the {} network", crate::checker::network()))?;
+
+    let total: u64 = utxos.iter().map(|utxo| utxo.value_sat).sum();
+    let fee = estimated_vbytes(utxos.len(), result.address_type) * fee_rate;
+    if total <= fee + DUST_SAT {
+        bail!("balance {total} sat does not cover the {fee} sat fee plus dust");
+    }
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: utxos
+            .iter()
+            .map(|utxo| {
+                Ok(TxIn {
+                    previous_output: OutPoint {
+                        txid: Txid::from_str(&utxo.txid).context("utxo txid does not parse")?,
+                        vout: utxo.vout,
+                    },
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+            })
+            .collect::<Result<_>>()?,
+        output: vec![TxOut {
+            value: Amount::from_sat(total - fee),
+            script_pubkey: destination.script_pubkey(),
+        }],
+    };
+    // Legacy P2PKH signing, one signature per input over the same key.
+    let cache = SighashCache::new(tx.clone());
+    for (index, input) in tx.input.iter_mut().enumerate() {
+        let sighash = cache
+            .legacy_signature_hash(index, &source_script, EcdsaSighashType::All.to_u32())
+            .context("computing the signature hash")?;
+        let signature = bitcoin::ecdsa::Signature {
+            signature: secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &secret),
+            sighash_type: EcdsaSighashType::All,
+        };
+        input.script_sig = ScriptBuf::builder()
+            .push_slice(signature.serialize())
+            .push_key(&public_key)
+            .into_script();
+    }
+    Ok(tx)
+}
+
+/// Fetch the solved address's UTXOs, build the sweep, persist the hex and
+/// (with `SWEEP_BROADCAST=true`) broadcast it. Returns a human-readable
+/// outcome for the notification channel.
+pub async fn run(state: &AppState, result: &CheckResult) -> Result<String> {
+    let destination = state
+        .config
+        .sweep_address
+        .as_ref()
+        .context("SWEEP_ADDRESS is not configured")?;
+    let chain = state
+        .chain
+        .as_ref()
+        .context("sweeping needs a chain backend; set CHAIN_BACKEND")?;
+    let utxos = chain.utxos(&result.address).await?;
+    if utxos.is_empty() {
+        bail!("no unspent outputs on {}", result.address);
+    }
+    let tx = build_sweep_tx(result, &utxos, destination, state.config.sweep_fee_rate)?;
+    let tx_hex = bitcoin::consensus::encode::serialize_hex(&tx);
+    let path = state
+        .config
+        .data_dir
+        .join(format!("sweep-puzzle-{}.hex", result.puzzle_number));
+    std::fs::create_dir_all(&state.config.data_dir)
+        .with_context(|| format!("creating {}", state.config.data_dir.display()))?;
+    std::fs::write(&path, &tx_hex)
+        .with_context(|| format!("writing sweep transaction to {}", path.display()))?;
+    if state.config.sweep_broadcast {
+        let txid = chain.broadcast(&tx_hex).await.context("broadcast failed")?;
+        Ok(format!("sweep of puzzle #{} broadcast: {txid}", result.puzzle_number))
+    } else {
+        Ok(format!(
+            "sweep transaction written to {}; broadcast it yourself or set SWEEP_BROADCAST=true",
+            path.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_one_result() -> CheckResult {
+        CheckResult::new(
+            1,
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH".into(),
+            format!("{:064x}", 1),
+            AddressType::Compressed,
+        )
+    }
+
+    fn utxo(value_sat: u64) -> Utxo {
+        Utxo {
+            txid: "e3bf3d07d4b0375638d5f1db5255fe07ba2c4cb067cd81b84ee974b6585fb468".into(),
+            vout: 0,
+            value_sat,
+        }
+    }
+
+    #[test]
+    fn sweep_spends_everything_minus_the_fee() {
+        let result = key_one_result();
+        let tx = build_sweep_tx(
+            &result,
+            &[utxo(100_000)],
+            "1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb",
+            10,
+        )
+        .unwrap();
+        assert_eq!(tx.input.len(), 1);
+        assert_eq!(tx.output.len(), 1);
+        let fee = estimated_vbytes(1, AddressType::Compressed) * 10;
+        assert_eq!(tx.output[0].value.to_sat(), 100_000 - fee);
+        assert!(!tx.input[0].script_sig.is_empty(), "input must be signed");
+    }
+
+    #[test]
+    fn dust_balances_are_refused() {
+        let result = key_one_result();
+        let err = build_sweep_tx(
+            &result,
+            &[utxo(1_000)],
+            "1CUNEBjYrCn2y1SdiUMohaKUi4wpP326Lb",
+            10,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not cover"));
+    }
+}
@@ -0,0 +1,487 @@
+//! Minimal Telegram Bot API client and command dispatcher.
+//!
+//! We talk to the HTTP API directly with `reqwest` rather than pulling in a
+//! full bot framework; the bot only needs `sendMessage` and long-polled
+//! `getUpdates`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::metrics::Metrics;
+use crate::notify::{Event, Notifier};
+use crate::state::AppState;
+
+const API_BASE: &str = "https://api.telegram.org";
+
+/// Transient send failures are retried this many times with linear backoff.
+const SEND_ATTEMPTS: u32 = 3;
+
+/// Rejections from one non-whitelisted chat are logged at most this many
+/// times per [`FLOOD_WINDOW`]; beyond that the chat is dropped silently so
+/// a public bot username cannot be used to bloat the logs.
+const FLOOD_LOG_LIMIT: u32 = 3;
+const FLOOD_WINDOW: Duration = Duration::from_secs(300);
+
+/// Telegram Bot API client bound to one token and one notification chat.
+#[derive(Clone)]
+pub struct TelegramBot {
+    client: reqwest::Client,
+    token: String,
+    /// Chat that receives unsolicited notifications (session reports, solves).
+    pub chat_id: i64,
+    /// Latency/failure accounting for every API call.
+    metrics: Arc<Metrics>,
+    /// Rejection counts per non-whitelisted chat, for flood protection.
+    flood: Arc<Mutex<HashMap<i64, (u32, Instant)>>>,
+}
+
+#[async_trait::async_trait]
+impl Notifier for TelegramBot {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    // `send_message` already retries transient failures internally.
+    async fn send(&self, event: &Event) -> Result<()> {
+        let text = match event {
+            Event::Solve(result) => crate::scheduler::solve_message(result),
+            Event::Alert(text) | Event::Lifecycle(text) => text.clone(),
+            Event::Report { text, .. } => format!("📊 Periodic report\n{text}"),
+        };
+        self.notify(&text).await
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    description: Option<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    from: Option<User>,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: i64,
+}
+
+/// Commands that mutate state or reveal sensitive data; everything else is
+/// available to viewers.
+fn requires_admin(command: &str) -> bool {
+    matches!(
+        command,
+        "/start" | "/stop" | "/focus" | "/window" | "/loglevel" | "/export" | "/solutions"
+            | "/role" | "/audit"
+    )
+}
+
+impl TelegramBot {
+    pub fn new(token: String, chat_id: i64, metrics: Arc<Metrics>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token,
+            chat_id,
+            metrics,
+            flood: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Account one message from a non-whitelisted chat. Returns how many
+    /// arrived in the current window so the caller can stop logging.
+    fn record_rejected(&self, chat_id: i64) -> u32 {
+        self.metrics.telegram_rejected.inc();
+        let mut flood = self.flood.lock().unwrap();
+        let (count, window_start) = flood.entry(chat_id).or_insert((0, Instant::now()));
+        if window_start.elapsed() > FLOOD_WINDOW {
+            *count = 0;
+            *window_start = Instant::now();
+        }
+        *count += 1;
+        *count
+    }
+
+    fn url(&self, method: &str) -> String {
+        format!("{}/bot{}/{}", API_BASE, self.token, method)
+    }
+
+    /// One timed, metered API call.
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        payload: &serde_json::Value,
+        timeout: Option<Duration>,
+    ) -> Result<T> {
+        let timer = self
+            .metrics
+            .telegram_request_seconds
+            .with_label_values(&[method])
+            .start_timer();
+        let result = async {
+            let mut request = self.client.post(self.url(method)).json(payload);
+            if let Some(timeout) = timeout {
+                request = request.timeout(timeout);
+            }
+            let response: ApiResponse<T> = request
+                .send()
+                .await
+                .with_context(|| format!("{method} request failed"))?
+                .json()
+                .await
+                .with_context(|| format!("{method} response was not JSON"))?;
+            if !response.ok {
+                bail!(
+                    "{method} rejected: {}",
+                    response.description.unwrap_or_default()
+                );
+            }
+            response
+                .result
+                .with_context(|| format!("{method} response had no result"))
+        }
+        .await;
+        timer.observe_duration();
+        let outcome = if result.is_ok() { "ok" } else { "error" };
+        self.metrics
+            .telegram_requests
+            .with_label_values(&[method, outcome])
+            .inc();
+        result
+    }
+
+    /// Send a plain-text message to the configured notification chat.
+    pub async fn notify(&self, text: &str) -> Result<()> {
+        self.send_message(self.chat_id, text).await
+    }
+
+    /// Send a plain-text message to an arbitrary chat, retrying transient
+    /// failures with linear backoff.
+    pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<()> {
+        let payload = json!({ "chat_id": chat_id, "text": text });
+        let mut last_err = None;
+        for attempt in 1..=SEND_ATTEMPTS {
+            match self
+                .call::<serde_json::Value>("sendMessage", &payload, None)
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(err) => {
+                    last_err = Some(err);
+                    if attempt < SEND_ATTEMPTS {
+                        self.metrics.telegram_retries.inc();
+                        tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+                    }
+                }
+            }
+        }
+        Err(last_err.expect("at least one attempt was made"))
+    }
+
+    /// Long-poll `getUpdates` and dispatch bot commands until shutdown.
+    pub async fn run_command_loop(&self, state: Arc<AppState>) {
+        let mut offset: i64 = 0;
+        loop {
+            if state.shutdown_requested() {
+                return;
+            }
+            match self.poll_updates(offset).await {
+                Ok(updates) => {
+                    state.set_telegram_ok(true);
+                    for update in updates {
+                        offset = offset.max(update.update_id + 1);
+                        if let Some(message) = update.message {
+                            if let Some(text) = message.text {
+                                let from = message.from.map(|user| user.id);
+                                self.handle_command(&state, message.chat.id, from, text.trim())
+                                    .await;
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    state.set_telegram_ok(false);
+                    tracing::warn!("getUpdates failed: {err:#}");
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                }
+            }
+        }
+    }
+
+    async fn poll_updates(&self, offset: i64) -> Result<Vec<Update>> {
+        self.call(
+            "getUpdates",
+            &json!({ "offset": offset, "timeout": 25 }),
+            Some(Duration::from_secs(35)),
+        )
+        .await
+    }
+
+    async fn handle_command(
+        &self,
+        state: &Arc<AppState>,
+        chat_id: i64,
+        from: Option<i64>,
+        text: &str,
+    ) {
+        if chat_id != self.chat_id {
+            match self.record_rejected(chat_id) {
+                count if count <= FLOOD_LOG_LIMIT => {
+                    tracing::debug!("ignoring message from non-whitelisted chat {chat_id}")
+                }
+                count if count == FLOOD_LOG_LIMIT + 1 => tracing::warn!(
+                    "muting non-whitelisted chat {chat_id} for {}s after {FLOOD_LOG_LIMIT} \
+                     rejections",
+                    FLOOD_WINDOW.as_secs()
+                ),
+                _ => {}
+            }
+            return;
+        }
+        let command = text.split_whitespace().next().unwrap_or("");
+        let role = state.roles.role_of(from);
+        if role != crate::roles::Role::Admin && requires_admin(command) {
+            tracing::info!("denying {command} from user {from:?} with role {role}");
+            let denied = format!("{command} requires the admin role (you are a {role}).");
+            if let Err(err) = self.send_message(chat_id, &denied).await {
+                tracing::warn!("failed to reply to {command}: {err:#}");
+            }
+            return;
+        }
+        let before = format!(
+            "running={} focus={:?}",
+            state.is_running(),
+            state.focused_puzzle()
+        );
+        let reply = match command {
+            "/status" => state.status_text(),
+            "/version" => crate::buildinfo::text(state.uptime_secs()),
+            "/stats" => state.stats_text(),
+            "/config" => state.config_text(),
+            "/start" => {
+                state.set_running(true);
+                "Solver started.".to_string()
+            }
+            "/stop" => {
+                state.set_running(false);
+                "Solver stopped. Sessions will be skipped until /start.".to_string()
+            }
+            "/focus" => match text.split_whitespace().nth(1).and_then(|n| n.parse().ok()) {
+                Some(number) => state.set_focus(Some(number)),
+                None => {
+                    state.set_focus(None);
+                    "Focus cleared; eligible puzzles rotate again.".to_string()
+                }
+            },
+            "/window" => match text.split_whitespace().nth(1).and_then(|n| n.parse::<u64>().ok())
+            {
+                Some(0) => "The window must be at least 1 key.".to_string(),
+                Some(window) => {
+                    state.set_hybrid_window(window);
+                    format!("Hybrid window set to {window} keys; workers re-anchor with it next.")
+                }
+                None => format!(
+                    "Hybrid window: {} keys.\nUsage: /window <keys>",
+                    state.hybrid_window()
+                ),
+            },
+            "/loglevel" => match text.split_whitespace().nth(1) {
+                Some(directives) => match crate::logging::set_filter(directives) {
+                    Ok(()) => format!("Log filter set to {directives} until restart."),
+                    Err(err) => format!("Failed to set log filter: {err:#}"),
+                },
+                None => concat!(
+                    "Usage: /loglevel <directives>\n",
+                    "Examples: /loglevel debug or ",
+                    "/loglevel info,btc_lotto_puzzles_bot::checker=debug"
+                )
+                .to_string(),
+            },
+            "/export" => {
+                let path = state.config.data_dir.join(format!(
+                    "snapshot-{}.json",
+                    chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+                ));
+                match crate::snapshot::export(state, &path) {
+                    Ok(_) => format!(
+                        "State snapshot written to {} on the host. Restore elsewhere with SNAPSHOT_IMPORT.",
+                        path.display()
+                    ),
+                    Err(err) => format!("Snapshot export failed: {err:#}"),
+                }
+            }
+            "/price" => match &state.price {
+                Some(price) => match price.btc_price().await {
+                    Ok(value) => {
+                        let mut reply = format!("BTC = {value:.2} {}", price.fiat());
+                        let reward = state
+                            .focused_puzzle()
+                            .or_else(|| state.active_puzzle())
+                            .and_then(|n| state.puzzles().get(n).map(|p| (n, p.reward_btc)));
+                        if let Some((number, reward_btc)) = reward {
+                            reply.push_str(&format!(
+                                "\nPuzzle #{number} reward: {reward_btc} BTC ≈ {:.2} {}",
+                                reward_btc * value,
+                                price.fiat(),
+                            ));
+                        }
+                        reply
+                    }
+                    Err(err) => format!("Price lookup failed: {err:#}"),
+                },
+                None => "Price lookups disabled (PRICE_PROVIDERS=none).".to_string(),
+            },
+            "/balance" => match text.split_whitespace().nth(1).and_then(|n| n.parse::<u32>().ok())
+            {
+                Some(number) => {
+                    let address = state.puzzles().get(number).map(|p| p.address.clone());
+                    match (address, &state.chain) {
+                        (None, _) => format!("Unknown puzzle #{number}."),
+                        (_, None) => {
+                            "No chain backend configured; set CHAIN_BACKEND.".to_string()
+                        }
+                        (Some(address), Some(chain)) => match chain.address_info(&address).await {
+                            Ok(info) => {
+                                let mut reply = format!(
+                                    "Puzzle #{number} ({address})\nBalance: {:.8} BTC\nTransactions: {}",
+                                    info.balance_sat() as f64 / 1e8,
+                                    info.tx_count,
+                                );
+                                if let Ok(txids) = chain.tx_history(&address).await {
+                                    if let Some(txid) = txids.first() {
+                                        reply.push_str(&format!("\nLatest tx: {txid}"));
+                                    }
+                                }
+                                reply
+                            }
+                            Err(err) => format!("Balance lookup failed: {err:#}"),
+                        },
+                    }
+                }
+                None => "Usage: /balance <puzzle>".to_string(),
+            },
+            "/solutions" => match state.solutions.read_all() {
+                Ok(entries) if entries.is_empty() => "No solutions stored yet.".to_string(),
+                Ok(entries) => format!(
+                    "{} solution(s) stored; keys are kept {} on disk.",
+                    entries.len(),
+                    if std::env::var("SOLUTIONS_PASSPHRASE").is_ok()
+                        || std::env::var("SOLUTIONS_PASSPHRASE_FILE").is_ok()
+                    {
+                        "encrypted"
+                    } else {
+                        "in PLAINTEXT"
+                    }
+                ),
+                Err(err) => format!("Failed to read solutions store: {err:#}"),
+            },
+            "/role" => {
+                let mut args = text.split_whitespace().skip(1);
+                match (
+                    args.next().and_then(|id| id.parse::<i64>().ok()),
+                    args.next().and_then(|role| role.parse::<crate::roles::Role>().ok()),
+                ) {
+                    (Some(user_id), Some(role)) => match state.roles.assign(user_id, role) {
+                        Ok(()) => format!("User {user_id} is now a {role}."),
+                        Err(err) => format!("Failed to persist role: {err:#}"),
+                    },
+                    _ => {
+                        let lines = state.roles.lines();
+                        if lines.is_empty() {
+                            concat!(
+                                "No roles assigned; every whitelisted user is an admin.\n",
+                                "Usage: /role <user id> <admin|viewer>"
+                            )
+                            .to_string()
+                        } else {
+                            format!(
+                                "Assigned roles (unlisted users are viewers):\n{}\n\
+                                 Usage: /role <user id> <admin|viewer>",
+                                lines.join("\n")
+                            )
+                        }
+                    }
+                }
+            }
+            "/audit" => match state.audit.recent(10) {
+                Ok(entries) if entries.is_empty() => "Audit log is empty.".to_string(),
+                Ok(entries) => format!("Recent control actions:\n{}", entries.join("\n")),
+                Err(err) => format!("Audit log problem: {err:#}"),
+            },
+            "/help" => concat!(
+                "Commands:\n",
+                "/status - scheduler status\n",
+                "/stats - search statistics\n",
+                "/config - active configuration\n",
+                "/start, /stop - control solving sessions\n",
+                "/focus <n> - restrict search to puzzle n (no arg clears)\n",
+                "/window <keys> - hybrid strategy window size (no arg shows)\n",
+                "/price - BTC spot price and current reward value\n",
+                "/balance <n> - on-chain balance of puzzle n's address\n",
+                "/solutions - number of stored solutions\n",
+                "/export - write a state snapshot archive\n",
+                "/version - build and uptime information\n",
+                "/loglevel <directives> - change the log filter at runtime\n",
+                "/role <id> <admin|viewer> - assign a user role\n",
+                "/audit - recent control actions from the audit log\n",
+            )
+            .to_string(),
+            _ => return,
+        };
+        // Admin commands leave a hash-chained trail with the acting user
+        // and the observable state around the change.
+        if requires_admin(command) {
+            let actor = from.map_or_else(|| "unknown".to_string(), |id| id.to_string());
+            let after = format!(
+                "running={} focus={:?}",
+                state.is_running(),
+                state.focused_puzzle()
+            );
+            if let Err(err) = state
+                .audit
+                .record(&actor, command, &format!("{text} [{before} -> {after}]"))
+            {
+                tracing::warn!("failed to record audit entry: {err:#}");
+            }
+        }
+        if let Err(err) = self.send_message(chat_id, &reply).await {
+            state.metrics.record_error(crate::metrics::ErrorKind::Telegram);
+            tracing::warn!("failed to reply to {command}: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejections_count_per_chat_and_feed_the_metric() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let bot = TelegramBot::new("token".into(), 1, Arc::clone(&metrics));
+        assert_eq!(bot.record_rejected(42), 1);
+        assert_eq!(bot.record_rejected(42), 2);
+        assert_eq!(bot.record_rejected(43), 1);
+        assert_eq!(metrics.telegram_rejected.get(), 3);
+    }
+}
@@ -0,0 +1,201 @@
+//! Minimal stack-allocated 256-bit unsigned integer for the key hot path.
+//!
+//! `BigUint` heap-allocates on every arithmetic operation, which is real
+//! overhead when the generators hand out millions of candidate keys per
+//! second. This type covers exactly what they need — add, subtract,
+//! compare, uniform sampling — on four u64 limbs with no allocation.
+//! Parsing and other cold paths stay on `BigUint` and convert at the
+//! boundary.
+
+use num_bigint::BigUint;
+
+/// 256-bit unsigned integer; limbs are little-endian (`limbs[0]` least
+/// significant).
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    pub const ZERO: Self = Self([0; 4]);
+    pub const ONE: Self = Self([1, 0, 0, 0]);
+
+    pub const fn from_u64(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+
+    /// Construct from little-endian limbs, for compile-time constants.
+    pub const fn from_limbs(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    /// Exact conversion; `None` when the value needs more than 256 bits.
+    pub fn from_biguint(value: &BigUint) -> Option<Self> {
+        if value.bits() > 256 {
+            return None;
+        }
+        let mut limbs = [0u64; 4];
+        for (limb, digit) in limbs.iter_mut().zip(value.iter_u64_digits()) {
+            *limb = digit;
+        }
+        Some(Self(limbs))
+    }
+
+    pub fn to_biguint(self) -> BigUint {
+        BigUint::from_bytes_be(&self.to_be_bytes())
+    }
+
+    /// Big-endian bytes, the secp256k1 secret-key wire format.
+    pub fn to_be_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().rev().enumerate() {
+            bytes[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    /// Position of the highest set bit plus one; 0 for zero.
+    pub fn bits(&self) -> u32 {
+        for (i, limb) in self.0.iter().enumerate().rev() {
+            if *limb != 0 {
+                return i as u32 * 64 + 64 - limb.leading_zeros();
+            }
+        }
+        0
+    }
+
+    /// The least significant limb.
+    pub fn low_u64(&self) -> u64 {
+        self.0[0]
+    }
+
+    /// Addition; `None` on 256-bit overflow.
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        let mut limbs = [0u64; 4];
+        let mut carry = false;
+        for (limb, (a, b)) in limbs.iter_mut().zip(self.0.iter().zip(&rhs.0)) {
+            let (sum, c1) = a.overflowing_add(*b);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (!carry).then_some(Self(limbs))
+    }
+
+    /// Subtraction; `None` when `rhs` is larger.
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        let mut limbs = [0u64; 4];
+        let mut borrow = false;
+        for (limb, (a, b)) in limbs.iter_mut().zip(self.0.iter().zip(&rhs.0)) {
+            let (diff, b1) = a.overflowing_sub(*b);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        (!borrow).then_some(Self(limbs))
+    }
+
+    /// A uniformly random value, all 256 bits drawn from the RNG.
+    pub fn random<R: rand::Rng>(rng: &mut R) -> Self {
+        Self([rng.gen(), rng.gen(), rng.gen(), rng.gen()])
+    }
+
+    /// Uniform sample in `[0, bound)` by rejection: draw only the limbs
+    /// the bound occupies, mask the top one to its bit length, retry on
+    /// overshoot (at most one expected retry).
+    pub fn random_below<R: rand::Rng>(rng: &mut R, bound: &Self) -> Self {
+        debug_assert!(!bound.is_zero());
+        let bits = bound.bits();
+        let limbs = bits.div_ceil(64) as usize;
+        let top_mask = match bits % 64 {
+            0 => u64::MAX,
+            partial => (1u64 << partial) - 1,
+        };
+        loop {
+            let mut value = [0u64; 4];
+            for limb in value.iter_mut().take(limbs) {
+                *limb = rng.gen();
+            }
+            value[limbs - 1] &= top_mask;
+            let value = Self(value);
+            if value < *bound {
+                return value;
+            }
+        }
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Most significant limb decides first.
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Debug for U256 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x}", self.to_biguint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::RandBigInt;
+
+    #[test]
+    fn arithmetic_matches_biguint() {
+        let mut rng = rand::thread_rng();
+        let limit = BigUint::from(1u32) << 256;
+        for _ in 0..200 {
+            let a = rng.gen_biguint_below(&limit);
+            let b = rng.gen_biguint_below(&limit);
+            let (ua, ub) = (
+                U256::from_biguint(&a).unwrap(),
+                U256::from_biguint(&b).unwrap(),
+            );
+            assert_eq!(ua.cmp(&ub), a.cmp(&b));
+            match ua.checked_add(&ub) {
+                Some(sum) => assert_eq!(sum.to_biguint(), &a + &b),
+                None => assert!(&a + &b >= limit),
+            }
+            match ua.checked_sub(&ub) {
+                Some(diff) => assert_eq!(diff.to_biguint(), &a - &b),
+                None => assert!(a < b),
+            }
+            assert_eq!(u64::from(ua.bits()), a.bits());
+        }
+        assert!(U256::from_biguint(&limit).is_none(), "257-bit value");
+    }
+
+    #[test]
+    fn byte_round_trip_is_big_endian() {
+        let value = BigUint::parse_bytes(b"0123456789abcdef00112233", 16).unwrap();
+        let u = U256::from_biguint(&value).unwrap();
+        assert_eq!(BigUint::from_bytes_be(&u.to_be_bytes()), value);
+        assert_eq!(u.low_u64(), 0x89ab_cdef_0011_2233);
+        assert_eq!(U256::from_u64(7).to_biguint(), BigUint::from(7u32));
+    }
+
+    #[test]
+    fn random_below_respects_the_bound() {
+        let mut rng = rand::thread_rng();
+        let bound = U256::from_biguint(&BigUint::from(1000u32)).unwrap();
+        for _ in 0..500 {
+            assert!(U256::random_below(&mut rng, &bound) < bound);
+        }
+        // An awkward bound straddling a limb boundary.
+        let bound = U256::from_biguint(&(BigUint::from(1u32) << 64)).unwrap();
+        for _ in 0..100 {
+            assert!(U256::random_below(&mut rng, &bound) < bound);
+        }
+    }
+}
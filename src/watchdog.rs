@@ -0,0 +1,117 @@
+//! Throughput watchdog.
+//!
+//! Watches the rolling key rate the scheduler already computes at each stats
+//! interval and raises an alert when it stays below a configurable fraction
+//! of the best rate seen so far — the usual symptoms are thermal throttling
+//! or a noisy neighbor stealing cores. A recovery notice is sent once the
+//! rate climbs back, and the alert does not repeat while the drop persists.
+
+use crate::config::Config;
+
+/// Tracks the throughput baseline and the current alert state.
+///
+/// One observation is fed in per stats interval; "sustained" is therefore
+/// measured in consecutive observations rather than wall-clock time.
+pub struct Watchdog {
+    /// Alert when the rate drops below this fraction of the baseline.
+    fraction: f64,
+    /// Consecutive low observations required before alerting.
+    sustain_obs: u32,
+    /// Best rate observed so far (keys/s).
+    baseline: u64,
+    /// Consecutive observations below the threshold.
+    low_streak: u32,
+    /// Whether an alert is currently outstanding.
+    alerted: bool,
+}
+
+impl Watchdog {
+    pub fn from_config(config: &Config) -> Self {
+        let stats_interval = config.scheduler.stats_interval_secs.max(1);
+        let sustain_obs = (config.watchdog_sustain_secs / stats_interval).max(1) as u32;
+        Self {
+            fraction: config.watchdog_fraction,
+            sustain_obs,
+            baseline: 0,
+            low_streak: 0,
+            alerted: false,
+        }
+    }
+
+    /// Feed one rolling-rate observation; returns an alert or recovery
+    /// message when the state changes.
+    pub fn observe(&mut self, rate: u64) -> Option<String> {
+        if self.fraction <= 0.0 {
+            return None;
+        }
+        let threshold = (self.baseline as f64 * self.fraction) as u64;
+        if self.baseline > 0 && rate < threshold {
+            self.low_streak += 1;
+            if self.low_streak >= self.sustain_obs && !self.alerted {
+                self.alerted = true;
+                return Some(format!(
+                    "⚠️ Throughput dropped: {rate} keys/s, below {:.0}% of the {} keys/s baseline \
+                     for {} consecutive report(s). Check for thermal throttling or CPU contention.",
+                    self.fraction * 100.0,
+                    self.baseline,
+                    self.low_streak,
+                ));
+            }
+            return None;
+        }
+        self.low_streak = 0;
+        self.baseline = self.baseline.max(rate);
+        if self.alerted {
+            self.alerted = false;
+            return Some(format!(
+                "✅ Throughput recovered: {rate} keys/s (baseline {} keys/s).",
+                self.baseline
+            ));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watchdog(fraction: f64, sustain_obs: u32) -> Watchdog {
+        Watchdog {
+            fraction,
+            sustain_obs,
+            baseline: 0,
+            low_streak: 0,
+            alerted: false,
+        }
+    }
+
+    #[test]
+    fn alerts_after_sustained_drop_then_recovers() {
+        let mut wd = watchdog(0.5, 2);
+        assert!(wd.observe(1000).is_none()); // establishes the baseline
+        assert!(wd.observe(100).is_none()); // first low observation
+        let alert = wd.observe(100).expect("second low observation alerts");
+        assert!(alert.contains("Throughput dropped"));
+        assert!(wd.observe(100).is_none()); // no repeat while still low
+        let recovery = wd.observe(900).expect("recovery notice");
+        assert!(recovery.contains("recovered"));
+    }
+
+    #[test]
+    fn brief_dips_do_not_alert() {
+        let mut wd = watchdog(0.5, 3);
+        assert!(wd.observe(1000).is_none());
+        assert!(wd.observe(100).is_none());
+        assert!(wd.observe(100).is_none());
+        assert!(wd.observe(1000).is_none()); // dip ended before sustain
+        assert!(wd.observe(100).is_none()); // streak restarted from zero
+    }
+
+    #[test]
+    fn zero_fraction_disables() {
+        let mut wd = watchdog(0.0, 1);
+        assert!(wd.observe(1000).is_none());
+        assert!(wd.observe(0).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+//! Generic outgoing webhook sink.
+//!
+//! POSTs a JSON payload for solver events to `WEBHOOK_URL`, letting users
+//! wire up automation we don't want to ship (Discord relays, home dashboards,
+//! SMS gateways). With `WEBHOOK_SECRET` set, every request carries an
+//! `X-Btclotto-Signature: sha256=<hex>` HMAC of the body so the receiver can
+//! authenticate it. Solve events omit the private key unless
+//! `WEBHOOK_INCLUDE_KEY=true` — most receivers only need to know *that* a
+//! puzzle fell, and webhook endpoints are a bad place for key material.
+
+use anyhow::{Context, Result};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::checker::CheckResult;
+use crate::notify::{Event, Notifier};
+
+/// A configured webhook endpoint.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    secret: Option<String>,
+    include_key: bool,
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, as sent in the signature header.
+fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+impl WebhookNotifier {
+    /// Build the notifier from the environment; `None` when `WEBHOOK_URL` is
+    /// unset.
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("WEBHOOK_URL").ok()?;
+        Some(Self {
+            client: reqwest::Client::new(),
+            url,
+            secret: std::env::var("WEBHOOK_SECRET").ok(),
+            include_key: std::env::var("WEBHOOK_INCLUDE_KEY").is_ok_and(|v| v == "true"),
+        })
+    }
+
+    /// POST one event, signing the body when a secret is configured.
+    async fn post(&self, event: &str, data: serde_json::Value) -> Result<()> {
+        let body = serde_json::to_vec(&json!({
+            "event": event,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "data": data,
+        }))
+        .context("encoding webhook payload")?;
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        if let Some(secret) = &self.secret {
+            request = request.header(
+                "X-Btclotto-Signature",
+                format!("sha256={}", signature(secret, &body)),
+            );
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .context("webhook request failed")?;
+        response
+            .error_for_status()
+            .context("webhook endpoint rejected the event")?;
+        Ok(())
+    }
+
+    /// Announce a solve. The private key stays out of the payload unless
+    /// explicitly enabled.
+    async fn notify_solve(&self, result: &CheckResult) -> Result<()> {
+        let mut data = json!({
+            "puzzle_number": result.puzzle_number,
+            "address": result.address,
+            "address_type": result.address_type,
+        });
+        if self.include_key {
+            data["private_key_hex"] = json!(result.reveal_private_key());
+        }
+        self.post("solve", data).await
+    }
+
+}
+
+#[async_trait::async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn retry_attempts(&self) -> u32 {
+        3
+    }
+
+    async fn send(&self, event: &Event) -> Result<()> {
+        match event {
+            Event::Solve(result) => self.notify_solve(result).await,
+            Event::Alert(text) => self.post("alert", json!({ "message": text })).await,
+            Event::Report {
+                keys_checked,
+                rate,
+                matches_found,
+                sessions_run,
+                uptime_secs,
+                ..
+            } => {
+                self.post(
+                    "stats",
+                    json!({
+                        "keys_checked": keys_checked,
+                        "rate_keys_per_sec": rate,
+                        "matches_found": matches_found,
+                        "sessions_run": sessions_run,
+                        "uptime_secs": uptime_secs,
+                    }),
+                )
+                .await
+            }
+            // Process lifecycle is noise for automation endpoints.
+            Event::Lifecycle(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_matches_known_vector() {
+        // RFC 4231 test case 2: key "Jefe", data "what do ya want for nothing?".
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
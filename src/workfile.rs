@@ -0,0 +1,79 @@
+//! Range work files in the keyhunt/BitCrack text format.
+//!
+//! One inclusive `start:end` hex pair per line — the same syntax keyhunt's
+//! `-r` option and BitCrack's `--keyspace` take, so files can be handed
+//! back and forth between this bot and those tools in a mixed fleet.
+//! Blank lines and `#` comments are ignored on read; exports group ranges
+//! under `# puzzle N` comments, which the other tools skip.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+use num_traits::Num;
+
+/// Parse one `start:end` line.
+fn parse_line(line: &str) -> Result<(BigUint, BigUint)> {
+    let (start, end) = line
+        .split_once(':')
+        .with_context(|| format!("expected start:end hex pair, got {line:?}"))?;
+    let parse = |bound: &str, name: &str| {
+        BigUint::from_str_radix(bound.trim().trim_start_matches("0x"), 16)
+            .with_context(|| format!("bad {name} in {line:?}"))
+    };
+    let (start, end) = (parse(start, "range start")?, parse(end, "range end")?);
+    anyhow::ensure!(start <= end, "inverted range {line:?}");
+    Ok((start, end))
+}
+
+/// Read every range from a work file.
+pub fn read_ranges(path: &Path) -> Result<Vec<(BigUint, BigUint)>> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("reading work file {}", path.display()))?;
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(parse_line)
+        .collect()
+}
+
+/// Write `(puzzle, start, end)` ranges as a work file.
+pub fn write_ranges(path: &Path, ranges: &[(u32, BigUint, BigUint)]) -> Result<()> {
+    let mut out = String::from("# unsearched ranges, start:end per line (keyhunt/BitCrack syntax)\n");
+    let mut current_puzzle = None;
+    for (puzzle, start, end) in ranges {
+        if current_puzzle != Some(*puzzle) {
+            out.push_str(&format!("# puzzle {puzzle}\n"));
+            current_puzzle = Some(*puzzle);
+        }
+        out.push_str(&format!("{start:x}:{end:x}\n"));
+    }
+    std::fs::write(path, out).with_context(|| format!("writing work file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_text_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("work.txt");
+        let ranges = vec![
+            (66, BigUint::from(0x80u32), BigUint::from(0xbfu32)),
+            (66, BigUint::from(0xc0u32), BigUint::from(0xffu32)),
+        ];
+        write_ranges(&path, &ranges).unwrap();
+        let read = read_ranges(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0], (BigUint::from(0x80u32), BigUint::from(0xbfu32)));
+        assert_eq!(read[1].1, BigUint::from(0xffu32));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse_line("deadbeef").is_err());
+        assert!(parse_line("ff:80").is_err());
+        assert!(parse_line("0x80:0xff").is_ok());
+    }
+}